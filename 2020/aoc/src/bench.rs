@@ -0,0 +1,91 @@
+use std::{cmp::Reverse, time::Duration};
+
+use common::{
+    report::{Align, MarkdownTable},
+    timing::Summary,
+};
+
+// How long one day took to answer both parts, parsing included, and under
+// which allocator, since timings only compare between builds with the same one
+pub struct DayTiming {
+    pub year: u16,
+    pub day: u8,
+    pub allocator: &'static str,
+    pub summary: Summary,
+}
+
+fn format_share(part: Duration, total: Duration) -> String {
+    if total.is_zero() {
+        return String::from("-");
+    }
+    format!("{:.1}%", 100.0 * part.as_secs_f64() / total.as_secs_f64())
+}
+
+// Slowest days first, by mean, then a total of the means and the minimums
+pub fn tabulate(mut timings: Vec<DayTiming>) -> MarkdownTable {
+    timings.sort_by_key(|timing| Reverse(timing.summary.mean));
+    let total_mean: Duration = timings.iter().map(|timing| timing.summary.mean).sum();
+    let total_min: Duration = timings.iter().map(|timing| timing.summary.min).sum();
+
+    let mut table = MarkdownTable::new(&["Day", "Allocator", "Mean", "Min", "Stddev", "Share"])
+        .align(2, Align::Right)
+        .align(3, Align::Right)
+        .align(4, Align::Right)
+        .align(5, Align::Right);
+    for timing in &timings {
+        table
+            .row()
+            .cell(format!("{} day {:02}", timing.year, timing.day))
+            .cell(timing.allocator)
+            .cell(format!("{:.2?}", timing.summary.mean))
+            .cell(format!("{:.2?}", timing.summary.min))
+            .cell(format!("{:.2?}", timing.summary.stddev))
+            .cell(format_share(timing.summary.mean, total_mean))
+            .finish();
+    }
+    table
+        .row()
+        .cell(format!("Total ({} days)", timings.len()))
+        .cell("")
+        .cell(format!("{total_mean:.2?}"))
+        .cell(format!("{total_min:.2?}"))
+        .cell("")
+        .cell(format_share(total_mean, total_mean))
+        .finish();
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timing(day: u8, millis: u64) -> DayTiming {
+        DayTiming {
+            year: 2020,
+            day,
+            allocator: "system",
+            summary: Summary {
+                runs: 1,
+                min: Duration::from_millis(millis),
+                mean: Duration::from_millis(millis),
+                stddev: Duration::ZERO,
+            },
+        }
+    }
+
+    #[test]
+    fn slowest_days_come_first() {
+        let table = tabulate(vec![timing(1, 1), timing(15, 600), timing(11, 399)]);
+        assert_eq!(
+            table.to_string(),
+            "\
+| Day            | Allocator |     Mean |      Min | Stddev |  Share |
+| :------------- | :-------- | -------: | -------: | -----: | -----: |
+| 2020 day 15    | system    | 600.00ms | 600.00ms | 0.00ns |  60.0% |
+| 2020 day 11    | system    | 399.00ms | 399.00ms | 0.00ns |  39.9% |
+| 2020 day 01    | system    |   1.00ms |   1.00ms | 0.00ns |   0.1% |
+| Total (3 days) |           |    1.00s |    1.00s |        | 100.0% |
+"
+        );
+    }
+}
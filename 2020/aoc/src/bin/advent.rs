@@ -0,0 +1,55 @@
+#![deny(clippy::all, clippy::pedantic)]
+
+use std::{env, path::Path, process};
+
+use aoc::days;
+use clap::{value_t, App, Arg};
+use common::{cli::Part, input};
+
+fn main() {
+    // Linked under a puzzle's name, like busybox, the binary solves just that
+    // one. Otherwise the puzzle is the first argument.
+    let linked = env::args_os()
+        .next()
+        .and_then(|binary| days::parse_puzzle(Path::new(&binary).file_stem()?.to_str()?));
+
+    let mut app = App::new("advent").about("Solves any day, picked by YEAR-DAY such as 2020-24");
+    if linked.is_none() {
+        app = app.arg(Arg::from_usage("<PUZZLE> 'Year and day, such as 2020-24'"));
+    }
+    let args = app
+        .arg(Arg::from_usage("<FILE> 'Puzzle input, or - for stdin'"))
+        .arg(
+            Arg::from_usage("--part [PART] 'Which part to answer'")
+                .possible_values(&Part::NAMES)
+                .default_value("both"),
+        )
+        .get_matches();
+    let part = value_t!(args, "part", Part).unwrap_or_else(|error| error.exit());
+
+    let (year, day) = linked.unwrap_or_else(|| {
+        let puzzle = args.value_of("PUZZLE").unwrap();
+        days::parse_puzzle(puzzle).unwrap_or_else(|| {
+            eprintln!("Failed to parse puzzle {puzzle}, expected YEAR-DAY such as 2020-24");
+            process::exit(1);
+        })
+    });
+    let registry = days::registry();
+    let solution = days::find(&registry, year, day).unwrap_or_else(|error| {
+        eprintln!("{error}");
+        process::exit(1);
+    });
+
+    let text = input::load(args.value_of("FILE").unwrap());
+    let mut answers = Vec::new();
+    if part != Part::Two {
+        answers.push((1, solution.part1(&text)));
+    }
+    if part != Part::One {
+        answers.push((2, solution.part2(&text)));
+    }
+    // The last day has no second part, and so no answer for it
+    for (number, answer) in answers.iter().filter(|(_, answer)| !answer.is_empty()) {
+        println!("Part {number}: {answer}");
+    }
+}
@@ -0,0 +1,150 @@
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+
+use common::{solution::Registry, Solution};
+
+// Every day with a solution that this build includes, in order. Each one has a
+// y2020-dNN feature, and all-2020 turns them all on.
+const SOLUTIONS: &[(u8, &dyn Solution)] = &[
+    #[cfg(feature = "y2020-d01")]
+    (1, &day_01::Solver),
+    #[cfg(feature = "y2020-d02")]
+    (2, &day_02::Solver),
+    #[cfg(feature = "y2020-d03")]
+    (3, &day_03::Solver),
+    #[cfg(feature = "y2020-d04")]
+    (4, &day_04::Solver),
+    #[cfg(feature = "y2020-d05")]
+    (5, &day_05::Solver),
+    #[cfg(feature = "y2020-d06")]
+    (6, &day_06::Solver),
+    #[cfg(feature = "y2020-d07")]
+    (7, &day_07::Solver),
+    #[cfg(feature = "y2020-d08")]
+    (8, &day_08::Solver),
+    #[cfg(feature = "y2020-d09")]
+    (9, &day_09::Solver),
+    #[cfg(feature = "y2020-d10")]
+    (10, &day_10::Solver),
+    #[cfg(feature = "y2020-d11")]
+    (11, &day_11::Solver),
+    #[cfg(feature = "y2020-d12")]
+    (12, &day_12::Solver),
+    #[cfg(feature = "y2020-d13")]
+    (13, &day_13::Solver),
+    #[cfg(feature = "y2020-d14")]
+    (14, &day_14::Solver),
+    #[cfg(feature = "y2020-d15")]
+    (15, &day_15::Solver),
+    #[cfg(feature = "y2020-d16")]
+    (16, &day_16::Solver),
+    #[cfg(feature = "y2020-d17")]
+    (17, &day_17::Solver),
+    #[cfg(feature = "y2020-d18")]
+    (18, &day_18::Solver),
+    #[cfg(feature = "y2020-d19")]
+    (19, &day_19::Solver),
+    #[cfg(feature = "y2020-d20")]
+    (20, &day_20::Solver),
+    #[cfg(feature = "y2020-d21")]
+    (21, &day_21::Solver),
+    #[cfg(feature = "y2020-d22")]
+    (22, &day_22::Solver),
+    #[cfg(feature = "y2020-d23")]
+    (23, &day_23::Solver),
+    #[cfg(feature = "y2020-d24")]
+    (24, &day_24::Solver),
+    #[cfg(feature = "y2020-d25")]
+    (25, &day_25::Solver),
+];
+
+#[must_use]
+pub fn registry() -> Registry {
+    let mut registry = Registry::new();
+    for &(day, solution) in SOLUTIONS {
+        registry.register(2020, day, solution);
+    }
+    registry
+}
+
+// Where a day's binary and input live
+#[must_use]
+pub fn crate_name(day: u8) -> String {
+    format!("day-{day:02}")
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnknownDay {
+    pub year: u16,
+    pub day: u8,
+}
+
+impl Display for UnknownDay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "no solver for {} day {}", self.year, self.day)
+    }
+}
+
+impl Error for UnknownDay {}
+
+pub fn find(registry: &Registry, year: u16, day: u8) -> Result<&'static dyn Solution, UnknownDay> {
+    registry.get(year, day).ok_or(UnknownDay { year, day })
+}
+
+// Reads a puzzle named the way advent takes it, such as 2020-24
+#[must_use]
+pub fn parse_puzzle(text: &str) -> Option<(u16, u8)> {
+    let (year, day) = text.split_once('-')?;
+    Some((year.parse().ok()?, day.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_every_included_day() {
+        let registry = registry();
+        assert_eq!(registry.len(), SOLUTIONS.len());
+        for day in 1..=25 {
+            let included = SOLUTIONS.iter().any(|(included, _)| *included == day);
+            assert_eq!(find(&registry, 2020, day).is_ok(), included);
+        }
+        assert_eq!(
+            find(&registry, 2017, 11).err().unwrap().to_string(),
+            "no solver for 2017 day 11"
+        );
+        assert_eq!(crate_name(7), "day-07");
+        assert_eq!(parse_puzzle("2020-24"), Some((2020, 24)));
+        assert_eq!(parse_puzzle("2020-07"), Some((2020, 7)));
+        assert_eq!(parse_puzzle("day-24"), None);
+        assert_eq!(parse_puzzle("advent"), None);
+    }
+
+    #[test]
+    #[cfg(feature = "all-2020")]
+    fn finds_every_day() {
+        assert_eq!(registry().len(), 25);
+    }
+
+    #[test]
+    #[cfg(all(feature = "y2020-d01", feature = "y2020-d25"))]
+    fn solutions_answer_from_text() {
+        let registry = registry();
+        let answer = |day, input| {
+            let solution = find(&registry, 2020, day).unwrap();
+            (solution.part1(input), solution.part2(input))
+        };
+
+        assert_eq!(
+            answer(1, "1721\n979\n366\n299\n675\n1456\n"),
+            (String::from("514579"), String::from("241861950"))
+        );
+        assert_eq!(
+            answer(25, "5764801\r\n17807724\n"),
+            (String::from("14897079"), String::new())
+        );
+    }
+}
@@ -0,0 +1,390 @@
+#[cfg(feature = "y2020-d24")]
+use std::convert::TryFrom;
+use std::{
+    fmt::{self, Display},
+    panic::{self, AssertUnwindSafe},
+};
+
+use common::gen::Rng;
+#[cfg(feature = "y2020-d11")]
+use day_11::{tiled::TiledLayout, Layout, Rule};
+#[cfg(feature = "y2020-d24")]
+use day_24::HexLife;
+
+// One way of solving a day, from input text to an answer the other engines
+// should match exactly
+pub struct Engine {
+    pub name: &'static str,
+    pub run: fn(&str) -> String,
+}
+
+// Random inputs for a day, the engines that have to agree on them, and the
+// smaller inputs to try in place of one they disagree on
+pub struct Target {
+    pub generate: fn(&mut Rng) -> String,
+    pub engines: &'static [Engine],
+    pub shrink: fn(&str) -> Vec<String>,
+}
+
+// Layouts are small enough to settle quickly, but still have to be able to
+// cycle forever under some rules
+#[cfg(feature = "y2020-d11")]
+const MAX_GENERATIONS: usize = 10_000;
+
+#[cfg(feature = "y2020-d11")]
+fn settle_layout(text: &str, engine: day_11::Engine) -> String {
+    let mut layout = Layout::new();
+    for line in text.lines() {
+        if let Err(error) = layout.add_line(line) {
+            return error.to_string();
+        }
+    }
+    layout.finalize(Rule::new(false));
+    layout.set_max_generations(Some(MAX_GENERATIONS));
+    day_11::solve(layout, engine).to_string()
+}
+
+// Tiles of a single row each, so every row is next to a halo
+#[cfg(feature = "y2020-d11")]
+fn settle_tiled(text: &str, jobs: usize) -> String {
+    match TiledLayout::from_rows(text.lines(), 1) {
+        Ok(layout) => {
+            day_11::tiled::settle(layout, Rule::new(false), jobs, Some(MAX_GENERATIONS), None)
+                .to_string()
+        }
+        Err(error) => error.to_string(),
+    }
+}
+
+// The GPU engine needs an adapter, so it's only compared when aoc is built with
+// y2020-d11-gpu
+#[cfg(feature = "y2020-d11")]
+const DAY_11_ENGINES: &[Engine] = &[
+    Engine {
+        name: "layout",
+        run: |text| settle_layout(text, day_11::Engine::Cpu),
+    },
+    Engine {
+        name: "tiled",
+        run: |text| settle_tiled(text, 1),
+    },
+    Engine {
+        name: "tiled (3 jobs)",
+        run: |text| settle_tiled(text, 3),
+    },
+    #[cfg(feature = "y2020-d11-gpu")]
+    Engine {
+        name: "gpu",
+        run: |text| settle_layout(text, day_11::Engine::Gpu),
+    },
+];
+
+// Up to 12 by 12, mostly seats, with the odd seat already occupied
+#[cfg(feature = "y2020-d11")]
+fn generate_layout(rng: &mut Rng) -> String {
+    let rows = 1 + rng.below(12);
+    let columns = 1 + rng.below(12);
+    let seat_density = 0.3 + 0.6 * rng.next_f64();
+    let mut text = String::new();
+    for _ in 0..rows {
+        for _ in 0..columns {
+            text.push(if !rng.chance(seat_density) {
+                '.'
+            } else if rng.chance(0.1) {
+                '#'
+            } else {
+                'L'
+            });
+        }
+        text.push('\n');
+    }
+    text
+}
+
+// Inputs keep the newline at the end of their last line
+#[cfg(any(feature = "y2020-d11", feature = "y2020-d24"))]
+fn join_lines<S: AsRef<str>>(lines: &[S]) -> String {
+    lines
+        .iter()
+        .flat_map(|line| [line.as_ref(), "\n"])
+        .collect()
+}
+
+// Drops a row, drops a column, or turns a single seat into floor
+#[cfg(feature = "y2020-d11")]
+fn shrink_layout(text: &str) -> Vec<String> {
+    let rows: Vec<&str> = text.lines().collect();
+    let columns = rows.first().map_or(0, |row| row.len());
+
+    let mut candidates = Vec::new();
+    for skipped in 0..rows.len() {
+        let kept: Vec<&str> = rows
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != skipped)
+            .map(|(_, row)| *row)
+            .collect();
+        candidates.push(join_lines(&kept));
+    }
+    for skipped in 0..columns {
+        let kept: Vec<String> = rows
+            .iter()
+            .map(|row| {
+                row.chars()
+                    .enumerate()
+                    .filter(|(index, _)| *index != skipped)
+                    .map(|(_, cell)| cell)
+                    .collect()
+            })
+            .collect();
+        candidates.push(join_lines(&kept));
+    }
+    for (index, cell) in text.char_indices() {
+        if cell != '.' && cell != '\n' {
+            let mut floored = String::from(text);
+            floored.replace_range(index..=index, ".");
+            candidates.push(floored);
+        }
+    }
+    candidates
+}
+
+// The black tile count on each of the first DAYS days
+#[cfg(feature = "y2020-d24")]
+fn count_tiles(text: &str, engine: day_24::Engine) -> String {
+    const DAYS: usize = 20;
+    let mut counts = Vec::new();
+    {
+        let mut life = HexLife::new();
+        if let Err(error) = life.flip_from_instructions(text.lines()) {
+            return error.to_string();
+        }
+        life.set_engine(engine);
+        life.on_generation(|_, life| counts.push(life.black_count().to_string()));
+        life.run(DAYS);
+    }
+    counts.join(" ")
+}
+
+#[cfg(feature = "y2020-d24")]
+const DAY_24_ENGINES: [Engine; 2] = [
+    Engine {
+        name: "sparse",
+        run: |text| count_tiles(text, day_24::Engine::Sparse),
+    },
+    Engine {
+        name: "dense",
+        run: |text| count_tiles(text, day_24::Engine::Dense),
+    },
+];
+
+#[cfg(feature = "y2020-d24")]
+const DIRECTIONS: [&str; 6] = ["e", "se", "sw", "w", "nw", "ne"];
+
+// Up to 30 paths of up to 12 steps
+#[cfg(feature = "y2020-d24")]
+fn generate_paths(rng: &mut Rng) -> String {
+    let mut text = String::new();
+    for _ in 0..=rng.below(30) {
+        for _ in 0..rng.below(13) {
+            text.push_str(DIRECTIONS[usize::try_from(rng.below(6)).unwrap()]);
+        }
+        text.push('\n');
+    }
+    text
+}
+
+// Drops a path, or the last step of one
+#[cfg(feature = "y2020-d24")]
+fn shrink_paths(text: &str) -> Vec<String> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut candidates = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        let mut without = lines.clone();
+        without.remove(index);
+        candidates.push(join_lines(&without));
+
+        let step = DIRECTIONS
+            .iter()
+            .filter(|step| line.ends_with(*step))
+            .map(|step| step.len())
+            .max();
+        if let Some(step) = step {
+            let mut shortened = lines.clone();
+            shortened[index] = &line[..line.len() - step];
+            candidates.push(join_lines(&shortened));
+        }
+    }
+    candidates
+}
+
+#[must_use]
+pub fn find(year: u16, day: u8) -> Option<Target> {
+    match (year, day) {
+        #[cfg(feature = "y2020-d11")]
+        (2020, 11) => Some(Target {
+            generate: generate_layout,
+            engines: DAY_11_ENGINES,
+            shrink: shrink_layout,
+        }),
+        #[cfg(feature = "y2020-d24")]
+        (2020, 24) => Some(Target {
+            generate: generate_paths,
+            engines: &DAY_24_ENGINES,
+            shrink: shrink_paths,
+        }),
+        _ => None,
+    }
+}
+
+// An input the engines disagreed on, with what each of them made of it
+pub struct Divergence {
+    pub iteration: usize,
+    pub input: String,
+    pub outputs: Vec<(&'static str, String)>,
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "Engines disagree on input {}, minimized to:",
+            self.iteration + 1
+        )?;
+        write!(f, "{}", self.input)?;
+        for (name, output) in &self.outputs {
+            write!(f, "\n{name}: {output}")?;
+        }
+        Ok(())
+    }
+}
+
+// A panic is just another answer, which the other engines won't match
+fn run_engines(target: &Target, input: &str) -> Vec<(&'static str, String)> {
+    target
+        .engines
+        .iter()
+        .map(|engine| {
+            let output = panic::catch_unwind(AssertUnwindSafe(|| (engine.run)(input)))
+                .unwrap_or_else(|payload| {
+                    let message = payload
+                        .downcast_ref::<&str>()
+                        .map(|message| String::from(*message))
+                        .or_else(|| payload.downcast_ref::<String>().cloned())
+                        .unwrap_or_default();
+                    format!("panicked: {message}")
+                });
+            (engine.name, output)
+        })
+        .collect()
+}
+
+fn disagree(outputs: &[(&'static str, String)]) -> bool {
+    outputs.windows(2).any(|pair| pair[0].1 != pair[1].1)
+}
+
+// Keeps taking the first smaller input the engines still disagree on, until
+// there isn't one
+fn minimize(target: &Target, mut input: String) -> String {
+    while let Some(smaller) = (target.shrink)(&input)
+        .into_iter()
+        .find(|candidate| disagree(&run_engines(target, candidate)))
+    {
+        input = smaller;
+    }
+    input
+}
+
+// Tries iterations random inputs, stopping at the first one the engines
+// disagree on. Panics are caught and compared rather than reported, so the
+// panic hook is silenced until then.
+#[must_use]
+pub fn run(target: &Target, iterations: usize, seed: u64) -> Option<Divergence> {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut rng = Rng::new(seed);
+    let divergence = (0..iterations).find_map(|iteration| {
+        let input = (target.generate)(&mut rng);
+        disagree(&run_engines(target, &input)).then(|| {
+            let input = minimize(target, input);
+            let outputs = run_engines(target, &input);
+            Divergence {
+                iteration,
+                input,
+                outputs,
+            }
+        })
+    });
+
+    panic::set_hook(hook);
+    divergence
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Counts seats, except that the second engine loses count past three
+    #[cfg(feature = "y2020-d11")]
+    const BROKEN_ENGINES: [Engine; 2] = [
+        Engine {
+            name: "right",
+            run: |text| text.matches('L').count().to_string(),
+        },
+        Engine {
+            name: "wrong",
+            run: |text| text.matches('L').count().min(3).to_string(),
+        },
+    ];
+
+    #[test]
+    #[cfg(feature = "y2020-d11")]
+    fn divergence_is_minimized() {
+        let target = Target {
+            generate: generate_layout,
+            engines: &BROKEN_ENGINES,
+            shrink: shrink_layout,
+        };
+        let divergence = run(&target, 100, 1).unwrap();
+        assert_eq!(divergence.input.matches('L').count(), 4);
+        assert!(divergence.input.len() <= 10, "{}", divergence.input);
+        assert_eq!(
+            divergence.outputs,
+            [("right", "4".into()), ("wrong", "3".into())]
+        );
+        assert!(divergence.to_string().ends_with("right: 4\nwrong: 3"));
+    }
+
+    #[test]
+    #[cfg(feature = "y2020-d24")]
+    fn panics_count_as_answers() {
+        let target = Target {
+            generate: generate_paths,
+            engines: &[
+                Engine {
+                    name: "fine",
+                    run: |_| String::new(),
+                },
+                Engine {
+                    name: "broken",
+                    run: |_| panic!("Failed on purpose"),
+                },
+            ],
+            shrink: shrink_paths,
+        };
+        let divergence = run(&target, 1, 0).unwrap();
+        assert_eq!(divergence.input, "");
+        assert_eq!(divergence.outputs[1].1, "panicked: Failed on purpose");
+    }
+
+    #[test]
+    fn engines_agree_on_random_inputs() {
+        for (year, day) in [(2020, 11), (2020, 24)] {
+            if let Some(target) = find(year, day) {
+                assert!(run(&target, 20, 7).is_none(), "{} day {}", year, day);
+            }
+        }
+        assert!(find(2020, 1).is_none());
+    }
+}
@@ -0,0 +1,151 @@
+use std::io::{self, Write};
+
+#[cfg(any(feature = "y2020-d11", feature = "y2020-d24"))]
+use clap::value_t;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+// A kind of input aoc gen can write, in the same format as the puzzle's own,
+// for feeding the scaling benchmarks and the engines more than the puzzle asks
+pub struct Kind {
+    pub name: &'static str,
+    pub write: fn(&ArgMatches, &mut dyn Write) -> io::Result<()>,
+}
+
+// Every kind this build includes the day for
+pub const KINDS: &[Kind] = &[
+    #[cfg(feature = "y2020-d11")]
+    Kind {
+        name: "seating",
+        write: write_seating,
+    },
+    #[cfg(feature = "y2020-d24")]
+    Kind {
+        name: "hexwalk",
+        write: write_hexwalk,
+    },
+];
+
+#[must_use]
+pub fn find(name: &str) -> Option<&'static Kind> {
+    KINDS.iter().find(|kind| kind.name == name)
+}
+
+pub fn subcommand() -> App<'static, 'static> {
+    let kinds: Vec<&str> = KINDS.iter().map(|kind| kind.name).collect();
+    SubCommand::with_name("gen")
+        .about("Writes a random input, as large as asked, for stress tests")
+        .arg(Arg::from_usage("-k, --kind <KIND> 'What to generate'").possible_values(&kinds))
+        .arg(Arg::from_usage("--rows [ROWS] 'Rows of seating'").default_value("100"))
+        .arg(Arg::from_usage("--cols [COLUMNS] 'Columns of seating'").default_value("100"))
+        .arg(
+            Arg::from_usage("--fill [FRACTION] 'Fraction of seating cells that are seats'")
+                .default_value("0.6"),
+        )
+        .arg(Arg::from_usage("--lines [LINES] 'Number of hexwalk paths'").default_value("1000"))
+        .arg(Arg::from_usage("--len [STEPS] 'Steps in each hexwalk path'").default_value("20"))
+        .arg(Arg::from_usage("-s, --seed [SEED] 'Seed for the generator'").default_value("0"))
+        .arg(Arg::from_usage(
+            "-o, --out [FILE] 'Write the input to FILE instead of stdout'",
+        ))
+}
+
+// A day 11 layout of empty seats, each cell a seat with probability fill
+#[cfg(feature = "y2020-d11")]
+pub fn seating(
+    rows: usize,
+    columns: usize,
+    fill: f64,
+    seed: u64,
+    output: &mut dyn Write,
+) -> io::Result<()> {
+    write!(
+        output,
+        "{}",
+        day_11::Layout::synthesize(rows, columns, fill, seed)
+    )
+}
+
+#[cfg(feature = "y2020-d11")]
+fn write_seating(args: &ArgMatches, output: &mut dyn Write) -> io::Result<()> {
+    let fill = value_t!(args, "fill", f64).unwrap_or_else(|error| error.exit());
+    if !(0.0..=1.0).contains(&fill) {
+        clap::Error::with_description(
+            "--fill has to be between 0 and 1",
+            clap::ErrorKind::InvalidValue,
+        )
+        .exit();
+    }
+    seating(
+        value_t!(args, "rows", usize).unwrap_or_else(|error| error.exit()),
+        value_t!(args, "cols", usize).unwrap_or_else(|error| error.exit()),
+        fill,
+        value_t!(args, "seed", u64).unwrap_or_else(|error| error.exit()),
+        output,
+    )
+}
+
+// Day 24 instructions, each line a random walk of length steps
+#[cfg(feature = "y2020-d24")]
+pub fn hexwalk(lines: usize, length: usize, seed: u64, output: &mut dyn Write) -> io::Result<()> {
+    for line in day_24::random_walks(lines, length, seed) {
+        writeln!(output, "{line}")?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "y2020-d24")]
+fn write_hexwalk(args: &ArgMatches, output: &mut dyn Write) -> io::Result<()> {
+    hexwalk(
+        value_t!(args, "lines", usize).unwrap_or_else(|error| error.exit()),
+        value_t!(args, "len", usize).unwrap_or_else(|error| error.exit()),
+        value_t!(args, "seed", u64).unwrap_or_else(|error| error.exit()),
+        output,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "y2020-d11")]
+    fn seating_reads_back_as_a_layout() {
+        let mut output = Vec::new();
+        seating(30, 50, 0.6, 3, &mut output).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert_eq!(text.lines().count(), 30);
+        assert!(text.lines().all(|line| line.len() == 50));
+
+        let seats = text.matches('L').count();
+        assert!(seats > 30 * 50 / 2 && seats < 30 * 50 * 7 / 10, "{}", seats);
+        assert_eq!(seats + text.matches('.').count(), 30 * 50);
+
+        let mut layout = day_11::Layout::new();
+        for line in text.lines() {
+            layout.add_line(line).unwrap();
+        }
+        assert_eq!(layout.to_string(), text);
+    }
+
+    #[test]
+    #[cfg(feature = "y2020-d24")]
+    fn hexwalk_flips_the_seeded_floor() {
+        let mut output = Vec::new();
+        hexwalk(200, 12, 5, &mut output).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert_eq!(text.lines().count(), 200);
+
+        let mut written = day_24::HexLife::new();
+        written.flip_from_instructions(text.lines()).unwrap();
+        let mut seeded = day_24::HexLife::new();
+        seeded.seed_random_walks(200, 12, 5);
+        assert_eq!(written.get_positions(), seeded.get_positions());
+    }
+
+    #[test]
+    fn kinds_follow_the_included_days() {
+        assert_eq!(find("seating").is_some(), cfg!(feature = "y2020-d11"));
+        assert_eq!(find("hexwalk").is_some(), cfg!(feature = "y2020-d24"));
+        assert!(find("maze").is_none());
+    }
+}
@@ -0,0 +1,6 @@
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(clippy::missing_errors_doc)]
+
+// Every included day's solver, shared by the aoc runner and the advent
+// multi-call binary
+pub mod days;
@@ -0,0 +1,513 @@
+#![deny(clippy::all, clippy::pedantic)]
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    process,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use aoc::days;
+use clap::{crate_name, value_t, App, AppSettings, Arg, ArgMatches, SubCommand};
+use common::{alloc, cli::Part, download, input, timing, Solution};
+
+mod bench;
+mod difftest;
+mod generate;
+mod notify;
+mod output;
+#[cfg(feature = "y2020-d24")]
+mod repl;
+mod scaffold;
+mod verify;
+
+use bench::DayTiming;
+use output::{Format, Record};
+use verify::{Status, Tally};
+
+// Each day's input.txt sits in its own crate, next to this one. Without it,
+// the input is downloaded instead.
+fn find_input(year: u16, day: u8) -> io::Result<PathBuf> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .join(days::crate_name(day))
+        .join("input.txt");
+    if path.exists() {
+        Ok(path)
+    } else {
+        download::fetch(year, day)
+    }
+}
+
+fn path_to_string(path: &Path) -> String {
+    path.to_str()
+        .expect("Failed to convert input path to a string")
+        .to_owned()
+}
+
+fn parse_year(args: &ArgMatches) -> Option<u16> {
+    args.is_present("year")
+        .then(|| value_t!(args, "year", u16).unwrap_or_else(|error| error.exit()))
+}
+
+fn solve(year: u16, day: u8, solution: &dyn Solution, text: &str, part: Part) -> Record {
+    let start = Instant::now();
+    let part1 = (part != Part::Two).then(|| solution.part1(text));
+    let part2 = (part != Part::One).then(|| solution.part2(text));
+    Record {
+        year,
+        day,
+        part1,
+        // The last day has no second part, and so no answer for it
+        part2: part2.filter(|answer| !answer.is_empty()),
+        elapsed: start.elapsed(),
+    }
+}
+
+// A webhook that can't be reached fails the command, once the answers are out
+fn notify_or_exit(url: &str, json: &str) {
+    if let Err(error) = notify::post_json(url, json) {
+        eprintln!("{error}");
+        process::exit(1);
+    }
+}
+
+// The day's own input unless another one was given, exiting if it can't be read
+fn read_input(year: u16, day: u8, args: &ArgMatches) -> (String, String) {
+    let filename = args.value_of("input").map_or_else(
+        || {
+            let path = find_input(year, day).unwrap_or_else(|error| {
+                eprintln!("Failed to find the input for {year} day {day}: {error}");
+                process::exit(1);
+            });
+            path_to_string(&path)
+        },
+        String::from,
+    );
+    let text = input::read_to_string(&filename).unwrap_or_else(|error| {
+        eprintln!("Failed to read {filename}: {error}");
+        process::exit(1);
+    });
+    (filename, text)
+}
+
+fn run(args: &ArgMatches) {
+    let year = value_t!(args, "YEAR", u16).unwrap_or_else(|error| error.exit());
+    let day = value_t!(args, "DAY", u8).unwrap_or_else(|error| error.exit());
+    let part = value_t!(args, "part", Part).unwrap_or_else(|error| error.exit());
+    let output = value_t!(args, "output", Format).unwrap_or_else(|error| error.exit());
+
+    let registry = days::registry();
+    let solution = days::find(&registry, year, day).unwrap_or_else(|error| {
+        eprintln!("{error}");
+        process::exit(1);
+    });
+    let (filename, text) = read_input(year, day, args);
+
+    let record = solve(year, day, solution, &text, part);
+    match output {
+        Format::Text => println!("{record}"),
+        Format::Json => println!("{}", record.to_json()),
+    }
+    if let Some(url) = args.value_of("notify") {
+        notify_or_exit(url, &record.to_json());
+    }
+
+    if args.is_present("verify") {
+        match verify::verify(Path::new(&filename), &record) {
+            Ok(mismatches) if mismatches.is_empty() => {}
+            Ok(mismatches) => {
+                for mismatch in mismatches {
+                    eprintln!("{year} day {day}: {mismatch}");
+                }
+                process::exit(1);
+            }
+            Err(error) => {
+                eprintln!("{error}");
+                process::exit(1);
+            }
+        }
+    }
+}
+
+// Times both parts of every day that has an input, each after a warmup run
+fn bench(args: &ArgMatches) {
+    let runs = value_t!(args, "runs", usize).unwrap_or_else(|error| error.exit());
+    let year = parse_year(args);
+
+    let registry = days::registry();
+    let mut timings = Vec::new();
+    for ((puzzle_year, day), solution) in registry.iter() {
+        if year.is_some_and(|year| year != puzzle_year) {
+            continue;
+        }
+        let text = match find_input(puzzle_year, day)
+            .and_then(|path| input::read_to_string(&path_to_string(&path)))
+        {
+            Ok(text) => text,
+            Err(error) => {
+                eprintln!("Skipping {puzzle_year} day {day}: {error}");
+                continue;
+            }
+        };
+        let summary = timing::repeat(runs, || (solution.part1(&text), solution.part2(&text)));
+        eprintln!("{puzzle_year} day {day:02}: {summary}");
+        timings.push(DayTiming {
+            year: puzzle_year,
+            day,
+            allocator: alloc::NAME,
+            summary,
+        });
+    }
+    print!("{}", bench::tabulate(timings));
+}
+
+// A day solved against its input, which the answers are checked next to
+type Solved = Result<(PathBuf, Record), String>;
+
+fn solve_day(year: u16, day: u8, solution: &dyn Solution) -> Solved {
+    let path =
+        find_input(year, day).map_err(|error| format!("Skipping {year} day {day}: {error}"))?;
+    let text = input::load(&path_to_string(&path));
+    Ok((path, solve(year, day, solution, &text, Part::Both)))
+}
+
+// Hands each day to whichever of the workers is free and passes on the results
+// in the order they finish, so a slow day doesn't hold back the rest
+fn solve_days<F>(puzzles: &[((u16, u8), &'static dyn Solution)], jobs: usize, mut on_solved: F)
+where
+    F: FnMut(Solved),
+{
+    if jobs <= 1 {
+        for ((year, day), solution) in puzzles {
+            on_solved(solve_day(*year, *day, *solution));
+        }
+        return;
+    }
+
+    let next_puzzle = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(puzzles.len()) {
+            let sender = sender.clone();
+            let next_puzzle = &next_puzzle;
+            scope.spawn(move || loop {
+                let index = next_puzzle.fetch_add(1, Ordering::Relaxed);
+                let Some(((year, day), solution)) = puzzles.get(index) else {
+                    return;
+                };
+                if sender.send(solve_day(*year, *day, *solution)).is_err() {
+                    return;
+                }
+            });
+        }
+        // The workers hold the only senders left, so this ends once they do
+        drop(sender);
+        for solved in receiver {
+            on_solved(solved);
+        }
+    });
+}
+
+// Solves every day that has an input, checking each against its recorded
+// answers, and exits with an error if any of them are wrong
+fn all(args: &ArgMatches) {
+    let year = parse_year(args);
+    let output = value_t!(args, "output", Format).unwrap_or_else(|error| error.exit());
+    let jobs = value_t!(args, "jobs", usize).unwrap_or_else(|error| error.exit());
+
+    let registry = days::registry();
+    let puzzles: Vec<_> = registry
+        .iter()
+        .filter(|((puzzle_year, _), _)| year.is_none_or(|year| year == *puzzle_year))
+        .collect();
+
+    let start = Instant::now();
+    let mut total = Duration::ZERO;
+    let mut tally = Tally::default();
+    let mut records = Vec::new();
+    solve_days(&puzzles, jobs, |solved| {
+        let (path, record) = match solved {
+            Ok(solved) => solved,
+            Err(error) => {
+                eprintln!("{error}");
+                return;
+            }
+        };
+        let (year, day) = (record.year, record.day);
+        total += record.elapsed;
+        let status = verify::status(&path, &record).unwrap_or_else(|error| {
+            eprintln!("{error}");
+            Status::Failed(Vec::new())
+        });
+        tally.add(&status);
+        records.push(record.to_json());
+
+        match output {
+            Format::Text => {
+                let verdict = match &status {
+                    Status::Passed => "passed",
+                    Status::Failed(_) => "FAILED",
+                    Status::Unchecked => "unchecked",
+                };
+                println!("{year} day {day:02} ({:.2?}, {verdict})", record.elapsed);
+                for line in record.to_string().lines() {
+                    println!("  {line}");
+                }
+            }
+            Format::Json => println!("{}", record.to_json()),
+        }
+        if let Status::Failed(mismatches) = &status {
+            for mismatch in mismatches {
+                eprintln!("{year} day {day}: {mismatch}");
+            }
+        }
+    });
+
+    // With several jobs, the days' own times add up to more than the wall clock
+    let summary = format!(
+        "{} days in {total:.2?} ({:.2?} wall clock): {tally}",
+        tally.days(),
+        start.elapsed()
+    );
+    match output {
+        Format::Text => println!("{summary}"),
+        // Only the records go to stdout, so it stays one JSON object per line
+        Format::Json => eprintln!("{summary}"),
+    }
+    // Every day goes in one array, in the order they finished
+    if let Some(url) = args.value_of("notify") {
+        notify_or_exit(url, &format!("[{}]", records.join(",")));
+    }
+    if tally.failed > 0 {
+        process::exit(1);
+    }
+}
+
+// Names every day in this build, and with --engines, the engines difftest
+// compares for the days that have more than one
+fn list(args: &ArgMatches) {
+    let year = parse_year(args);
+    for ((puzzle_year, day), _) in days::registry().iter() {
+        if year.is_some_and(|year| year != puzzle_year) {
+            continue;
+        }
+        if !args.is_present("engines") {
+            println!("{puzzle_year}-{day:02}");
+        } else if let Some(target) = difftest::find(puzzle_year, day) {
+            let names: Vec<&str> = target.engines.iter().map(|engine| engine.name).collect();
+            println!("{puzzle_year}-{day:02}: {}", names.join(", "));
+        }
+    }
+}
+
+fn list_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("list")
+        .about("Lists the days this build includes")
+        .arg(Arg::from_usage(
+            "--engines 'List the engines of the days that have several instead'",
+        ))
+        .arg(Arg::from_usage(
+            "--year [YEAR] 'Only list this year's days'",
+        ))
+}
+
+// Runs random inputs through every engine a day has, and exits with an error
+// once they disagree, showing the smallest input they still disagree on
+fn difftest(args: &ArgMatches) {
+    let year = value_t!(args, "year", u16).unwrap_or_else(|error| error.exit());
+    let day = value_t!(args, "day", u8).unwrap_or_else(|error| error.exit());
+    let iterations = value_t!(args, "iterations", usize).unwrap_or_else(|error| error.exit());
+    let seed = value_t!(args, "seed", u64).unwrap_or_else(|error| error.exit());
+
+    let Some(target) = difftest::find(year, day) else {
+        eprintln!("{year} day {day} has no engines to compare in this build");
+        process::exit(1);
+    };
+    if let Some(divergence) = difftest::run(&target, iterations, seed) {
+        println!("{divergence}");
+        process::exit(1);
+    }
+    let names: Vec<&str> = target.engines.iter().map(|engine| engine.name).collect();
+    println!("{} agreed on {iterations} random inputs", names.join(", "));
+}
+
+// Writes a generated input to a file or stdout, for benchmarks and stress tests
+// at sizes the puzzle never reaches
+fn generate(args: &ArgMatches) {
+    let name = args.value_of("kind").unwrap();
+    let Some(kind) = generate::find(name) else {
+        eprintln!("No {name} generator in this build");
+        process::exit(1);
+    };
+
+    let mut output: BufWriter<Box<dyn Write>> = BufWriter::new(match args.value_of("out") {
+        Some(filename) => Box::new(File::create(filename).unwrap_or_else(|error| {
+            eprintln!("Failed to create {filename}: {error}");
+            process::exit(1);
+        })),
+        None => Box::new(io::stdout().lock()),
+    });
+    if let Err(error) = (kind.write)(args, &mut output).and_then(|()| output.flush()) {
+        eprintln!("Failed to write the input: {error}");
+        process::exit(1);
+    }
+}
+
+// Flips the tiles once, then lets the floor be stepped and looked at from a
+// prompt. Day 24 is the only day with a floor to explore this way, and only
+// when it's included in the build.
+fn repl(args: &ArgMatches) {
+    let year = value_t!(args, "year", u16).unwrap_or_else(|error| error.exit());
+    let day = value_t!(args, "day", u8).unwrap_or_else(|error| error.exit());
+
+    #[cfg(feature = "y2020-d24")]
+    if (year, day) == (2020, 24) {
+        let (filename, text) = read_input(year, day, args);
+        let mut life = day_24::HexLife::new();
+        if let Err(error) = life.flip_from_instructions(text.lines()) {
+            eprintln!("{filename}: {error}");
+            process::exit(1);
+        }
+        if let Err(error) = repl::run(&life, io::stdin().lock(), io::stdout()) {
+            eprintln!("{error}");
+            process::exit(1);
+        }
+        return;
+    }
+
+    eprintln!("{year} day {day} has no REPL in this build");
+    process::exit(1);
+}
+
+fn new(args: &ArgMatches) {
+    let day = value_t!(args, "DAY", u8).unwrap_or_else(|error| error.exit());
+    let workspace = args.value_of("workspace").map_or_else(
+        || {
+            Path::new(env!("CARGO_MANIFEST_DIR"))
+                .parent()
+                .unwrap()
+                .to_path_buf()
+        },
+        PathBuf::from,
+    );
+
+    match scaffold::create(&workspace, day) {
+        Ok(directory) => println!("Created {}", directory.display()),
+        Err(error) => {
+            eprintln!("Failed to create day {day}: {error}");
+            process::exit(1);
+        }
+    }
+}
+
+fn main() {
+    let args = App::new(crate_name!())
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(
+            SubCommand::with_name("run")
+                .about("Runs one day's solver")
+                .arg(Arg::from_usage("<YEAR> 'Puzzle year'"))
+                .arg(Arg::from_usage("<DAY> 'Day of the month'"))
+                .arg(
+                    Arg::from_usage("--part [PART] 'Which part to answer'")
+                        .possible_values(&Part::NAMES)
+                        .default_value("both"),
+                )
+                .arg(Arg::from_usage(
+                    "-i, --input [FILE] 'Puzzle input, instead of the day's own input.txt'",
+                ))
+                .arg(
+                    Arg::from_usage("-o, --output [FORMAT] 'How to print the answers'")
+                        .possible_values(&Format::NAMES)
+                        .default_value("text"),
+                )
+                .arg(Arg::from_usage(
+                    "--verify 'Exit with an error unless the answers match the answers.txt next to the input'",
+                ))
+                .arg(Arg::from_usage(
+                    "--notify [URL] 'POST the answers as JSON to a webhook'",
+                )),
+        )
+        .subcommand(
+            SubCommand::with_name("all")
+                .about("Runs every day against its input and checks the recorded answers")
+                .arg(Arg::from_usage("--year [YEAR] 'Only run this year's days'"))
+                .arg(
+                    Arg::from_usage("-j, --jobs [JOBS] 'Number of days to solve in parallel'")
+                        .default_value("1"),
+                )
+                .arg(
+                    Arg::from_usage("-o, --output [FORMAT] 'How to print the answers'")
+                        .possible_values(&Format::NAMES)
+                        .default_value("text"),
+                )
+                .arg(Arg::from_usage(
+                    "--notify [URL] 'POST every day's answers as a JSON array to a webhook'",
+                )),
+        )
+        .subcommand(
+            SubCommand::with_name("bench")
+                .about("Times every day's solver and tabulates the slowest first")
+                .arg(
+                    Arg::from_usage("-n, --runs [N] 'Timed runs of each day, after one warmup'")
+                        .default_value("1"),
+                )
+                .arg(Arg::from_usage(
+                    "--year [YEAR] 'Only time this year's days'",
+                )),
+        )
+        .subcommand(list_subcommand())
+        .subcommand(
+            SubCommand::with_name("difftest")
+                .about("Checks that a day's engines agree on random inputs")
+                .arg(Arg::from_usage("-d, --day <DAY> 'Day of the month'"))
+                .arg(Arg::from_usage("--year [YEAR] 'Puzzle year'").default_value("2020"))
+                .arg(
+                    Arg::from_usage("-n, --iterations [N] 'Random inputs to try'")
+                        .default_value("100"),
+                )
+                .arg(
+                    Arg::from_usage("-s, --seed [SEED] 'Seed for the input generator'")
+                        .default_value("0"),
+                ),
+        )
+        .subcommand(generate::subcommand())
+        .subcommand(
+            SubCommand::with_name("repl")
+                .about("Steps through a day interactively from its parsed input")
+                .arg(Arg::from_usage("-d, --day <DAY> 'Day of the month'"))
+                .arg(Arg::from_usage("--year [YEAR] 'Puzzle year'").default_value("2020"))
+                .arg(Arg::from_usage(
+                    "-i, --input [FILE] 'Puzzle input, instead of the day's own input.txt'",
+                )),
+        )
+        .subcommand(
+            SubCommand::with_name("new")
+                .about("Creates a crate for a new day and adds it to the workspace")
+                .arg(Arg::from_usage("<DAY> 'Day of the month'"))
+                .arg(Arg::from_usage(
+                    "-w, --workspace [DIR] 'Workspace to add the day to, instead of this one'",
+                )),
+        )
+        .get_matches();
+
+    match args.subcommand() {
+        ("run", Some(args)) => run(args),
+        ("all", Some(args)) => all(args),
+        ("bench", Some(args)) => bench(args),
+        ("difftest", Some(args)) => difftest(args),
+        ("list", Some(args)) => list(args),
+        ("gen", Some(args)) => generate(args),
+        ("repl", Some(args)) => repl(args),
+        ("new", Some(args)) => new(args),
+        _ => unreachable!("Impossible subcommand"),
+    }
+}
@@ -0,0 +1,84 @@
+use std::{
+    io::{self, Write},
+    process::{Command, Stdio},
+};
+
+// POSTs a JSON body to a webhook through curl, like common::download, with the
+// body going in through stdin. Whatever the webhook answers is thrown away so
+// it doesn't end up mixed in with the answers.
+pub fn post_json(url: &str, json: &str) -> io::Result<()> {
+    let mut curl = Command::new("curl")
+        .args(["--fail", "--silent", "--show-error"])
+        .args(["--header", "Content-Type: application/json"])
+        .args(["--data-binary", "@-"])
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()?;
+    curl.stdin.take().unwrap().write_all(json.as_bytes())?;
+    if curl.wait()?.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("Failed to notify {url}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read},
+        net::TcpListener,
+        thread,
+    };
+
+    use super::*;
+
+    // Accepts one request, answers it with status, and returns what was posted
+    fn serve_once(status: &'static str) -> (String, thread::JoinHandle<(String, String)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut headers = String::new();
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                if line == "\r\n" {
+                    break;
+                }
+                headers.push_str(&line);
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            )
+            .unwrap();
+            (headers, String::from_utf8(body).unwrap())
+        });
+        (url, server)
+    }
+
+    #[test]
+    fn posts_json() {
+        let (url, server) = serve_once("200 OK");
+        post_json(&url, "{\"day\":11}").unwrap();
+        let (headers, body) = server.join().unwrap();
+        assert!(headers.starts_with("POST /hook "), "{}", headers);
+        assert!(headers.contains("Content-Type: application/json"));
+        assert_eq!(body, "{\"day\":11}");
+    }
+
+    #[test]
+    fn rejected_posts_are_errors() {
+        let (url, server) = serve_once("500 Internal Server Error");
+        assert!(post_json(&url, "[]").is_err());
+        server.join().unwrap();
+    }
+}
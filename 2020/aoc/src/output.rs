@@ -0,0 +1,136 @@
+use std::{
+    fmt::{self, Display, Formatter, Write},
+    str::FromStr,
+    time::Duration,
+};
+
+// How run prints a day's answers: text for people, one JSON object per day for
+// scripts
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+impl Format {
+    pub const NAMES: [&'static str; 2] = ["text", "json"];
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(format!(
+                "unknown output format {text}, expected text or json"
+            )),
+        }
+    }
+}
+
+// A day's answers, with None for a part that wasn't asked for or, like the
+// last day's second part, doesn't exist
+pub struct Record {
+    pub year: u16,
+    pub day: u8,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+    pub elapsed: Duration,
+}
+
+fn write_json_string(out: &mut String, text: &str) {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => write!(out, "\\u{:04x}", u32::from(c)).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn write_json_answer(out: &mut String, answer: Option<&String>) {
+    match answer {
+        Some(answer) => write_json_string(out, answer),
+        None => out.push_str("null"),
+    }
+}
+
+impl Record {
+    pub fn to_json(&self) -> String {
+        let mut json = format!("{{\"year\":{},\"day\":{},\"part1\":", self.year, self.day);
+        write_json_answer(&mut json, self.part1.as_ref());
+        json.push_str(",\"part2\":");
+        write_json_answer(&mut json, self.part2.as_ref());
+        write!(
+            json,
+            ",\"elapsed_ms\":{:.3}}}",
+            self.elapsed.as_secs_f64() * 1000.0
+        )
+        .unwrap();
+        json
+    }
+}
+
+impl Display for Record {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let answers = [(1, &self.part1), (2, &self.part2)];
+        let mut first = true;
+        for (number, answer) in answers {
+            if let Some(answer) = answer {
+                if !first {
+                    writeln!(f)?;
+                }
+                write!(f, "Part {number}: {answer}")?;
+                first = false;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_print_as_text_or_json() {
+        let record = Record {
+            year: 2020,
+            day: 24,
+            part1: Some(String::from("10")),
+            part2: Some(String::from("2208")),
+            elapsed: Duration::from_micros(12_345),
+        };
+        assert_eq!(record.to_string(), "Part 1: 10\nPart 2: 2208");
+        assert_eq!(
+            record.to_json(),
+            r#"{"year":2020,"day":24,"part1":"10","part2":"2208","elapsed_ms":12.345}"#
+        );
+    }
+
+    #[test]
+    fn missing_parts_are_null() {
+        let record = Record {
+            year: 2020,
+            day: 25,
+            part1: Some(String::from("say \"hi\"\n")),
+            part2: None,
+            elapsed: Duration::ZERO,
+        };
+        assert_eq!(record.to_string(), "Part 1: say \"hi\"\n");
+        assert_eq!(
+            record.to_json(),
+            r#"{"year":2020,"day":25,"part1":"say \"hi\"\n","part2":null,"elapsed_ms":0.000}"#
+        );
+        assert_eq!("json".parse(), Ok(Format::Json));
+        assert!("yaml".parse::<Format>().is_err());
+    }
+}
@@ -0,0 +1,158 @@
+use std::{
+    fmt::Write as _,
+    fs,
+    io::{self, BufRead, Write},
+};
+
+use common::vis::term;
+use day_24::HexLife;
+
+const HELP: &str = "\
+step [N]    Evolve N days, one if N is left out
+count       Count the black tiles
+show        Print the floor, with # for black tiles
+save FILE   Write the day and the black tiles to FILE as JSON
+reset       Go back to the flipped tiles, before any days
+quit        Leave, as does the end of input";
+
+// The day and where each black tile sits relative to the reference tile,
+// sorted so that saving the same floor twice gives the same file
+fn to_json(life: &HexLife) -> String {
+    let mut positions = life.get_positions();
+    positions.sort_unstable();
+    let mut json = format!("{{\"day\":{},\"black_tiles\":[", life.get_generation());
+    for (index, (x, y)) in positions.iter().enumerate() {
+        if index > 0 {
+            json.push(',');
+        }
+        write!(json, "[{x},{y}]").unwrap();
+    }
+    json.push_str("]}\n");
+    json
+}
+
+// Carries out one command, returning false once it's time to leave
+fn execute(
+    command: &str,
+    initial: &HexLife,
+    life: &mut HexLife<'static>,
+    output: &mut impl Write,
+) -> io::Result<bool> {
+    let mut words = command.split_whitespace();
+    match (words.next(), words.next()) {
+        (None, _) => {}
+        (Some("step"), days) => match days.map_or(Ok(1), str::parse::<usize>) {
+            Ok(days) => {
+                life.run(days);
+                writeln!(
+                    output,
+                    "Day {}: {} black tiles",
+                    life.get_generation(),
+                    life.black_count()
+                )?;
+            }
+            Err(error) => writeln!(output, "Invalid number of days: {error}")?,
+        },
+        (Some("count"), None) => writeln!(output, "{}", life.black_count())?,
+        (Some("show"), None) => match life.get_map() {
+            Some(map) => write!(
+                output,
+                "{}",
+                term::text(&map, |tile| match tile {
+                    Some(true) => '#',
+                    Some(false) => '.',
+                    None => ' ',
+                })
+            )?,
+            None => writeln!(output, "No black tiles")?,
+        },
+        (Some("save"), Some(filename)) => match fs::write(filename, to_json(life)) {
+            Ok(()) => writeln!(output, "Saved day {} to {filename}", life.get_generation())?,
+            Err(error) => writeln!(output, "Failed to write {filename}: {error}")?,
+        },
+        (Some("reset"), None) => {
+            *life = initial.detach();
+            writeln!(output, "Day 0: {} black tiles", life.black_count())?;
+        }
+        (Some("help"), None) => writeln!(output, "{HELP}")?,
+        (Some("quit" | "exit"), None) => return Ok(false),
+        (Some(_), _) => writeln!(output, "Unknown command {command:?}, try help")?,
+    }
+    Ok(true)
+}
+
+// Reads commands from input until quit or the end of it, with a prompt before
+// each one
+pub fn run(initial: &HexLife, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    let mut life = initial.detach();
+    writeln!(
+        output,
+        "Day 0: {} black tiles. Type help for commands.",
+        life.black_count()
+    )?;
+    let mut lines = input.lines();
+    loop {
+        write!(output, "> ")?;
+        output.flush()?;
+        let Some(command) = lines.next() else {
+            writeln!(output)?;
+            return Ok(());
+        };
+        if !execute(&command?, initial, &mut life, &mut output)? {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(commands: &str) -> String {
+        let mut initial = HexLife::new();
+        initial.flip_from_instructions(["e", "w", "ne"]).unwrap();
+        let mut output = Vec::new();
+        run(&initial, commands.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn steps_count_and_reset() {
+        let output = session("step\nstep 2\ncount\nreset\ncount\nquit\nstep\n");
+        assert_eq!(
+            output,
+            "\
+Day 0: 3 black tiles. Type help for commands.
+> Day 1: 4 black tiles
+> Day 3: 11 black tiles
+> 11
+> Day 0: 3 black tiles
+> 3
+> "
+        );
+    }
+
+    #[test]
+    fn shows_the_floor() {
+        let output = session("show\n");
+        assert!(output.contains(" . # \n# . #\n"), "{}", output);
+        assert!(output.ends_with("> \n"));
+    }
+
+    #[test]
+    fn saves_sorted_json() {
+        let mut life = HexLife::new();
+        life.flip_from_instructions(["e", "w", "ne"]).unwrap();
+        assert_eq!(
+            to_json(&life),
+            "{\"day\":0,\"black_tiles\":[[-2,0],[1,2],[2,0]]}\n"
+        );
+    }
+
+    #[test]
+    fn mistakes_are_reported() {
+        let output = session("step x\nsave\nfly\n");
+        assert!(output.contains("Invalid number of days"));
+        assert_eq!(output.matches("try help").count(), 2);
+    }
+}
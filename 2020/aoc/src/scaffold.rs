@@ -0,0 +1,188 @@
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+// Templates for a new day, where day-NN and day_NN stand for its crate name
+const CARGO_TOML: &str = r#"[package]
+name = "day-NN"
+version = "0.1.0"
+authors = ["Dan Stoza <dstoza@gmail.com>"]
+edition = "2018"
+
+# See more keys and their definitions at https://doc.rust-lang.org/cargo/reference/manifest.html
+
+[dependencies]
+clap = "2"
+common = { path = "../common" }
+
+[features]
+# Compiles input.txt into the binary, which then reads it when no FILE is given
+embed-input = []
+"#;
+
+const LIB_RS: &str = r"#![deny(clippy::all, clippy::pedantic)]
+#![allow(clippy::missing_panics_doc)]
+
+use common::{LineReader, Solution};
+
+pub struct Solver;
+
+impl Solution for Solver {
+    fn part1(&self, input: &str) -> String {
+        let mut count = 0;
+        for line in LineReader::from_text(input) {
+            if !line.is_empty() {
+                count += 1;
+            }
+        }
+        count.to_string()
+    }
+
+    fn part2(&self, _input: &str) -> String {
+        String::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {}
+";
+
+const MAIN_RS: &str = r#"#![deny(clippy::all, clippy::pedantic)]
+
+use clap::{crate_name, App, Arg};
+use common::{input, Solution};
+use day_NN::Solver;
+
+fn main() {
+    let args = App::new(crate_name!())
+        .arg(Arg::from_usage("[FILE]").required(!cfg!(feature = "embed-input")))
+        .get_matches();
+
+    let text = input::load(common::resolve_input!(args.value_of("FILE")));
+    println!("Part 1: {}", Solver.part1(&text));
+    println!("Part 2: {}", Solver.part2(&text));
+}
+"#;
+
+fn fill(template: &str, name: &str) -> String {
+    template
+        .replace("day-NN", name)
+        .replace("day_NN", &name.replace('-', "_"))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ScaffoldError {
+    Exists(PathBuf),
+    NoMembers(PathBuf),
+    Io(String),
+}
+
+impl Display for ScaffoldError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exists(path) => write!(f, "{} already exists", path.display()),
+            Self::NoMembers(path) => write!(f, "no members list in {}", path.display()),
+            Self::Io(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl Error for ScaffoldError {}
+
+impl From<io::Error> for ScaffoldError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error.to_string())
+    }
+}
+
+// Adds member to the workspace's members list, keeping it sorted, or returns
+// None if the manifest has no list laid out one member per line
+pub fn add_member(manifest: &str, member: &str) -> Option<String> {
+    let lines: Vec<&str> = manifest.lines().collect();
+    let start = lines
+        .iter()
+        .position(|line| line.trim_start().starts_with("members = ["))?;
+    let end = start + lines[start..].iter().position(|line| line.trim() == "]")?;
+
+    let entry = format!("    \"{member}\",");
+    let mut members: Vec<String> = lines[start + 1..end]
+        .iter()
+        .map(|line| String::from(*line))
+        .chain(std::iter::once(entry))
+        .collect();
+    members.sort();
+    members.dedup();
+
+    let mut updated: Vec<String> = lines[..=start]
+        .iter()
+        .map(|line| String::from(*line))
+        .collect();
+    updated.extend(members);
+    updated.extend(lines[end..].iter().map(|line| String::from(*line)));
+    Some(updated.join("\n") + "\n")
+}
+
+// Creates day-NN in the workspace, with a lib.rs holding its Solver and a
+// main.rs that prints both parts, and lists it as a member. Returns the new
+// crate's directory.
+pub fn create(workspace: &Path, day: u8) -> Result<PathBuf, ScaffoldError> {
+    let name = format!("day-{day:02}");
+    let directory = workspace.join(&name);
+    if directory.exists() {
+        return Err(ScaffoldError::Exists(directory));
+    }
+
+    let manifest_path = workspace.join("Cargo.toml");
+    let manifest = add_member(&fs::read_to_string(&manifest_path)?, &name)
+        .ok_or_else(|| ScaffoldError::NoMembers(manifest_path.clone()))?;
+
+    fs::create_dir_all(directory.join("src"))?;
+    fs::write(directory.join("Cargo.toml"), fill(CARGO_TOML, &name))?;
+    fs::write(directory.join("src").join("lib.rs"), fill(LIB_RS, &name))?;
+    fs::write(directory.join("src").join("main.rs"), fill(MAIN_RS, &name))?;
+    fs::write(manifest_path, manifest)?;
+    Ok(directory)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = "[workspace]\n\nmembers = [\n    \"aoc\",\n    \"common\",\n    \"day-01\",\n    \"day-03\",\n]\n";
+
+    #[test]
+    fn members_stay_sorted() {
+        assert_eq!(
+            add_member(MANIFEST, "day-02").unwrap(),
+            "[workspace]\n\nmembers = [\n    \"aoc\",\n    \"common\",\n    \"day-01\",\n    \"day-02\",\n    \"day-03\",\n]\n"
+        );
+        assert_eq!(add_member(MANIFEST, "day-01").unwrap(), MANIFEST);
+        assert_eq!(add_member("[package]\n", "day-01"), None);
+    }
+
+    #[test]
+    fn creates_a_day() {
+        let workspace = std::env::temp_dir().join(format!("aoc-new-{}", std::process::id()));
+        fs::create_dir_all(&workspace).unwrap();
+        fs::write(workspace.join("Cargo.toml"), MANIFEST).unwrap();
+
+        let directory = create(&workspace, 2).unwrap();
+        assert!(directory.ends_with("day-02"));
+        let main = fs::read_to_string(directory.join("src/main.rs")).unwrap();
+        assert!(main.contains("use day_02::Solver;"));
+        let manifest = fs::read_to_string(directory.join("Cargo.toml")).unwrap();
+        assert!(manifest.contains("name = \"day-02\""));
+        assert!(fs::read_to_string(workspace.join("Cargo.toml"))
+            .unwrap()
+            .contains("\"day-02\""));
+
+        assert_eq!(
+            create(&workspace, 2),
+            Err(ScaffoldError::Exists(directory.clone()))
+        );
+        fs::remove_dir_all(workspace).unwrap();
+    }
+}
@@ -0,0 +1,217 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::output::Record;
+
+// A day's known answers sit next to its input: answers.txt beside input.txt,
+// or day-07.answers.txt beside a downloaded day-07.txt. They're in the same
+// form run prints them, so `aoc run 2020 7 > day-07/answers.txt` records them.
+pub fn answers_path(input: &Path) -> PathBuf {
+    let stem = input.file_stem().and_then(|stem| stem.to_str());
+    match stem {
+        Some("input") | None => input.with_file_name("answers.txt"),
+        Some(stem) => input.with_file_name(format!("{stem}.answers.txt")),
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Expected {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+// Reads "Part N: answer" lines, skipping blank ones
+pub fn parse_answers(text: &str) -> Result<Expected, String> {
+    let mut expected = Expected::default();
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let answer = line
+            .strip_prefix("Part ")
+            .and_then(|rest| rest.split_once(": "));
+        match answer {
+            Some(("1", answer)) => expected.part1 = Some(String::from(answer)),
+            Some(("2", answer)) => expected.part2 = Some(String::from(answer)),
+            _ => {
+                return Err(format!(
+                    "unexpected line {line:?}, expected Part 1 or Part 2"
+                ))
+            }
+        }
+    }
+    Ok(expected)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Mismatch {
+    pub part: usize,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "part {} answered {}, expected {}",
+            self.part, self.actual, self.expected
+        )
+    }
+}
+
+// Only parts that were both answered and are known get compared
+pub fn check(expected: &Expected, record: &Record) -> Vec<Mismatch> {
+    let parts = [
+        (1, &expected.part1, &record.part1),
+        (2, &expected.part2, &record.part2),
+    ];
+    parts
+        .iter()
+        .filter_map(|(part, expected, actual)| match (expected, actual) {
+            (Some(expected), Some(actual)) if expected != actual => Some(Mismatch {
+                part: *part,
+                expected: expected.clone(),
+                actual: actual.clone(),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+// Checks a record against the answers next to the input it was solved from
+pub fn verify(input: &Path, record: &Record) -> Result<Vec<Mismatch>, String> {
+    let path = answers_path(input);
+    let text = fs::read_to_string(&path)
+        .map_err(|error| format!("Failed to read {}: {error}", path.display()))?;
+    let expected = parse_answers(&text)
+        .map_err(|error| format!("Failed to parse {}: {error}", path.display()))?;
+    Ok(check(&expected, record))
+}
+
+// Where a day stands against its recorded answers
+pub enum Status {
+    Passed,
+    Failed(Vec<Mismatch>),
+    // There's nothing recorded to check against
+    Unchecked,
+}
+
+pub fn status(input: &Path, record: &Record) -> Result<Status, String> {
+    if !answers_path(input).exists() {
+        return Ok(Status::Unchecked);
+    }
+    let mismatches = verify(input, record)?;
+    if mismatches.is_empty() {
+        Ok(Status::Passed)
+    } else {
+        Ok(Status::Failed(mismatches))
+    }
+}
+
+// How many days passed and failed, out of all those run
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Tally {
+    pub passed: usize,
+    pub failed: usize,
+    pub unchecked: usize,
+}
+
+impl Tally {
+    pub fn add(&mut self, status: &Status) {
+        match status {
+            Status::Passed => self.passed += 1,
+            Status::Failed(_) => self.failed += 1,
+            Status::Unchecked => self.unchecked += 1,
+        }
+    }
+
+    pub fn days(&self) -> usize {
+        self.passed + self.failed + self.unchecked
+    }
+}
+
+impl Display for Tally {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} passed, {} failed, {} unchecked",
+            self.passed, self.failed, self.unchecked
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn answers_sit_next_to_inputs() {
+        assert_eq!(
+            answers_path(Path::new("day-07/input.txt")),
+            Path::new("day-07/answers.txt")
+        );
+        assert_eq!(
+            answers_path(Path::new("inputs/2020/day-07.txt")),
+            Path::new("inputs/2020/day-07.answers.txt")
+        );
+    }
+
+    #[test]
+    fn parses_what_run_prints() {
+        assert_eq!(
+            parse_answers("Part 1: 10\nPart 2: 2208\n"),
+            Ok(Expected {
+                part1: Some(String::from("10")),
+                part2: Some(String::from("2208")),
+            })
+        );
+        assert_eq!(
+            parse_answers("\nPart 1: 14897079\n"),
+            Ok(Expected {
+                part1: Some(String::from("14897079")),
+                part2: None,
+            })
+        );
+        assert!(parse_answers("Part 3: 1").is_err());
+        assert!(parse_answers("Occupied seats: 2361").is_err());
+    }
+
+    #[test]
+    fn reports_only_wrong_answers() {
+        let expected = parse_answers("Part 1: 10\nPart 2: 2208\n").unwrap();
+        let record = |part1: &str, part2: Option<&str>| Record {
+            year: 2020,
+            day: 24,
+            part1: Some(String::from(part1)),
+            part2: part2.map(String::from),
+            elapsed: Duration::ZERO,
+        };
+
+        assert!(check(&expected, &record("10", Some("2208"))).is_empty());
+        assert!(check(&expected, &record("10", None)).is_empty());
+        let mismatches = check(&expected, &record("11", Some("2208")));
+        assert_eq!(
+            mismatches,
+            [Mismatch {
+                part: 1,
+                expected: String::from("10"),
+                actual: String::from("11"),
+            }]
+        );
+        assert_eq!(mismatches[0].to_string(), "part 1 answered 11, expected 10");
+    }
+
+    #[test]
+    fn tallies_statuses() {
+        let mut tally = Tally::default();
+        tally.add(&Status::Passed);
+        tally.add(&Status::Passed);
+        tally.add(&Status::Unchecked);
+        tally.add(&Status::Failed(Vec::new()));
+        assert_eq!(tally.days(), 4);
+        assert_eq!(tally.to_string(), "2 passed, 1 failed, 1 unchecked");
+    }
+}
@@ -0,0 +1,22 @@
+// Swaps the global allocator for every binary linking common, chosen with the
+// mimalloc or jemalloc feature. Days forward the features, so one day can be
+// built with each and timed against the system allocator. When both are on,
+// as with --all-features, mimalloc wins.
+
+#[cfg(feature = "mimalloc")]
+#[global_allocator]
+static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+#[cfg(all(feature = "jemalloc", not(feature = "mimalloc")))]
+#[global_allocator]
+static GLOBAL: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
+
+// For tagging timings, since they only compare between builds with the same
+// allocator
+pub const NAME: &str = if cfg!(feature = "mimalloc") {
+    "mimalloc"
+} else if cfg!(feature = "jemalloc") {
+    "jemalloc"
+} else {
+    "system"
+};
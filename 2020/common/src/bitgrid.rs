@@ -0,0 +1,347 @@
+use std::ops::{BitAndAssign, BitOrAssign, BitXorAssign, Not};
+
+const WORD_BITS: usize = 64;
+
+// A fixed-size grid of bits, stored row by row with each row padded out to a
+// whole number of u64 words. Bits past the right edge are always kept clear so
+// that counts and comparisons never see them.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct BitGrid2D {
+    width: usize,
+    height: usize,
+    words_per_row: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid2D {
+    pub fn new(width: usize, height: usize) -> Self {
+        let words_per_row = width.div_ceil(WORD_BITS);
+        Self {
+            width,
+            height,
+            words_per_row,
+            words: vec![0; words_per_row * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn get_index(&self, x: usize, y: usize) -> (usize, u64) {
+        assert!(
+            x < self.width && y < self.height,
+            "({}, {}) is outside a {}x{} grid",
+            x,
+            y,
+            self.width,
+            self.height
+        );
+        (y * self.words_per_row + x / WORD_BITS, 1 << (x % WORD_BITS))
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        let (index, bit) = self.get_index(x, y);
+        self.words[index] & bit != 0
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: bool) {
+        let (index, bit) = self.get_index(x, y);
+        if value {
+            self.words[index] |= bit;
+        } else {
+            self.words[index] &= !bit;
+        }
+    }
+
+    // Sets the bit, returning whether it was previously clear, like
+    // HashSet::insert
+    pub fn insert(&mut self, x: usize, y: usize) -> bool {
+        let (index, bit) = self.get_index(x, y);
+        let was_clear = self.words[index] & bit == 0;
+        self.words[index] |= bit;
+        was_clear
+    }
+
+    pub fn clear(&mut self) {
+        self.words.iter_mut().for_each(|word| *word = 0);
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    // Every set bit as (x, y), row by row
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.words
+            .iter()
+            .enumerate()
+            .flat_map(move |(index, word)| {
+                let y = index / self.words_per_row;
+                let base = (index % self.words_per_row) * WORD_BITS;
+                let mut word = *word;
+                std::iter::from_fn(move || {
+                    if word == 0 {
+                        return None;
+                    }
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    Some((base + bit, y))
+                })
+            })
+    }
+
+    fn get_row_mut(&mut self, y: usize) -> &mut [u64] {
+        &mut self.words[y * self.words_per_row..(y + 1) * self.words_per_row]
+    }
+
+    // Clears the padding bits in the last word of every row
+    fn mask_padding(&mut self) {
+        let used = self.width % WORD_BITS;
+        if used == 0 {
+            return;
+        }
+        let mask = (1 << used) - 1;
+        for y in 0..self.height {
+            let row = self.get_row_mut(y);
+            *row.last_mut().unwrap() &= mask;
+        }
+    }
+
+    // The shifts move every bit n cells in the given direction. Bits pushed
+    // over the edge are lost and the cells left behind are cleared.
+    pub fn shift_up(&mut self, n: usize) {
+        let n = n.min(self.height) * self.words_per_row;
+        self.words.drain(..n);
+        self.words.resize(self.words_per_row * self.height, 0);
+    }
+
+    pub fn shift_down(&mut self, n: usize) {
+        let n = n.min(self.height) * self.words_per_row;
+        self.words.truncate(self.words.len() - n);
+        self.words.splice(0..0, std::iter::repeat_n(0, n));
+    }
+
+    // Towards x = 0
+    pub fn shift_left(&mut self, n: usize) {
+        let (word_shift, bit_shift) = (n / WORD_BITS, n % WORD_BITS);
+        let words_per_row = self.words_per_row;
+        for y in 0..self.height {
+            let row = self.get_row_mut(y);
+            // Reads only ever look at words at or past the one being written
+            for i in 0..words_per_row {
+                let get = |offset: usize| row.get(i + word_shift + offset).copied().unwrap_or(0);
+                let word = if bit_shift == 0 {
+                    get(0)
+                } else {
+                    (get(0) >> bit_shift) | (get(1) << (WORD_BITS - bit_shift))
+                };
+                row[i] = word;
+            }
+        }
+    }
+
+    // Away from x = 0
+    pub fn shift_right(&mut self, n: usize) {
+        let (word_shift, bit_shift) = (n / WORD_BITS, n % WORD_BITS);
+        let words_per_row = self.words_per_row;
+        for y in 0..self.height {
+            let row = self.get_row_mut(y);
+            // Reads only ever look at words at or before the one being written
+            for i in (0..words_per_row).rev() {
+                let get = |offset: usize| {
+                    i.checked_sub(word_shift + offset)
+                        .map_or(0, |index| row[index])
+                };
+                let word = if bit_shift == 0 {
+                    get(0)
+                } else {
+                    (get(0) << bit_shift) | (get(1) >> (WORD_BITS - bit_shift))
+                };
+                row[i] = word;
+            }
+        }
+        self.mask_padding();
+    }
+
+    fn check_size(&self, other: &BitGrid2D) {
+        assert!(
+            self.width == other.width && self.height == other.height,
+            "Can't combine a {}x{} grid with a {}x{} grid",
+            self.width,
+            self.height,
+            other.width,
+            other.height
+        );
+    }
+}
+
+impl BitAndAssign<&BitGrid2D> for BitGrid2D {
+    fn bitand_assign(&mut self, other: &BitGrid2D) {
+        self.check_size(other);
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word &= other;
+        }
+    }
+}
+
+impl BitOrAssign<&BitGrid2D> for BitGrid2D {
+    fn bitor_assign(&mut self, other: &BitGrid2D) {
+        self.check_size(other);
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word |= other;
+        }
+    }
+}
+
+impl BitXorAssign<&BitGrid2D> for BitGrid2D {
+    fn bitxor_assign(&mut self, other: &BitGrid2D) {
+        self.check_size(other);
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word ^= other;
+        }
+    }
+}
+
+impl Not for BitGrid2D {
+    type Output = BitGrid2D;
+
+    fn not(mut self) -> BitGrid2D {
+        self.words.iter_mut().for_each(|word| *word = !*word);
+        self.mask_padding();
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BitGrid2D;
+
+    fn from_rows(rows: &[&str]) -> BitGrid2D {
+        let mut grid = BitGrid2D::new(rows[0].len(), rows.len());
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                grid.set(x, y, c == '#');
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn shifts_drop_bits_at_edges() {
+        let mut grid = from_rows(&["#..#", ".#..", "...#"]);
+        grid.shift_left(1);
+        assert_eq!(grid, from_rows(&["..#.", "#...", "..#."]));
+        grid.shift_right(2);
+        assert_eq!(grid, from_rows(&["....", "..#.", "...."]));
+        grid.shift_up(1);
+        assert_eq!(grid, from_rows(&["..#.", "....", "...."]));
+        grid.shift_down(2);
+        assert_eq!(grid, from_rows(&["....", "....", "..#."]));
+        grid.shift_down(5);
+        assert!(grid.is_empty());
+    }
+
+    #[test]
+    fn shifts_cross_word_boundaries() {
+        let mut grid = BitGrid2D::new(150, 2);
+        grid.set(0, 0, true);
+        grid.set(63, 1, true);
+        grid.set(149, 1, true);
+
+        grid.shift_right(70);
+        assert_eq!(grid.iter().collect::<Vec<_>>(), vec![(70, 0), (133, 1)]);
+        grid.shift_left(64);
+        assert_eq!(grid.iter().collect::<Vec<_>>(), vec![(6, 0), (69, 1)]);
+        grid.shift_right(150);
+        assert!(grid.is_empty());
+    }
+
+    #[test]
+    fn logic_and_counts() {
+        let a = from_rows(&["##..", "#.#."]);
+        let b = from_rows(&[".##.", "..##"]);
+
+        let mut and = a.clone();
+        and &= &b;
+        assert_eq!(and, from_rows(&[".#..", "..#."]));
+
+        let mut or = a.clone();
+        or |= &b;
+        assert_eq!(or.count_ones(), 6);
+
+        let mut xor = a.clone();
+        xor ^= &b;
+        assert_eq!(xor, from_rows(&["#.#.", "#..#"]));
+
+        assert_eq!((!a).count_ones(), 4);
+    }
+
+    #[test]
+    fn insert_tracks_visits() {
+        let mut visited = BitGrid2D::new(100, 100);
+        assert!(visited.insert(99, 42));
+        assert!(!visited.insert(99, 42));
+        assert!(visited.get(99, 42));
+        assert_eq!(visited.count_ones(), 1);
+        visited.clear();
+        assert!(visited.is_empty());
+    }
+
+    #[test]
+    fn shifted_neighbors_run_life() {
+        // A blinker under Conway's rules, with neighbor counts read from the
+        // eight shifted copies of the grid
+        let mut grid = from_rows(&[".....", "..#..", "..#..", "..#..", "....."]);
+        for _ in 0..2 {
+            let mut neighbors = Vec::new();
+            for (dx, dy) in &[
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (-1, 0),
+                (1, 0),
+                (-1, 1),
+                (0, 1),
+                (1, 1),
+            ] {
+                let mut shifted = grid.clone();
+                match dx {
+                    -1 => shifted.shift_left(1),
+                    1 => shifted.shift_right(1),
+                    _ => (),
+                }
+                match dy {
+                    -1 => shifted.shift_up(1),
+                    1 => shifted.shift_down(1),
+                    _ => (),
+                }
+                neighbors.push(shifted);
+            }
+
+            let mut next = BitGrid2D::new(5, 5);
+            for y in 0..5 {
+                for x in 0..5 {
+                    let count = neighbors.iter().filter(|grid| grid.get(x, y)).count();
+                    next.set(x, y, count == 3 || (count == 2 && grid.get(x, y)));
+                }
+            }
+            grid = next;
+        }
+        assert_eq!(
+            grid,
+            from_rows(&[".....", "..#..", "..#..", "..#..", "....."])
+        );
+    }
+}
@@ -0,0 +1,242 @@
+use std::{collections::HashMap, fmt, hash::Hash};
+
+const NONE: usize = usize::MAX;
+
+struct Entry<K, V> {
+    key: K,
+    value: V,
+    // Neighbors in recency order, towards the most and least recently used
+    newer: usize,
+    older: usize,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Stats {
+    pub hits: usize,
+    pub misses: usize,
+    pub evictions: usize,
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let lookups = self.hits + self.misses;
+        let hit_rate = if lookups == 0 {
+            0.0
+        } else {
+            100.0 * self.hits as f64 / lookups as f64
+        };
+        write!(
+            f,
+            "{} hits, {} misses ({:.1}% hit rate), {} evictions",
+            self.hits, self.misses, hit_rate, self.evictions
+        )
+    }
+}
+
+// A map that holds at most capacity entries, dropping the least recently used
+// one to make room. Entries live in a slab and are threaded onto a doubly linked
+// list by index, so every operation is O(1).
+pub struct Lru<K, V> {
+    capacity: usize,
+    indices: HashMap<K, usize>,
+    entries: Vec<Entry<K, V>>,
+    newest: usize,
+    oldest: usize,
+    stats: Stats,
+}
+
+impl<K: Clone + Eq + Hash, V> Lru<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "Capacity must be positive");
+        Self {
+            capacity,
+            indices: HashMap::with_capacity(capacity),
+            entries: Vec::with_capacity(capacity),
+            newest: NONE,
+            oldest: NONE,
+            stats: Stats::default(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.indices.contains_key(key)
+    }
+
+    // Looks up key without counting towards the stats or refreshing it
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        self.indices
+            .get(key)
+            .map(|index| &self.entries[*index].value)
+    }
+
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        match self.indices.get(key) {
+            Some(index) => {
+                let index = *index;
+                self.stats.hits += 1;
+                self.unlink(index);
+                self.push_newest(index);
+                Some(&self.entries[index].value)
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    // Returns the entry that was evicted to make room, if any
+    pub fn insert(&mut self, key: K, value: V) -> Option<(K, V)> {
+        if let Some(index) = self.indices.get(&key) {
+            let index = *index;
+            self.entries[index].value = value;
+            self.unlink(index);
+            self.push_newest(index);
+            return None;
+        }
+
+        if self.entries.len() < self.capacity {
+            let index = self.entries.len();
+            self.indices.insert(key.clone(), index);
+            self.entries.push(Entry {
+                key,
+                value,
+                newer: NONE,
+                older: NONE,
+            });
+            self.push_newest(index);
+            return None;
+        }
+
+        // Reuse the oldest entry's slot for the new one
+        let index = self.oldest;
+        self.unlink(index);
+        self.indices.remove(&self.entries[index].key);
+        self.indices.insert(key.clone(), index);
+        let entry = &mut self.entries[index];
+        let evicted_key = std::mem::replace(&mut entry.key, key);
+        let evicted_value = std::mem::replace(&mut entry.value, value);
+        self.push_newest(index);
+        self.stats.evictions += 1;
+        Some((evicted_key, evicted_value))
+    }
+
+    fn unlink(&mut self, index: usize) {
+        let (newer, older) = (self.entries[index].newer, self.entries[index].older);
+        if newer == NONE {
+            self.newest = older;
+        } else {
+            self.entries[newer].older = older;
+        }
+        if older == NONE {
+            self.oldest = newer;
+        } else {
+            self.entries[older].newer = newer;
+        }
+    }
+
+    fn push_newest(&mut self, index: usize) {
+        self.entries[index].newer = NONE;
+        self.entries[index].older = self.newest;
+        if self.newest == NONE {
+            self.oldest = index;
+        } else {
+            self.entries[self.newest].newer = index;
+        }
+        self.newest = index;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Lru, Stats};
+    use crate::gen::Rng;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = Lru::new(2);
+        assert_eq!(cache.insert('a', 1), None);
+        assert_eq!(cache.insert('b', 2), None);
+        assert_eq!(cache.get(&'a'), Some(&1));
+        assert_eq!(cache.insert('c', 3), Some(('b', 2)));
+        assert_eq!(cache.get(&'b'), None);
+        assert_eq!(cache.insert('d', 4), Some(('a', 1)));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.peek(&'c'), Some(&3));
+        assert_eq!(
+            cache.stats(),
+            Stats {
+                hits: 1,
+                misses: 1,
+                evictions: 2
+            }
+        );
+    }
+
+    #[test]
+    fn replacing_refreshes_entry() {
+        let mut cache = Lru::new(2);
+        cache.insert("x", 1);
+        cache.insert("y", 2);
+        assert_eq!(cache.insert("x", 10), None);
+        assert_eq!(cache.insert("z", 3), Some(("y", 2)));
+        assert_eq!(cache.peek(&"x"), Some(&10));
+    }
+
+    #[test]
+    fn matches_model_under_churn() {
+        // Compare against a plain vector kept in recency order
+        let mut cache = Lru::new(5);
+        let mut model: Vec<(u64, u64)> = Vec::new();
+        let mut rng = Rng::new(12345);
+        for step in 0..2000 {
+            let key = rng.below(9);
+            if step % 3 == 0 {
+                let expected = model.iter().position(|(k, _)| *k == key).map(|position| {
+                    let entry = model.remove(position);
+                    model.push(entry);
+                    entry.1
+                });
+                assert_eq!(cache.get(&key).copied(), expected);
+            } else {
+                let evicted = cache.insert(key, step);
+                if let Some(position) = model.iter().position(|(k, _)| *k == key) {
+                    model.remove(position);
+                    assert_eq!(evicted, None);
+                } else if model.len() == 5 {
+                    assert_eq!(evicted, Some(model.remove(0)));
+                }
+                model.push((key, step));
+            }
+        }
+    }
+
+    #[test]
+    fn stats_display() {
+        let stats = Stats {
+            hits: 3,
+            misses: 1,
+            evictions: 0,
+        };
+        assert_eq!(
+            stats.to_string(),
+            "3 hits, 1 misses (75.0% hit rate), 0 evictions"
+        );
+    }
+}
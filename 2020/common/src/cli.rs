@@ -0,0 +1,255 @@
+use std::{
+    fmt::{self, Display},
+    process,
+    str::FromStr,
+    time::Instant,
+};
+
+use crate::{input, timing};
+
+// Which half of a puzzle to answer. Days take it as --part, with NAMES as the
+// possible values so that clap's value_t! can hand the text to from_str.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+    Both,
+}
+
+impl Part {
+    pub const NAMES: [&'static str; 3] = ["1", "2", "both"];
+
+    fn includes(self, number: usize) -> bool {
+        match self {
+            Self::One => number == 1,
+            Self::Two => number == 2,
+            Self::Both => true,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParsePartError(String);
+
+impl Display for ParsePartError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown part {}, expected 1, 2 or both", self.0)
+    }
+}
+
+impl FromStr for Part {
+    type Err = ParsePartError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "1" => Ok(Self::One),
+            "2" => Ok(Self::Two),
+            "both" => Ok(Self::Both),
+            _ => Err(ParsePartError(String::from(text))),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Answer {
+    pub part: usize,
+    pub value: String,
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Part {}: {}", self.part, self.value)
+    }
+}
+
+// Parses once and hands the result to each requested part. Each step runs in
+// its own timing scope (parse, part 1, part 2), so a day's --timing report
+// breaks them out.
+pub fn solve_parts<T, E, A, B>(
+    part: Part,
+    parse: impl FnOnce() -> Result<T, E>,
+    part1: impl FnOnce(&T) -> A,
+    part2: impl FnOnce(&T) -> B,
+) -> Result<Vec<Answer>, E>
+where
+    A: Display,
+    B: Display,
+{
+    let input = {
+        let _scope = crate::scope!("parse");
+        parse()?
+    };
+
+    let mut answers = Vec::new();
+    if part.includes(1) {
+        let _scope = crate::scope!("part 1");
+        answers.push(Answer {
+            part: 1,
+            value: part1(&input).to_string(),
+        });
+    }
+    if part.includes(2) {
+        let _scope = crate::scope!("part 2");
+        answers.push(Answer {
+            part: 2,
+            value: part2(&input).to_string(),
+        });
+    }
+    Ok(answers)
+}
+
+// The whole of a simple day's main: prints each answer on its own line, or
+// exits with a message if the input couldn't be read or parsed
+pub fn run_parts<T, E, A, B>(
+    part: Part,
+    parse: impl FnOnce() -> Result<T, E>,
+    part1: impl FnOnce(&T) -> A,
+    part2: impl FnOnce(&T) -> B,
+) where
+    E: Display,
+    A: Display,
+    B: Display,
+{
+    match solve_parts(part, parse, part1, part2) {
+        Ok(answers) => {
+            for answer in answers {
+                println!("{answer}");
+            }
+        }
+        Err(error) => {
+            eprintln!("Failed to load input: {error}");
+            process::exit(1);
+        }
+    }
+}
+
+// Like run_parts, but for text that may hold several %%-separated instances.
+// Each is parsed and solved in turn under an "Instance N" heading, with how
+// long it took on stderr. A lone instance prints just as run_parts would. An
+// instance that fails to parse doesn't stop the rest, but the exit status is
+// 1 once they've all run.
+//
+// With repeat, each instance is solved that many more times after the run
+// that prints its answers, which doubles as a warmup, and the spread of those
+// times goes to stderr instead.
+pub fn run_instances<T, E, A, B>(
+    part: Part,
+    repeat: Option<usize>,
+    text: &str,
+    parse: impl Fn(&str) -> Result<T, E>,
+    mut part1: impl FnMut(&T) -> A,
+    mut part2: impl FnMut(&T) -> B,
+) where
+    E: Display,
+    A: Display,
+    B: Display,
+{
+    let instances = input::split_instances(text);
+    if instances.len() == 1 && repeat.is_none() {
+        run_parts(part, || parse(&instances[0]), part1, part2);
+        return;
+    }
+
+    let mut failed = false;
+    for (index, instance) in instances.iter().enumerate() {
+        let _scope = crate::scope!("instance");
+        let (name, took) = if instances.len() == 1 {
+            (String::from("input"), String::from("Took"))
+        } else {
+            println!("Instance {}", index + 1);
+            (
+                format!("instance {}", index + 1),
+                format!("Instance {} took", index + 1),
+            )
+        };
+
+        let start = Instant::now();
+        match solve_parts(part, || parse(instance), &mut part1, &mut part2) {
+            Ok(answers) => {
+                for answer in answers {
+                    println!("{answer}");
+                }
+            }
+            Err(error) => {
+                eprintln!("Failed to load {name}: {error}");
+                failed = true;
+                continue;
+            }
+        }
+
+        match repeat {
+            Some(runs) => {
+                let summary = timing::measure(runs, || {
+                    solve_parts(part, || parse(instance), &mut part1, &mut part2)
+                });
+                eprintln!("{took} {summary}");
+            }
+            None => eprintln!("{} {:.2?}", took, start.elapsed()),
+        }
+    }
+    if failed {
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timing;
+
+    #[test]
+    fn parses_parts() {
+        for name in &Part::NAMES {
+            assert!(name.parse::<Part>().is_ok(), "{}", name);
+        }
+        assert_eq!("2".parse(), Ok(Part::Two));
+        assert_eq!(
+            "3".parse::<Part>().unwrap_err().to_string(),
+            "unknown part 3, expected 1, 2 or both"
+        );
+    }
+
+    #[test]
+    fn solves_requested_parts_from_one_parse() {
+        let mut parses = 0;
+        let mut solve = |part| {
+            solve_parts(
+                part,
+                || -> Result<Vec<u32>, String> {
+                    parses += 1;
+                    Ok(vec![1, 2, 3])
+                },
+                |numbers| numbers.iter().sum::<u32>(),
+                |numbers| numbers.iter().product::<u32>(),
+            )
+            .unwrap()
+        };
+
+        let answers: Vec<String> = solve(Part::Both).iter().map(ToString::to_string).collect();
+        assert_eq!(answers, ["Part 1: 6", "Part 2: 6"]);
+        assert_eq!(solve(Part::One).len(), 1);
+        assert_eq!(solve(Part::Two)[0].part, 2);
+        assert_eq!(parses, 3);
+    }
+
+    #[test]
+    fn parse_errors_skip_the_parts() {
+        let result = solve_parts(
+            Part::Both,
+            || "bad".parse::<u32>(),
+            |_| -> u32 { panic!("Part 1 ran without input") },
+            |_| -> u32 { panic!("Part 2 ran without input") },
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn steps_are_timed() {
+        timing::reset();
+        solve_parts(Part::Both, || Ok::<_, String>(()), |_| 1, |_| 2).unwrap();
+        let report = timing::report();
+        for name in &["parse", "part 1", "part 2"] {
+            assert!(report.contains(name), "{}", report);
+        }
+    }
+}
@@ -0,0 +1,185 @@
+// Both iterators yield selections in lexicographic order of the item positions,
+// so sorted input gives sorted output
+
+pub struct Combinations<'a, T> {
+    items: &'a [T],
+    indices: Vec<usize>,
+    done: bool,
+}
+
+impl<T: Clone> Iterator for Combinations<'_, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let combination = self
+            .indices
+            .iter()
+            .map(|index| self.items[*index].clone())
+            .collect();
+
+        // Find the rightmost index that can still move right, move it, and
+        // pack everything after it up against it
+        let n = self.items.len();
+        let k = self.indices.len();
+        match (0..k).rev().find(|i| self.indices[*i] != i + n - k) {
+            Some(i) => {
+                self.indices[i] += 1;
+                for j in i + 1..k {
+                    self.indices[j] = self.indices[j - 1] + 1;
+                }
+            }
+            None => self.done = true,
+        }
+
+        Some(combination)
+    }
+}
+
+// Every way of choosing k of the items, ignoring order
+pub fn combinations<T: Clone>(items: &[T], k: usize) -> Combinations<'_, T> {
+    Combinations {
+        items,
+        indices: (0..k).collect(),
+        done: k > items.len(),
+    }
+}
+
+pub struct Permutations<'a, T> {
+    items: &'a [T],
+    indices: Vec<usize>,
+    cycles: Vec<usize>,
+    k: usize,
+    first: bool,
+    done: bool,
+}
+
+impl<T: Clone> Permutations<'_, T> {
+    fn get_current(&self) -> Vec<T> {
+        self.indices[..self.k]
+            .iter()
+            .map(|index| self.items[*index].clone())
+            .collect()
+    }
+}
+
+impl<T: Clone> Iterator for Permutations<'_, T> {
+    type Item = Vec<T>;
+
+    // Follows the cycle-counting scheme from Python's itertools: cycles[i]
+    // counts how many more values position i can take before it resets
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if self.first {
+            self.first = false;
+            return Some(self.get_current());
+        }
+
+        let n = self.items.len();
+        for i in (0..self.k).rev() {
+            self.cycles[i] -= 1;
+            if self.cycles[i] == 0 {
+                self.indices[i..].rotate_left(1);
+                self.cycles[i] = n - i;
+            } else {
+                let j = n - self.cycles[i];
+                self.indices.swap(i, j);
+                return Some(self.get_current());
+            }
+        }
+
+        self.done = true;
+        None
+    }
+}
+
+// Every ordered arrangement of k of the items
+pub fn permutations<T: Clone>(items: &[T], k: usize) -> Permutations<'_, T> {
+    let n = items.len();
+    Permutations {
+        items,
+        indices: (0..n).collect(),
+        cycles: (n.saturating_sub(k) + 1..=n).rev().collect(),
+        k,
+        first: true,
+        done: k > n,
+    }
+}
+
+// Calls visit with every ordering of items, rearranging them in place with
+// Heap's algorithm so that consecutive orderings differ by a single swap
+pub fn for_each_permutation<T, F>(items: &mut [T], mut visit: F)
+where
+    F: FnMut(&[T]),
+{
+    let n = items.len();
+    let mut counters = vec![0; n];
+    visit(items);
+
+    let mut i = 1;
+    while i < n {
+        if counters[i] < i {
+            if i % 2 == 0 {
+                items.swap(0, i);
+            } else {
+                items.swap(counters[i], i);
+            }
+            visit(items);
+            counters[i] += 1;
+            i = 1;
+        } else {
+            counters[i] = 0;
+            i += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn combinations_in_order() {
+        let all: Vec<Vec<i32>> = combinations(&[1, 2, 3, 4], 2).collect();
+        assert_eq!(
+            all,
+            vec![
+                vec![1, 2],
+                vec![1, 3],
+                vec![1, 4],
+                vec![2, 3],
+                vec![2, 4],
+                vec![3, 4]
+            ]
+        );
+        assert_eq!(combinations(&[1, 2, 3], 0).count(), 1);
+        assert_eq!(combinations(&[1, 2, 3], 4).count(), 0);
+        assert_eq!(combinations(&(0..10).collect::<Vec<_>>(), 4).count(), 210);
+    }
+
+    #[test]
+    fn permutations_in_order() {
+        let all: Vec<String> = permutations(&['a', 'b', 'c'], 2)
+            .map(|permutation| permutation.into_iter().collect())
+            .collect();
+        assert_eq!(all, vec!["ab", "ac", "ba", "bc", "ca", "cb"]);
+        assert_eq!(permutations(&[1, 2, 3, 4], 4).count(), 24);
+        assert_eq!(permutations(&[1, 2, 3], 0).count(), 1);
+        assert_eq!(permutations(&[1, 2], 3).count(), 0);
+    }
+
+    #[test]
+    fn heap_visits_every_ordering_once() {
+        let mut items = [1, 2, 3, 4, 5];
+        let mut seen = HashSet::new();
+        for_each_permutation(&mut items, |permutation| {
+            assert!(seen.insert(permutation.to_vec()));
+        });
+        assert_eq!(seen.len(), 120);
+    }
+}
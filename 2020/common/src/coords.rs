@@ -0,0 +1,136 @@
+use crate::hex::Cube;
+
+// A point on a square grid as (x, y)
+pub type Point = (i64, i64);
+
+// Steps between points when moving only along rows and columns
+pub fn manhattan(a: Point, b: Point) -> i64 {
+    (a.0 - b.0).abs() + (a.1 - b.1).abs()
+}
+
+// Steps between points when diagonal moves count as one step too
+pub fn chebyshev(a: Point, b: Point) -> i64 {
+    (a.0 - b.0).abs().max((a.1 - b.1).abs())
+}
+
+pub fn hex_distance(a: Cube, b: Cube) -> i64 {
+    a.distance(b)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Metric {
+    Manhattan,
+    Chebyshev,
+}
+
+impl Metric {
+    pub fn distance(self, a: Point, b: Point) -> i64 {
+        match self {
+            Self::Manhattan => manhattan(a, b),
+            Self::Chebyshev => chebyshev(a, b),
+        }
+    }
+}
+
+// Every point no further than radius from center under the metric, row by row
+// from the smallest y, each row from the smallest x
+pub fn points_within(center: Point, radius: i64, metric: Metric) -> impl Iterator<Item = Point> {
+    (-radius..=radius).flat_map(move |dy| {
+        let reach = match metric {
+            Metric::Manhattan => radius - dy.abs(),
+            Metric::Chebyshev => radius,
+        };
+        (-reach..=reach).map(move |dx| (center.0 + dx, center.1 + dy))
+    })
+}
+
+// Every hex no further than radius from center, ordered by x and then by y
+pub fn hexes_within(center: Cube, radius: i64) -> impl Iterator<Item = Cube> {
+    (-radius..=radius).flat_map(move |dx| {
+        ((-radius).max(-dx - radius)..=radius.min(-dx + radius))
+            .map(move |dy| center + Cube::new(dx, dy, -dx - dy))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen::Rng;
+    use std::convert::TryFrom;
+
+    fn random_offset(rng: &mut Rng) -> i64 {
+        i64::try_from(rng.below(201)).unwrap() - 100
+    }
+
+    fn random_point(rng: &mut Rng) -> Point {
+        (random_offset(rng), random_offset(rng))
+    }
+
+    fn random_hex(rng: &mut Rng) -> Cube {
+        let (x, y) = random_point(rng);
+        Cube::new(x, y, -x - y)
+    }
+
+    #[test]
+    fn distances_are_metrics() {
+        let mut rng = Rng::new(491);
+        for _ in 0..1000 {
+            let (a, b, c) = (
+                random_point(&mut rng),
+                random_point(&mut rng),
+                random_point(&mut rng),
+            );
+            for metric in &[Metric::Manhattan, Metric::Chebyshev] {
+                let distance = |p, q| metric.distance(p, q);
+                assert_eq!(distance(a, a), 0);
+                assert_eq!(distance(a, b), distance(b, a));
+                assert!(distance(a, c) <= distance(a, b) + distance(b, c));
+            }
+
+            let (a, b, c) = (
+                random_hex(&mut rng),
+                random_hex(&mut rng),
+                random_hex(&mut rng),
+            );
+            assert_eq!(hex_distance(a, a), 0);
+            assert_eq!(hex_distance(a, b), hex_distance(b, a));
+            assert!(hex_distance(a, c) <= hex_distance(a, b) + hex_distance(b, c));
+        }
+    }
+
+    #[test]
+    fn measures_known_distances() {
+        assert_eq!(manhattan((0, 0), (17, -8)), 25);
+        assert_eq!(chebyshev((0, 0), (17, -8)), 17);
+        assert_eq!(hex_distance(Cube::ORIGIN, Cube::new(3, -1, -2)), 3);
+    }
+
+    #[test]
+    fn points_within_fill_the_radius() {
+        let center = (5, -3);
+        for radius in 0..5 {
+            for (metric, count) in &[
+                (Metric::Manhattan, 2 * radius * (radius + 1) + 1),
+                (Metric::Chebyshev, (2 * radius + 1) * (2 * radius + 1)),
+            ] {
+                let points: Vec<Point> = points_within(center, radius, *metric).collect();
+                assert_eq!(points.len(), usize::try_from(*count).unwrap());
+                assert!(points
+                    .iter()
+                    .all(|point| metric.distance(center, *point) <= radius));
+            }
+
+            let center = Cube::new(2, -5, 3);
+            let hexes: Vec<Cube> = hexes_within(center, radius).collect();
+            assert_eq!(
+                hexes.len(),
+                usize::try_from(3 * radius * (radius + 1) + 1).unwrap()
+            );
+            assert!(hexes.iter().all(|hex| hex_distance(center, *hex) <= radius));
+        }
+        assert_eq!(
+            points_within((0, 0), 1, Metric::Manhattan).collect::<Vec<_>>(),
+            [(0, -1), (-1, 0), (0, 0), (1, 0), (0, 1)]
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    iter::FromIterator,
+    ops::{Add, Sub},
+};
+
+// A multiset: how many times each item has been added. Items whose count drops
+// to zero are removed entirely.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Counter<T: Eq + Hash> {
+    counts: HashMap<T, usize>,
+}
+
+impl<T: Eq + Hash> Counter<T> {
+    pub fn new() -> Self {
+        Self {
+            counts: HashMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, item: T) {
+        self.add_count(item, 1);
+    }
+
+    pub fn add_count(&mut self, item: T, count: usize) {
+        if count > 0 {
+            *self.counts.entry(item).or_insert(0) += count;
+        }
+    }
+
+    pub fn add_all<I: IntoIterator<Item = T>>(&mut self, items: I) {
+        for item in items {
+            self.add(item);
+        }
+    }
+
+    pub fn get(&self, item: &T) -> usize {
+        self.counts.get(item).copied().unwrap_or(0)
+    }
+
+    // Number of distinct items
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    // Sum of all counts
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    // In no particular order, since it follows the hash map; use sorted for
+    // anything that gets printed or compared between runs
+    pub fn iter(&self) -> impl Iterator<Item = (&T, usize)> {
+        self.counts.iter().map(|(item, count)| (item, *count))
+    }
+}
+
+impl<T: Eq + Hash + Ord + Clone> Counter<T> {
+    // The n items with the highest counts, highest first. Equal counts are
+    // ordered by item so the result doesn't depend on hashing.
+    pub fn most_common(&self, n: usize) -> Vec<(T, usize)> {
+        let mut entries: Vec<(T, usize)> = self
+            .counts
+            .iter()
+            .map(|(item, count)| (item.clone(), *count))
+            .collect();
+        entries.sort_by(|(a_item, a_count), (b_item, b_count)| {
+            b_count.cmp(a_count).then_with(|| a_item.cmp(b_item))
+        });
+        entries.truncate(n);
+        entries
+    }
+
+    // Every item with its count, in ascending order of item
+    pub fn sorted(&self) -> Vec<(T, usize)> {
+        let mut entries: Vec<(T, usize)> = self
+            .counts
+            .iter()
+            .map(|(item, count)| (item.clone(), *count))
+            .collect();
+        entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+        entries
+    }
+}
+
+impl<T: Eq + Hash> Default for Counter<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Eq + Hash> FromIterator<T> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(items: I) -> Self {
+        let mut counter = Counter::new();
+        counter.add_all(items);
+        counter
+    }
+}
+
+impl<T: Eq + Hash> Extend<T> for Counter<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, items: I) {
+        self.add_all(items);
+    }
+}
+
+impl<T: Eq + Hash + Clone> Add for &Counter<T> {
+    type Output = Counter<T>;
+
+    fn add(self, other: &Counter<T>) -> Counter<T> {
+        let mut sum = self.clone();
+        for (item, count) in other.iter() {
+            sum.add_count(item.clone(), count);
+        }
+        sum
+    }
+}
+
+// Counts can't go negative, so anything other has at least as many of is
+// dropped
+impl<T: Eq + Hash + Clone> Sub for &Counter<T> {
+    type Output = Counter<T>;
+
+    fn sub(self, other: &Counter<T>) -> Counter<T> {
+        let mut difference = Counter::new();
+        for (item, count) in self.iter() {
+            difference.add_count(item.clone(), count.saturating_sub(other.get(item)));
+        }
+        difference
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Counter;
+
+    #[test]
+    fn counts_characters() {
+        let counter: Counter<char> = "abbcccdddd".chars().collect();
+        assert_eq!(counter.get(&'c'), 3);
+        assert_eq!(counter.get(&'z'), 0);
+        assert_eq!(counter.len(), 4);
+        assert_eq!(counter.total(), 10);
+        assert_eq!(counter.most_common(2), vec![('d', 4), ('c', 3)]);
+    }
+
+    #[test]
+    fn sorted_orders_by_item() {
+        let counter: Counter<u32> = vec![30, 10, 20, 10, 40, 30, 10].into_iter().collect();
+        assert_eq!(counter.sorted(), vec![(10, 3), (20, 1), (30, 2), (40, 1)]);
+    }
+
+    #[test]
+    fn most_common_breaks_ties_by_item() {
+        let counter: Counter<&str> = vec!["b", "a", "c", "a", "b"].into_iter().collect();
+        assert_eq!(counter.most_common(10), vec![("a", 2), ("b", 2), ("c", 1)]);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a: Counter<char> = "aab".chars().collect();
+        let b: Counter<char> = "abbc".chars().collect();
+
+        let sum = &a + &b;
+        assert_eq!(sum.get(&'a'), 3);
+        assert_eq!(sum.get(&'b'), 3);
+        assert_eq!(sum.get(&'c'), 1);
+
+        let difference = &a - &b;
+        assert_eq!(difference.get(&'a'), 1);
+        assert_eq!(difference.len(), 1);
+    }
+}
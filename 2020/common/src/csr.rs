@@ -0,0 +1,121 @@
+use std::iter::FromIterator;
+
+// Compressed sparse rows: the neighbors of every node sit back to back in one
+// vector, with node n's run at targets[offsets[n]..offsets[n + 1]], so large
+// adjacency lists take two allocations instead of one per node. Nodes are
+// numbered from 0 and keep their neighbors in the order they were given.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Csr<T> {
+    offsets: Vec<usize>,
+    targets: Vec<T>,
+}
+
+impl<T> Csr<T> {
+    pub fn new() -> Self {
+        Self {
+            offsets: vec![0],
+            targets: Vec::new(),
+        }
+    }
+
+    // Edges are (from, neighbor) pairs in any order. Nodes without edges, up
+    // to node_count, are still given an empty run.
+    pub fn from_edges<I>(node_count: usize, edges: I) -> Self
+    where
+        I: IntoIterator<Item = (usize, T)>,
+    {
+        let mut edges: Vec<(usize, T)> = edges.into_iter().collect();
+        // Stable, so each node's neighbors stay in the order they came in
+        edges.sort_by_key(|(from, _)| *from);
+
+        let node_count = edges
+            .last()
+            .map_or(node_count, |(from, _)| node_count.max(from + 1));
+        let mut offsets = vec![0; node_count + 1];
+        for (from, _) in &edges {
+            offsets[from + 1] += 1;
+        }
+        for node in 0..node_count {
+            offsets[node + 1] += offsets[node];
+        }
+        Self {
+            offsets,
+            targets: edges.into_iter().map(|(_, target)| target).collect(),
+        }
+    }
+
+    // Adds the next node along with its neighbors
+    pub fn push_node<I>(&mut self, neighbors: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        self.targets.extend(neighbors);
+        self.offsets.push(self.targets.len());
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.targets.len()
+    }
+
+    pub fn neighbors(&self, node: usize) -> &[T] {
+        &self.targets[self.offsets[node]..self.offsets[node + 1]]
+    }
+
+    // Each node's neighbors, in node order
+    pub fn iter(&self) -> impl Iterator<Item = &[T]> {
+        self.offsets
+            .windows(2)
+            .map(move |run| &self.targets[run[0]..run[1]])
+    }
+}
+
+impl<T> Default for Csr<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Collects one list of neighbors per node
+impl<T, L> FromIterator<L> for Csr<T>
+where
+    L: IntoIterator<Item = T>,
+{
+    fn from_iter<I: IntoIterator<Item = L>>(lists: I) -> Self {
+        let mut csr = Self::new();
+        for neighbors in lists {
+            csr.push_node(neighbors);
+        }
+        csr
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Csr;
+
+    #[test]
+    fn edges_are_grouped_by_node() {
+        let csr = Csr::from_edges(5, vec![(2, 'c'), (0, 'a'), (2, 'b'), (0, 'd')]);
+        assert_eq!(csr.node_count(), 5);
+        assert_eq!(csr.edge_count(), 4);
+        assert_eq!(csr.neighbors(0), ['a', 'd']);
+        assert!(csr.neighbors(1).is_empty());
+        assert_eq!(csr.neighbors(2), ['c', 'b']);
+        assert!(csr.neighbors(4).is_empty());
+    }
+
+    #[test]
+    fn lists_collect_in_node_order() {
+        let csr: Csr<usize> = vec![vec![1, 2], vec![], vec![0]].into_iter().collect();
+        assert_eq!(csr.node_count(), 3);
+        assert_eq!(
+            csr.iter().collect::<Vec<_>>(),
+            [&[1_usize, 2][..], &[], &[0]]
+        );
+        assert_eq!(csr, Csr::from_edges(3, vec![(0, 1), (0, 2), (2, 0)]));
+    }
+}
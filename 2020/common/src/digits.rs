@@ -0,0 +1,109 @@
+// The digits of n in the given base, most significant first. Zero has a single
+// zero digit.
+pub fn digits(mut n: u64, base: u64) -> Vec<u64> {
+    assert!(base >= 2, "Base must be at least 2");
+    let mut digits = Vec::new();
+    loop {
+        digits.push(n % base);
+        n /= base;
+        if n == 0 {
+            break;
+        }
+    }
+    digits.reverse();
+    digits
+}
+
+// The inverse of digits. Returns None if a digit is out of range for the base
+// or the value doesn't fit in a u64.
+pub fn from_digits(digits: &[u64], base: u64) -> Option<u64> {
+    assert!(base >= 2, "Base must be at least 2");
+    digits.iter().try_fold(0_u64, |value, digit| {
+        if *digit >= base {
+            return None;
+        }
+        value.checked_mul(base)?.checked_add(*digit)
+    })
+}
+
+// Just the lowest set bit of value, or 0 if there isn't one
+pub fn isolate_lowest_set(value: u64) -> u64 {
+    value & value.wrapping_neg()
+}
+
+// The index of each set bit, lowest first
+pub fn set_bits(mut mask: u64) -> impl Iterator<Item = u32> {
+    std::iter::from_fn(move || {
+        if mask == 0 {
+            return None;
+        }
+        let bit = mask.trailing_zeros();
+        mask &= mask - 1;
+        Some(bit)
+    })
+}
+
+// Every value whose set bits are a subset of mask's, in increasing order and
+// starting from 0, so a mask with k bits set yields 2^k values
+pub fn iterate_subsets(mask: u64) -> impl Iterator<Item = u64> {
+    let mut next = Some(0_u64);
+    std::iter::from_fn(move || {
+        let subset = next?;
+        // Subtracting the mask borrows through the gaps between its bits,
+        // which counts upwards through just those bits
+        next = if subset == mask {
+            None
+        } else {
+            Some(subset.wrapping_sub(mask) & mask)
+        };
+        Some(subset)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digits_round_trip() {
+        assert_eq!(digits(1234, 10), vec![1, 2, 3, 4]);
+        assert_eq!(digits(0, 10), vec![0]);
+        assert_eq!(digits(10, 2), vec![1, 0, 1, 0]);
+        assert_eq!(digits(255, 16), vec![15, 15]);
+        for n in &[0, 7, 1_000_000_007, u64::MAX] {
+            for base in &[2, 3, 10, 36] {
+                assert_eq!(from_digits(&digits(*n, *base), *base), Some(*n));
+            }
+        }
+    }
+
+    #[test]
+    fn from_digits_rejects_bad_input() {
+        assert_eq!(from_digits(&[1, 10], 10), None);
+        assert_eq!(from_digits(&[1; 21], 10), None);
+        assert_eq!(from_digits(&[], 10), Some(0));
+    }
+
+    #[test]
+    fn bit_helpers() {
+        assert_eq!(isolate_lowest_set(0b1011_0100), 0b100);
+        assert_eq!(isolate_lowest_set(0), 0);
+        assert_eq!(isolate_lowest_set(1 << 63), 1 << 63);
+        assert_eq!(set_bits(0b1010_0001).collect::<Vec<_>>(), vec![0, 5, 7]);
+    }
+
+    #[test]
+    fn floating_address_subsets() {
+        // 2020 day 14 part 2's sample: address 42 with mask 000...X1001X
+        let floating = (1 << 5) | 1;
+        let base = (42 | 0b10010) & !floating;
+        let addresses: Vec<u64> = iterate_subsets(floating)
+            .map(|subset| base | subset)
+            .collect();
+        assert_eq!(addresses, vec![26, 27, 58, 59]);
+
+        assert_eq!(iterate_subsets(0).collect::<Vec<_>>(), vec![0]);
+        assert_eq!(iterate_subsets(0b1111).count(), 16);
+        assert_eq!(iterate_subsets(u64::MAX).nth(5), Some(5));
+    }
+}
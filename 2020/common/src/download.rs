@@ -0,0 +1,153 @@
+use std::{
+    env, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+// The session cookie from a logged-in browser, which the site needs before it
+// hands out anyone's input. The environment wins over the config file.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+// The site asks automated requests to say where they come from
+const USER_AGENT: &str = "github.com/dstoza/advent-2017 via curl";
+
+pub fn input_url(year: u16, day: u8) -> String {
+    format!("https://adventofcode.com/{}/day/{}/input", year, day)
+}
+
+// Downloads live in inputs/ at the root of the repository, one directory per
+// year, out of git's sight
+pub fn cache_path(year: u16, day: u8) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .ancestors()
+        .nth(2)
+        .unwrap()
+        .join("inputs")
+        .join(year.to_string())
+        .join(format!("day-{:02}.txt", day))
+}
+
+// $XDG_CONFIG_HOME/aoc/session, or ~/.config/aoc/session without it
+fn config_path() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+        .map(|config| config.join("aoc").join("session"))
+}
+
+pub fn session_token() -> Option<String> {
+    env::var(SESSION_VAR)
+        .ok()
+        .or_else(|| fs::read_to_string(config_path()?).ok())
+        .map(|token| String::from(token.trim()))
+        .filter(|token| !token.is_empty())
+}
+
+// The year and day a binary such as day-11 solves. Every day here is 2020's,
+// which is the directory this crate sits in.
+pub fn puzzle_for_binary(binary: &str) -> Option<(u16, u8)> {
+    let year = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()?
+        .file_name()?
+        .to_str()?
+        .parse()
+        .ok()?;
+    let day = binary.strip_prefix("day-")?.parse().ok()?;
+    Some((year, day))
+}
+
+fn download(url: &str, token: &str, path: &Path) -> io::Result<()> {
+    let mut curl = Command::new("curl")
+        .args(["--fail", "--silent", "--show-error", "--location"])
+        .args(["--user-agent", USER_AGENT])
+        // The cookie goes in through stdin so it never shows up in ps
+        .args(["--header", "@-", "--output"])
+        .arg(path)
+        .arg(url)
+        .stdin(Stdio::piped())
+        .spawn()?;
+    writeln!(curl.stdin.take().unwrap(), "Cookie: session={}", token)?;
+    if curl.wait()?.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("Failed to download {}", url)))
+    }
+}
+
+// Returns the cached input for the puzzle, downloading it first if this is the
+// first time it's been asked for
+pub fn fetch(year: u16, day: u8) -> io::Result<PathBuf> {
+    let path = cache_path(year, day);
+    if path.exists() {
+        return Ok(path);
+    }
+
+    let token = session_token().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "No session token in {} or {}",
+                SESSION_VAR,
+                config_path().map_or_else(String::new, |path| path.display().to_string())
+            ),
+        )
+    })?;
+    fs::create_dir_all(path.parent().unwrap())?;
+    // Written aside and renamed, so a failed download never looks cached
+    let partial = path.with_extension("part");
+    let result = download(&input_url(year, day), &token, &partial);
+    if result.is_err() {
+        let _ = fs::remove_file(&partial);
+    }
+    result?;
+    fs::rename(&partial, &path)?;
+    Ok(path)
+}
+
+// Where a day binary should read a missing input.txt from: its cached download,
+// when a session token is set up to fetch one. Anything else, including every
+// file that exists, is left for the caller to open as given.
+pub fn stand_in(filename: &str) -> io::Result<Option<PathBuf>> {
+    let path = Path::new(filename);
+    if path.exists() || path.file_name().is_none_or(|name| name != "input.txt") {
+        return Ok(None);
+    }
+    let puzzle = env::current_exe()
+        .ok()
+        .and_then(|binary| puzzle_for_binary(binary.file_stem()?.to_str()?));
+    match puzzle {
+        Some((year, day)) if cache_path(year, day).exists() || session_token().is_some() => {
+            eprintln!(
+                "{} is missing, using the input for {} day {}",
+                filename, year, day
+            );
+            fetch(year, day).map(Some)
+        }
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locates_puzzles() {
+        assert_eq!(
+            input_url(2020, 7),
+            "https://adventofcode.com/2020/day/7/input"
+        );
+        assert!(cache_path(2020, 7).ends_with("inputs/2020/day-07.txt"));
+        assert_eq!(puzzle_for_binary("day-11"), Some((2020, 11)));
+        assert_eq!(puzzle_for_binary("aoc"), None);
+    }
+
+    #[test]
+    fn existing_and_other_files_are_left_alone() {
+        assert!(stand_in("src/download.rs").unwrap().is_none());
+        assert!(stand_in("no such file").unwrap().is_none());
+        // The test binary isn't a day, so there's nothing to fetch for
+        assert!(stand_in("missing/input.txt").unwrap().is_none());
+    }
+}
@@ -0,0 +1,84 @@
+// Checks a puzzle's worked example against both parts in one go:
+//
+//     assert_example! {
+//         name: sample,
+//         input: include_str!("../sample.txt"),
+//         part1: get_basic_score => 306,
+//         part2: get_recursive_score => 291,
+//         bench,
+//     }
+//
+// expands to a module called sample with part_1 and part_2 tests, each calling
+// its function with the input and comparing against the expected answer. The
+// optional bench adds an ignored benchmark of both parts. It's only compiled
+// with the calling crate's nightly feature, under which the crate should have
+// #![cfg_attr(feature = "nightly", feature(test))] and extern crate test.
+#[macro_export]
+macro_rules! assert_example {
+    (
+        name: $name:ident,
+        input: $input:expr,
+        part1: $part1:path => $expected1:expr,
+        part2: $part2:path => $expected2:expr $(,)?
+    ) => {
+        mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            $crate::assert_example!(@tests $input, $part1 => $expected1, $part2 => $expected2);
+        }
+    };
+    (
+        name: $name:ident,
+        input: $input:expr,
+        part1: $part1:path => $expected1:expr,
+        part2: $part2:path => $expected2:expr,
+        bench $(,)?
+    ) => {
+        mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            $crate::assert_example!(@tests $input, $part1 => $expected1, $part2 => $expected2);
+
+            #[cfg(feature = "nightly")]
+            #[bench]
+            #[ignore = "run with --ignored to benchmark the example"]
+            fn both_parts(bencher: &mut test::Bencher) {
+                bencher.iter(|| {
+                    test::black_box($part1($input));
+                    test::black_box($part2($input));
+                });
+            }
+        }
+    };
+    (@tests $input:expr, $part1:path => $expected1:expr, $part2:path => $expected2:expr) => {
+        #[test]
+        fn part_1() {
+            assert_eq!($part1($input), $expected1);
+        }
+
+        #[test]
+        fn part_2() {
+            assert_eq!($part2($input), $expected2);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    fn count_lines(input: &str) -> usize {
+        input.lines().count()
+    }
+
+    fn sum_numbers(input: &str) -> i64 {
+        input.lines().map(|line| line.parse::<i64>().unwrap()).sum()
+    }
+
+    crate::assert_example! {
+        name: numbers,
+        input: "1\n-2\n30\n",
+        part1: count_lines => 3,
+        part2: sum_numbers => 29,
+    }
+}
@@ -0,0 +1,37 @@
+use std::{env, path::Path};
+
+// Finds a file for a test or bench by trying the relative path against the
+// crate being built and each directory above it, so that both
+// "2020/day-11/sample.txt" and "day-11/sample.txt" work wherever cargo was run
+// from. Cargo sets CARGO_MANIFEST_DIR for tests and benches; this crate's own
+// directory is the fallback.
+pub fn path(relative: &str) -> String {
+    let manifest_dir =
+        env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| String::from(env!("CARGO_MANIFEST_DIR")));
+    Path::new(&manifest_dir)
+        .ancestors()
+        .map(|directory| directory.join(relative))
+        .find(|candidate| candidate.exists())
+        .unwrap_or_else(|| panic!("Failed to find fixture {} above {}", relative, manifest_dir))
+        .to_str()
+        .expect("Failed to convert fixture path to a string")
+        .to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::path;
+
+    #[test]
+    fn resolves_from_any_ancestor() {
+        assert!(path("2020/common/src/fixtures.rs").ends_with("2020/common/src/fixtures.rs"));
+        assert_eq!(path("day-11/sample.txt"), path("2020/day-11/sample.txt"));
+        assert_eq!(path("src/fixtures.rs"), path("common/src/fixtures.rs"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Failed to find fixture day-99/missing.txt")]
+    fn missing_fixtures_panic() {
+        path("day-99/missing.txt");
+    }
+}
@@ -0,0 +1,313 @@
+use std::{convert::TryFrom, error::Error, fmt, fmt::Write};
+
+use crate::grid::Grid;
+
+// Golly starts a new line before one would pass this length
+const MAX_LINE: usize = 70;
+
+// Letters for states 1 to 24; higher states put p to y in front
+const STATE_LETTERS: &[u8; 24] = b"ABCDEFGHIJKLMNOPQRSTUVWX";
+
+fn get_token(state: u8, two_state: bool) -> String {
+    match (state, two_state) {
+        (0, true) => String::from("b"),
+        (_, true) => String::from("o"),
+        (0, false) => String::from("."),
+        (_, false) => {
+            let index = usize::from(state - 1);
+            let letter = char::from(STATE_LETTERS[index % 24]);
+            match index / 24 {
+                0 => letter.to_string(),
+                prefix => format!("{}{}", char::from(b'o' + prefix as u8), letter),
+            }
+        }
+    }
+}
+
+// Collects run-length items, wrapping lines without splitting an item
+struct Writer {
+    text: String,
+    line_length: usize,
+}
+
+impl Writer {
+    fn push(&mut self, count: usize, token: &str) {
+        let item = if count > 1 {
+            format!("{count}{token}")
+        } else {
+            token.to_string()
+        };
+        if self.line_length + item.len() > MAX_LINE {
+            self.text.push('\n');
+            self.line_length = 0;
+        }
+        self.line_length += item.len();
+        self.text.push_str(&item);
+    }
+}
+
+// Encodes the grid as a Golly RLE pattern. State 0 is dead; if every other
+// state is 1 the pattern uses the two-state b/o letters, and otherwise the
+// multi-state ones. The rule is optional since not every automaton here has a
+// Golly equivalent.
+pub fn encode<T, F>(grid: &Grid<T>, rule: Option<&str>, state: F) -> String
+where
+    F: Fn(&T) -> u8,
+{
+    let states = grid.map(|cell| state(cell));
+    let two_state = states.iter().all(|(_, state)| *state <= 1);
+
+    let mut text = format!("x = {}, y = {}", grid.width(), grid.height());
+    if let Some(rule) = rule {
+        write!(text, ", rule = {rule}").unwrap();
+    }
+    text.push('\n');
+
+    let mut writer = Writer {
+        text: String::new(),
+        line_length: 0,
+    };
+    let mut pending_rows = 0;
+    for row in states.rows() {
+        // Dead cells at the end of a row are implied
+        let length = row
+            .iter()
+            .rposition(|state| *state != 0)
+            .map_or(0, |end| end + 1);
+        if length == 0 {
+            pending_rows += 1;
+            continue;
+        }
+        if pending_rows > 0 {
+            writer.push(pending_rows, "$");
+        }
+
+        let mut cells = row[..length].iter().peekable();
+        while let Some(state) = cells.next() {
+            let mut count = 1;
+            while cells.peek() == Some(&state) {
+                cells.next();
+                count += 1;
+            }
+            writer.push(count, &get_token(*state, two_state));
+        }
+        pending_rows = 1;
+    }
+    writer.push(1, "!");
+
+    text.push_str(&writer.text);
+    text.push('\n');
+    text
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DecodeError {
+    // Starts from 1, counting comment lines too
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for DecodeError {}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Pattern {
+    pub grid: Grid<u8>,
+    pub rule: Option<String>,
+    // Whether the pattern used the lettered states rather than b/o, which
+    // tells callers how to read state 1
+    pub multi_state: bool,
+}
+
+fn parse_header(header: &str) -> Option<(usize, usize, Option<String>)> {
+    let (mut width, mut height, mut rule) = (None, None, None);
+    for field in header.split(',') {
+        let (key, value) = field.split_once('=')?;
+        match key.trim() {
+            "x" => width = value.trim().parse().ok(),
+            "y" => height = value.trim().parse().ok(),
+            "rule" => rule = Some(value.trim().to_string()),
+            _ => return None,
+        }
+    }
+    Some((width?, height?, rule))
+}
+
+// Reads a Golly RLE pattern into a grid of states, sized by its header
+pub fn decode(text: &str) -> Result<Pattern, DecodeError> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, crate::normalize_line(line)))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+    let (header_line, header) = lines.next().ok_or_else(|| DecodeError {
+        line: 1,
+        message: String::from("missing header"),
+    })?;
+    let (width, height, rule) = parse_header(header).ok_or_else(|| DecodeError {
+        line: header_line,
+        message: format!("expected 'x = WIDTH, y = HEIGHT', found '{}'", header),
+    })?;
+
+    let mut pattern = Pattern {
+        grid: Grid::new(width, height, 0),
+        rule,
+        multi_state: false,
+    };
+    let (mut x, mut y) = (0, 0);
+    let mut count: Option<usize> = None;
+    let mut prefix: Option<u8> = None;
+    let mut last_line = header_line;
+    for (line_number, line) in lines {
+        last_line = line_number;
+        let error = |message: String| DecodeError {
+            line: line_number,
+            message,
+        };
+        for c in line.chars() {
+            if let Some(digit) = c.to_digit(10) {
+                count = Some(count.unwrap_or(0) * 10 + digit as usize);
+                continue;
+            }
+
+            let run = count.take().unwrap_or(1);
+            let state = match c {
+                'b' => 0,
+                'o' => 1,
+                '.' => {
+                    pattern.multi_state = true;
+                    0
+                }
+                'p'..='y' => {
+                    prefix = Some(c as u8 - b'o');
+                    count = Some(run);
+                    continue;
+                }
+                'A'..='X' => {
+                    pattern.multi_state = true;
+                    let state = 24 * usize::from(prefix.take().unwrap_or(0))
+                        + (c as usize - 'A' as usize)
+                        + 1;
+                    u8::try_from(state)
+                        .map_err(|_| error(format!("state {} is out of range", state)))?
+                }
+                '$' => {
+                    y += run;
+                    x = 0;
+                    continue;
+                }
+                '!' => return Ok(pattern),
+                _ if c.is_whitespace() => continue,
+                _ => return Err(error(format!("unexpected character '{}'", c))),
+            };
+
+            if state != 0 {
+                if y >= height || x + run > width {
+                    return Err(error(format!(
+                        "cells at ({}, {}) run outside the {}x{} pattern",
+                        x, y, width, height
+                    )));
+                }
+                for offset in 0..run {
+                    pattern.grid[(x + offset, y)] = state;
+                }
+            }
+            x += run;
+        }
+    }
+
+    Err(DecodeError {
+        line: last_line,
+        message: String::from("missing '!' at the end of the pattern"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glider() {
+        let grid = Grid::parse(".#.\n..#\n###\n");
+        assert_eq!(
+            encode(&grid, Some("B3/S23"), |cell| u8::from(*cell == '#')),
+            "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n"
+        );
+    }
+
+    #[test]
+    fn blank_rows_merge() {
+        let grid = Grid::parse("#..\n...\n...\n..#\n...\n");
+        assert_eq!(
+            encode(&grid, None, |cell| u8::from(*cell == '#')),
+            "x = 3, y = 5\no3$2bo!\n"
+        );
+    }
+
+    #[test]
+    fn multiple_states() {
+        let grid = Grid::from_cells(4, vec![0_u8, 1, 1, 2, 25, 0, 0, 0]);
+        assert_eq!(
+            encode(&grid, None, |cell| *cell),
+            "x = 4, y = 2\n.2AB$pA!\n"
+        );
+    }
+
+    #[test]
+    fn long_rows_wrap() {
+        let grid = Grid::from_cells(100, (0..100).map(|x| x % 2 == 0).collect());
+        let encoded = encode(&grid, None, |cell| u8::from(*cell));
+        let lines: Vec<&str> = encoded.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines.iter().all(|line| line.len() <= MAX_LINE));
+        assert_eq!(lines[1..].concat(), "ob".repeat(49) + "o!");
+    }
+
+    #[test]
+    fn decode_glider() {
+        let pattern = decode("#N Glider\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n").unwrap();
+        assert_eq!(pattern.rule.as_deref(), Some("B3/S23"));
+        assert!(!pattern.multi_state);
+        assert_eq!(
+            pattern.grid,
+            Grid::from_cells(3, vec![0, 1, 0, 0, 0, 1, 1, 1, 1])
+        );
+    }
+
+    #[test]
+    fn round_trips() {
+        let grid = Grid::from_cells(4, vec![0_u8, 1, 1, 2, 0, 0, 0, 0, 25, 0, 0, 3]);
+        let pattern = decode(&encode(&grid, Some("Seating"), |cell| *cell)).unwrap();
+        assert_eq!(pattern.grid, grid);
+        assert!(pattern.multi_state);
+
+        let grid = Grid::from_cells(100, (0..300).map(|x| u8::from(x % 3 == 0)).collect());
+        assert_eq!(
+            decode(&encode(&grid, None, |cell| *cell)).unwrap().grid,
+            grid
+        );
+    }
+
+    #[test]
+    fn decode_errors() {
+        assert_eq!(
+            decode("x = 2, y = 1\n3o!").unwrap_err().to_string(),
+            "line 2: cells at (0, 0) run outside the 2x1 pattern"
+        );
+        assert_eq!(
+            decode("#C comment\nwidth 3").unwrap_err().to_string(),
+            "line 2: expected 'x = WIDTH, y = HEIGHT', found 'width 3'"
+        );
+        assert_eq!(
+            decode("x = 2, y = 2\nbo$\no").unwrap_err().to_string(),
+            "line 3: missing '!' at the end of the pattern"
+        );
+        assert_eq!(decode("x = 2, y = 2\nbz!").unwrap_err().line, 2);
+    }
+}
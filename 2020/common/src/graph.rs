@@ -0,0 +1,390 @@
+use crate::{csr::Csr, pq::KeyedHeap};
+use std::{
+    cell::OnceCell,
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+// Nodes are stored in insertion order and referred to internally by index, so
+// every traversal visits neighbors in the order their edges were added and the
+// results don't depend on hashing
+#[derive(Clone, Debug)]
+pub struct DiGraph<N, W = usize> {
+    nodes: Vec<N>,
+    indices: HashMap<N, usize>,
+    // Every edge as (from, (to, weight)), in the order they were added
+    edges: Vec<(usize, (usize, W))>,
+    // Built from edges the first time a traversal needs it, and dropped
+    // whenever a node or edge is added
+    adjacency: OnceCell<Csr<(usize, W)>>,
+}
+
+impl<N, W> DiGraph<N, W>
+where
+    N: Copy + Eq + Hash,
+    W: Copy + Ord + Add<Output = W> + Default,
+{
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            indices: HashMap::new(),
+            edges: Vec::new(),
+            adjacency: OnceCell::new(),
+        }
+    }
+
+    pub fn add_node(&mut self, node: N) -> usize {
+        if let Some(index) = self.indices.get(&node) {
+            return *index;
+        }
+
+        let index = self.nodes.len();
+        self.nodes.push(node);
+        self.indices.insert(node, index);
+        self.adjacency.take();
+        index
+    }
+
+    pub fn add_edge(&mut self, from: N, to: N, weight: W) {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+        self.edges.push((from, (to, weight)));
+        self.adjacency.take();
+    }
+
+    fn adjacency(&self) -> &Csr<(usize, W)> {
+        self.adjacency
+            .get_or_init(|| Csr::from_edges(self.nodes.len(), self.edges.iter().copied()))
+    }
+
+    pub fn contains(&self, node: N) -> bool {
+        self.indices.contains_key(&node)
+    }
+
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    pub fn neighbors(&self, node: N) -> impl Iterator<Item = (N, W)> + '_ {
+        self.indices
+            .get(&node)
+            .into_iter()
+            .flat_map(move |index| self.adjacency().neighbors(*index))
+            .map(move |(to, weight)| (self.nodes[*to], *weight))
+    }
+
+    // Number of edges from start to each reachable node, ignoring weights
+    pub fn bfs(&self, start: N) -> HashMap<N, usize> {
+        let mut depths = HashMap::new();
+        let Some(start) = self.indices.get(&start) else {
+            return depths;
+        };
+
+        let mut depth_by_index = vec![None; self.nodes.len()];
+        depth_by_index[*start] = Some(0);
+        let mut queue = VecDeque::from(vec![*start]);
+        while let Some(index) = queue.pop_front() {
+            let depth = depth_by_index[index].unwrap();
+            depths.insert(self.nodes[index], depth);
+            for (to, _) in self.adjacency().neighbors(index) {
+                if depth_by_index[*to].is_none() {
+                    depth_by_index[*to] = Some(depth + 1);
+                    queue.push_back(*to);
+                }
+            }
+        }
+        depths
+    }
+
+    // Reachable nodes in preorder
+    pub fn dfs(&self, start: N) -> Vec<N> {
+        let mut order = Vec::new();
+        let Some(start) = self.indices.get(&start) else {
+            return order;
+        };
+
+        let mut visited = vec![false; self.nodes.len()];
+        let mut stack = vec![*start];
+        while let Some(index) = stack.pop() {
+            if visited[index] {
+                continue;
+            }
+            visited[index] = true;
+            order.push(self.nodes[index]);
+
+            // Reversed so that the first edge is explored first
+            stack.extend(
+                self.adjacency()
+                    .neighbors(index)
+                    .iter()
+                    .rev()
+                    .map(|(to, _)| *to)
+                    .filter(|to| !visited[*to]),
+            );
+        }
+        order
+    }
+
+    // Cheapest total weight from start to each reachable node
+    pub fn dijkstra(&self, start: N) -> HashMap<N, W> {
+        self.search(start, None, |_| W::default())
+            .0
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, cost)| cost.map(|cost| (self.nodes[index], cost)))
+            .collect()
+    }
+
+    // Cheapest path from start to goal along with its cost. The heuristic must
+    // never overestimate the remaining cost for the path to be optimal.
+    pub fn astar<H>(&self, start: N, goal: N, heuristic: H) -> Option<(W, Vec<N>)>
+    where
+        H: Fn(N) -> W,
+    {
+        let goal = *self.indices.get(&goal)?;
+        let (costs, previous) =
+            self.search(start, Some(goal), |index| heuristic(self.nodes[index]));
+        let cost = costs.get(goal).copied().flatten()?;
+
+        let mut path = vec![self.nodes[goal]];
+        let mut index = goal;
+        while let Some(from) = previous[index] {
+            path.push(self.nodes[from]);
+            index = from;
+        }
+        path.reverse();
+        Some((cost, path))
+    }
+
+    // Shared by dijkstra and astar: returns the best known cost and the
+    // previous node on the best path for every node index
+    #[allow(clippy::type_complexity)]
+    fn search<H>(
+        &self,
+        start: N,
+        goal: Option<usize>,
+        heuristic: H,
+    ) -> (Vec<Option<W>>, Vec<Option<usize>>)
+    where
+        H: Fn(usize) -> W,
+    {
+        let mut costs = vec![None; self.nodes.len()];
+        let mut previous = vec![None; self.nodes.len()];
+        let mut done = vec![false; self.nodes.len()];
+        let Some(start) = self.indices.get(&start) else {
+            return (costs, previous);
+        };
+
+        costs[*start] = Some(W::default());
+        // Priorities carry the index too so that ties pop in insertion order
+        let mut queue = KeyedHeap::new();
+        queue.push_or_decrease(*start, (heuristic(*start), *start));
+
+        while let Some((index, _)) = queue.pop() {
+            done[index] = true;
+            if Some(index) == goal {
+                break;
+            }
+
+            let cost = costs[index].unwrap();
+            for (to, weight) in self.adjacency().neighbors(index) {
+                let next_cost = cost + *weight;
+                if !done[*to] && costs[*to].is_none_or(|best| next_cost < best) {
+                    costs[*to] = Some(next_cost);
+                    previous[*to] = Some(index);
+                    queue.push_or_decrease(*to, (next_cost + heuristic(*to), *to));
+                }
+            }
+        }
+        (costs, previous)
+    }
+
+    // Orders the nodes so that every edge points forwards, or returns None if
+    // there's a cycle. Ties are broken by insertion order.
+    pub fn topological_sort(&self) -> Option<Vec<N>> {
+        let mut in_degrees = vec![0; self.nodes.len()];
+        for (_, (to, _)) in &self.edges {
+            in_degrees[*to] += 1;
+        }
+
+        let mut ready: VecDeque<usize> = (0..self.nodes.len())
+            .filter(|index| in_degrees[*index] == 0)
+            .collect();
+        let mut order = Vec::with_capacity(self.nodes.len());
+        while let Some(index) = ready.pop_front() {
+            order.push(self.nodes[index]);
+            for (to, _) in self.adjacency().neighbors(index) {
+                in_degrees[*to] -= 1;
+                if in_degrees[*to] == 0 {
+                    ready.push_back(*to);
+                }
+            }
+        }
+
+        if order.len() == self.nodes.len() {
+            Some(order)
+        } else {
+            None
+        }
+    }
+}
+
+impl<N, W> Default for DiGraph<N, W>
+where
+    N: Copy + Eq + Hash,
+    W: Copy + Ord + Add<Output = W> + Default,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// An undirected graph is a directed one with every edge added both ways
+#[derive(Clone, Debug)]
+pub struct Graph<N, W = usize> {
+    graph: DiGraph<N, W>,
+}
+
+impl<N, W> Graph<N, W>
+where
+    N: Copy + Eq + Hash,
+    W: Copy + Ord + Add<Output = W> + Default,
+{
+    pub fn new() -> Self {
+        Self {
+            graph: DiGraph::new(),
+        }
+    }
+
+    pub fn add_node(&mut self, node: N) -> usize {
+        self.graph.add_node(node)
+    }
+
+    pub fn add_edge(&mut self, a: N, b: N, weight: W) {
+        self.graph.add_edge(a, b, weight);
+        self.graph.add_edge(b, a, weight);
+    }
+
+    pub fn as_directed(&self) -> &DiGraph<N, W> {
+        &self.graph
+    }
+
+    pub fn bfs(&self, start: N) -> HashMap<N, usize> {
+        self.graph.bfs(start)
+    }
+
+    pub fn dfs(&self, start: N) -> Vec<N> {
+        self.graph.dfs(start)
+    }
+
+    pub fn dijkstra(&self, start: N) -> HashMap<N, W> {
+        self.graph.dijkstra(start)
+    }
+
+    pub fn astar<H>(&self, start: N, goal: N, heuristic: H) -> Option<(W, Vec<N>)>
+    where
+        H: Fn(N) -> W,
+    {
+        self.graph.astar(start, goal, heuristic)
+    }
+}
+
+impl<N, W> Default for Graph<N, W>
+where
+    N: Copy + Eq + Hash,
+    W: Copy + Ord + Add<Output = W> + Default,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DiGraph, Graph};
+
+    fn get_weighted() -> DiGraph<char, u32> {
+        let mut graph = DiGraph::new();
+        graph.add_edge('a', 'b', 7);
+        graph.add_edge('a', 'c', 2);
+        graph.add_edge('c', 'b', 3);
+        graph.add_edge('b', 'd', 1);
+        graph.add_edge('c', 'd', 8);
+        graph.add_node('e');
+        graph
+    }
+
+    #[test]
+    fn bfs_counts_edges() {
+        let depths = get_weighted().bfs('a');
+        assert_eq!(depths[&'a'], 0);
+        assert_eq!(depths[&'b'], 1);
+        assert_eq!(depths[&'d'], 2);
+        assert!(!depths.contains_key(&'e'));
+    }
+
+    #[test]
+    fn dfs_follows_first_edges() {
+        assert_eq!(get_weighted().dfs('a'), vec!['a', 'b', 'd', 'c']);
+    }
+
+    #[test]
+    fn dijkstra_finds_cheapest_costs() {
+        let costs = get_weighted().dijkstra('a');
+        assert_eq!(costs[&'b'], 5);
+        assert_eq!(costs[&'d'], 6);
+        assert_eq!(costs.len(), 4);
+    }
+
+    #[test]
+    fn astar_finds_cheapest_path() {
+        let graph = get_weighted();
+        assert_eq!(
+            graph.astar('a', 'd', |_| 0),
+            Some((6, vec!['a', 'c', 'b', 'd']))
+        );
+        assert_eq!(graph.astar('a', 'e', |_| 0), None);
+    }
+
+    #[test]
+    fn astar_on_grid() {
+        let mut graph = Graph::new();
+        for x in 0..5_i32 {
+            for y in 0..5_i32 {
+                // A wall down the middle with a gap at the bottom
+                if x == 2 && y < 4 {
+                    continue;
+                }
+                for (dx, dy) in &[(1, 0), (0, 1)] {
+                    let (nx, ny) = (x + dx, y + dy);
+                    if nx < 5 && ny < 5 && !(nx == 2 && ny < 4) {
+                        graph.add_edge((x, y), (nx, ny), 1);
+                    }
+                }
+            }
+        }
+
+        let manhattan = |(x, y): (i32, i32)| (4 - x).abs() + y.abs();
+        let (cost, path) = graph.astar((0, 0), (4, 0), manhattan).unwrap();
+        assert_eq!(cost, 12);
+        assert_eq!(path.len(), 13);
+        assert_eq!(graph.dijkstra((0, 0))[&(4, 0)], 12);
+    }
+
+    #[test]
+    fn topological_sort_detects_cycles() {
+        let mut graph: DiGraph<&str> = DiGraph::new();
+        graph.add_edge("shirt", "tie", 1);
+        graph.add_edge("tie", "jacket", 1);
+        graph.add_edge("pants", "shoes", 1);
+        graph.add_edge("pants", "jacket", 1);
+        assert_eq!(
+            graph.topological_sort(),
+            Some(vec!["shirt", "pants", "tie", "shoes", "jacket"])
+        );
+
+        graph.add_edge("jacket", "shirt", 1);
+        assert_eq!(graph.topological_sort(), None);
+    }
+}
@@ -0,0 +1,320 @@
+use std::ops::{Index, IndexMut};
+
+// Cells that connect to each other through their edges. The bounding box runs
+// from min to max, both included, and cells are in the order the fill
+// reached them.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Region {
+    pub cells: Vec<(usize, usize)>,
+    pub min: (usize, usize),
+    pub max: (usize, usize),
+}
+
+impl Region {
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+}
+
+// A fixed-size grid of cells stored row by row, indexed by (x, y) with y
+// growing downwards like the lines of a puzzle input
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    // Cells must be in row order and fill a whole number of rows
+    pub fn from_cells(width: usize, cells: Vec<T>) -> Self {
+        assert!(width > 0, "Width must be positive");
+        assert_eq!(
+            cells.len() % width,
+            0,
+            "{} cells don't fill rows of {}",
+            cells.len(),
+            width
+        );
+        Self {
+            width,
+            height: cells.len() / width,
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if self.contains(x, y) {
+            Some(&self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if self.contains(x, y) {
+            Some(&mut self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    // Every cell along with its (x, y), in row order
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(index, cell)| ((index % width, index / width), cell))
+    }
+
+    // Up, down, left and right, leaving out any that fall off the edge
+    fn edge_neighbors(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        let (width, height) = (self.width, self.height);
+        // Arrays only iterate by value from the 2021 edition on
+        IntoIterator::into_iter([
+            y.checked_sub(1).map(|y| (x, y)),
+            (y + 1 < height).then_some((x, y + 1)),
+            x.checked_sub(1).map(|x| (x, y)),
+            (x + 1 < width).then_some((x + 1, y)),
+        ])
+        .flatten()
+    }
+
+    // Every cell that can be reached from start through the edges of cells
+    // that match, starting with start itself. Nothing can be reached from a
+    // start that doesn't match or is off the grid.
+    pub fn flood_fill<P>(&self, start: (usize, usize), predicate: P) -> Vec<(usize, usize)>
+    where
+        P: Fn(&T) -> bool,
+    {
+        let mut visited = vec![false; self.cells.len()];
+        self.fill_from(start, &predicate, &mut visited)
+    }
+
+    fn fill_from<P>(
+        &self,
+        start: (usize, usize),
+        predicate: &P,
+        visited: &mut [bool],
+    ) -> Vec<(usize, usize)>
+    where
+        P: Fn(&T) -> bool,
+    {
+        let mut cells = Vec::new();
+        if !self.get(start.0, start.1).is_some_and(predicate) {
+            return cells;
+        }
+
+        visited[start.1 * self.width + start.0] = true;
+        let mut pending = vec![start];
+        while let Some((x, y)) = pending.pop() {
+            cells.push((x, y));
+            for (x, y) in self.edge_neighbors(x, y) {
+                let index = y * self.width + x;
+                if !visited[index] && predicate(&self.cells[index]) {
+                    visited[index] = true;
+                    pending.push((x, y));
+                }
+            }
+        }
+        cells
+    }
+
+    // Each connected group of matching cells, in row order of the first cell
+    // in each
+    pub fn regions<P>(&self, predicate: P) -> Vec<Region>
+    where
+        P: Fn(&T) -> bool,
+    {
+        let mut visited = vec![false; self.cells.len()];
+        let mut regions = Vec::new();
+        for index in 0..self.cells.len() {
+            if visited[index] {
+                continue;
+            }
+            let start = (index % self.width, index / self.width);
+            let cells = self.fill_from(start, &predicate, &mut visited);
+            if cells.is_empty() {
+                continue;
+            }
+            let (min, max) = cells.iter().fold((start, start), |(min, max), (x, y)| {
+                (
+                    (min.0.min(*x), min.1.min(*y)),
+                    (max.0.max(*x), max.1.max(*y)),
+                )
+            });
+            regions.push(Region { cells, min, max });
+        }
+        regions
+    }
+
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl Grid<char> {
+    // One row per non-empty line, which must all be the same length
+    pub fn parse(text: &str) -> Self {
+        let mut width = 0;
+        let mut cells = Vec::new();
+        for line in text
+            .lines()
+            .map(crate::normalize_line)
+            .filter(|line| !line.is_empty())
+        {
+            let length = line.chars().count();
+            if width == 0 {
+                width = length;
+            } else {
+                assert_eq!(length, width, "Row [{}] has the wrong length", line);
+            }
+            cells.extend(line.chars());
+        }
+        Self {
+            width,
+            height: cells.len().checked_div(width).unwrap_or(0),
+            cells,
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y).unwrap_or_else(|| {
+            panic!(
+                "({}, {}) is outside a {}x{} grid",
+                x, y, self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(x, y)
+            .unwrap_or_else(|| panic!("({}, {}) is outside a {}x{} grid", x, y, width, height))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Grid, Region};
+
+    #[test]
+    fn parse_and_index() {
+        let grid = Grid::parse("L.L\n#L#\n");
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 0)], '.');
+        assert_eq!(grid[(2, 1)], '#');
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.get(0, 2), None);
+        assert_eq!(
+            grid.rows()
+                .map(|row| row.iter().collect())
+                .collect::<Vec<String>>(),
+            vec!["L.L", "#L#"]
+        );
+    }
+
+    #[test]
+    fn iter_visits_rows_in_order() {
+        let mut grid = Grid::new(2, 2, 0);
+        grid[(1, 0)] = 1;
+        grid[(0, 1)] = 2;
+        let cells: Vec<_> = grid
+            .iter()
+            .map(|(position, cell)| (position, *cell))
+            .collect();
+        assert_eq!(
+            cells,
+            vec![((0, 0), 0), ((1, 0), 1), ((0, 1), 2), ((1, 1), 0)]
+        );
+        assert_eq!(
+            grid.map(|cell| cell * 10),
+            Grid::from_cells(2, vec![0, 10, 20, 0])
+        );
+    }
+
+    #[test]
+    fn flood_fill_follows_edges() {
+        let grid = Grid::parse("##.\n.##\n#..\n");
+        let mut cells = grid.flood_fill((0, 0), |cell| *cell == '#');
+        cells.sort_unstable();
+        assert_eq!(cells, vec![(0, 0), (1, 0), (1, 1), (2, 1)]);
+        assert!(grid.flood_fill((2, 0), |cell| *cell == '#').is_empty());
+        assert!(grid.flood_fill((3, 0), |cell| *cell == '#').is_empty());
+    }
+
+    #[test]
+    fn regions_are_labeled_in_row_order() {
+        // Diagonal cells don't connect
+        let grid = Grid::parse("##.#\n.#..\n#..#\n");
+        let regions = grid.regions(|cell| *cell == '#');
+        let summary: Vec<_> = regions
+            .iter()
+            .map(|region| (region.area(), region.min, region.max))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (3, (0, 0), (1, 1)),
+                (1, (3, 0), (3, 0)),
+                (1, (0, 2), (0, 2)),
+                (1, (3, 2), (3, 2))
+            ]
+        );
+
+        let mut gaps = grid.regions(|cell| *cell == '.');
+        gaps[0].cells.sort_unstable();
+        assert_eq!(
+            gaps[0],
+            Region {
+                cells: vec![(1, 2), (2, 0), (2, 1), (2, 2), (3, 1)],
+                min: (1, 0),
+                max: (3, 2)
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "(2, 0) is outside a 2x1 grid")]
+    fn index_out_of_bounds() {
+        let grid = Grid::from_cells(2, vec!['a', 'b']);
+        let _ = grid[(2, 0)];
+    }
+}
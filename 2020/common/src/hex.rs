@@ -0,0 +1,109 @@
+use std::ops::{Add, Sub};
+
+// Cube coordinates for a hex grid: every hex satisfies x + y + z == 0, and
+// stepping to a neighbor adds one to one axis and subtracts one from another.
+// This works for both pointy-topped and flat-topped grids; only the names given
+// to the six unit steps differ.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Cube {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl Cube {
+    pub const ORIGIN: Cube = Cube { x: 0, y: 0, z: 0 };
+
+    pub fn new(x: i64, y: i64, z: i64) -> Self {
+        assert_eq!(x + y + z, 0, "Cube coordinates must sum to zero");
+        Self { x, y, z }
+    }
+
+    pub fn distance(self, other: Cube) -> i64 {
+        let delta = self - other;
+        delta.x.abs().max(delta.y.abs()).max(delta.z.abs())
+    }
+
+    pub fn length(self) -> i64 {
+        self.distance(Cube::ORIGIN)
+    }
+
+    // Returns indices into steps, which must be the six unit steps in order
+    // around the ring, for a shortest path from the origin to this hex. Among
+    // the possible shortest paths, this always picks the one that takes as
+    // many steps as possible in the earliest direction before moving on.
+    pub fn get_shortest_path(self, steps: &[Cube; 6]) -> Vec<usize> {
+        let mut path = Vec::new();
+        let mut remaining = self;
+        while remaining != Cube::ORIGIN {
+            let length = remaining.length();
+            let index = steps
+                .iter()
+                .position(|step| (remaining - *step).length() < length)
+                .expect("Steps must be the six unit hex steps");
+            path.push(index);
+            remaining = remaining - steps[index];
+        }
+        path
+    }
+}
+
+impl Add for Cube {
+    type Output = Cube;
+
+    fn add(self, other: Cube) -> Cube {
+        Cube {
+            x: self.x + other.x,
+            y: self.y + other.y,
+            z: self.z + other.z,
+        }
+    }
+}
+
+impl Sub for Cube {
+    type Output = Cube;
+
+    fn sub(self, other: Cube) -> Cube {
+        Cube {
+            x: self.x - other.x,
+            y: self.y - other.y,
+            z: self.z - other.z,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cube;
+
+    // East, southeast, southwest, west, northwest, northeast on a pointy-topped grid
+    const STEPS: [Cube; 6] = [
+        Cube { x: 1, y: -1, z: 0 },
+        Cube { x: 0, y: -1, z: 1 },
+        Cube { x: -1, y: 0, z: 1 },
+        Cube { x: -1, y: 1, z: 0 },
+        Cube { x: 0, y: 1, z: -1 },
+        Cube { x: 1, y: 0, z: -1 },
+    ];
+
+    #[test]
+    fn distance_is_largest_axis_delta() {
+        assert_eq!(Cube::new(2, -1, -1).length(), 2);
+        assert_eq!(Cube::new(3, 0, -3).distance(Cube::new(-1, 1, 0)), 4);
+    }
+
+    #[test]
+    fn shortest_path_reaches_target() {
+        for x in -3..=3 {
+            for y in -3..=3 {
+                let target = Cube::new(x, y, -x - y);
+                let path = target.get_shortest_path(&STEPS);
+                assert_eq!(path.len() as i64, target.length());
+                let end = path
+                    .iter()
+                    .fold(Cube::ORIGIN, |cube, index| cube + STEPS[*index]);
+                assert_eq!(end, target);
+            }
+        }
+    }
+}
@@ -0,0 +1,311 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    fs::{self, File},
+    io::{self, BufRead, BufReader, Cursor},
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use crate::download;
+
+// Stands in for a file name once a day has handed over its embedded input, so
+// the readers here can be given it like any other file
+pub const EMBEDDED: &str = "input.txt (embedded)";
+
+static EMBEDDED_TEXT: OnceLock<&'static str> = OnceLock::new();
+
+// Makes the text readable under EMBEDDED and returns that name. Only the first
+// text handed over is kept, since a binary only embeds one input.
+pub fn embed(text: &'static str) -> &'static str {
+    EMBEDDED_TEXT.get_or_init(|| text);
+    EMBEDDED
+}
+
+// Stands in for standard input, as a file name given on the command line. It's
+// read to the end the first time it's asked for and kept, so a day can look at
+// its size or read it again just as it could a file.
+pub const STDIN: &str = "-";
+
+static STDIN_TEXT: OnceLock<String> = OnceLock::new();
+
+// The text of the names that don't refer to files
+fn get_in_memory(filename: &str) -> io::Result<Option<&'static str>> {
+    match filename {
+        EMBEDDED => Ok(EMBEDDED_TEXT.get().copied()),
+        STDIN => {
+            if STDIN_TEXT.get().is_none() {
+                let text = io::read_to_string(io::stdin())?;
+                STDIN_TEXT.get_or_init(|| text);
+            }
+            Ok(STDIN_TEXT.get().map(String::as_str))
+        }
+        _ => Ok(None),
+    }
+}
+
+// The file to read in place of filename, which differs only for a missing
+// input.txt that can be downloaded instead
+fn locate(filename: &str) -> io::Result<PathBuf> {
+    Ok(download::stand_in(filename)?.unwrap_or_else(|| PathBuf::from(filename)))
+}
+
+pub fn open(filename: &str) -> io::Result<Box<dyn BufRead>> {
+    match get_in_memory(filename)? {
+        Some(text) => Ok(Box::new(Cursor::new(text.as_bytes()))),
+        None => Ok(Box::new(BufReader::new(File::open(locate(filename)?)?))),
+    }
+}
+
+pub fn read_to_string(filename: &str) -> io::Result<String> {
+    match get_in_memory(filename)? {
+        Some(text) => Ok(String::from(text)),
+        None => fs::read_to_string(locate(filename)?),
+    }
+}
+
+// For binaries, which have nothing to do without their input
+pub fn load(filename: &str) -> String {
+    read_to_string(filename).unwrap_or_else(|_| panic!("Failed to read file {}", filename))
+}
+
+// In bytes
+pub fn size(filename: &str) -> io::Result<u64> {
+    match get_in_memory(filename)? {
+        Some(text) => Ok(text.len() as u64),
+        None => Ok(fs::metadata(locate(filename)?)?.len()),
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct GlobError {
+    pub pattern: String,
+}
+
+impl Display for GlobError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "no files match {}", self.pattern)
+    }
+}
+
+impl Error for GlobError {}
+
+fn is_glob(path: &str) -> bool {
+    path.contains(['*', '?'])
+}
+
+// * matches any run of characters and ? any single one
+fn matches_glob(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|skip| matches_glob(rest, &name[skip..])),
+        Some((first, rest)) => name.split_first().is_some_and(|(next, name)| {
+            (*first == '?' || first == next) && matches_glob(rest, name)
+        }),
+    }
+}
+
+fn expand_glob(pattern: &str) -> Vec<PathBuf> {
+    let mut paths = vec![if pattern.starts_with('/') {
+        PathBuf::from("/")
+    } else {
+        PathBuf::new()
+    }];
+    for component in pattern.split('/').filter(|component| !component.is_empty()) {
+        if !is_glob(component) {
+            for path in &mut paths {
+                path.push(component);
+            }
+            continue;
+        }
+
+        let component: Vec<char> = component.chars().collect();
+        let mut matches = Vec::new();
+        for path in &paths {
+            let directory = if path.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                path
+            };
+            // Anything that isn't a readable directory just has no matches
+            let Ok(entries) = fs::read_dir(directory) else {
+                continue;
+            };
+            for entry in entries.filter_map(Result::ok) {
+                let name = entry.file_name();
+                let Some(name) = name.to_str() else {
+                    continue;
+                };
+                let name: Vec<char> = name.chars().collect();
+                // Like a shell, only a pattern starting with . matches hidden files
+                if name.first() == Some(&'.') && component.first() != Some(&'.') {
+                    continue;
+                }
+                if matches_glob(&component, &name) {
+                    matches.push(path.join(entry.file_name()));
+                }
+            }
+        }
+        paths = matches;
+    }
+    paths.retain(|path| path.exists());
+    paths.sort();
+    paths
+}
+
+// Replaces each path containing * or ? with the files it matches, in sorted
+// order. Wildcards stay within one component, so inputs/*/day-11.txt looks one
+// directory down. Other paths pass through untouched, leaving a missing file
+// to be reported by whatever opens it.
+pub fn expand_globs<'a, I>(paths: I) -> Result<Vec<String>, GlobError>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut expanded = Vec::new();
+    for path in paths {
+        if !is_glob(path) {
+            expanded.push(String::from(path));
+            continue;
+        }
+
+        let matches = expand_glob(path);
+        if matches.is_empty() {
+            return Err(GlobError {
+                pattern: String::from(path),
+            });
+        }
+        expanded.extend(
+            matches
+                .into_iter()
+                .map(|path| path.to_string_lossy().into_owned()),
+        );
+    }
+    Ok(expanded)
+}
+
+// Test corpora can hold several puzzle instances in one file, each ending at
+// a line of just %%. A file without one is a single instance.
+pub const INSTANCE_SEPARATOR: &str = "%%";
+
+pub fn split_instances(text: &str) -> Vec<String> {
+    let mut instances = vec![String::new()];
+    for line in text.lines() {
+        if crate::normalize_line(line) == INSTANCE_SEPARATOR {
+            instances.push(String::new());
+        } else {
+            let instance = instances.last_mut().unwrap();
+            instance.push_str(line);
+            instance.push('\n');
+        }
+    }
+    // A separator at the very end doesn't start another instance
+    if instances.len() > 1 && instances.last().unwrap().trim().is_empty() {
+        instances.pop();
+    }
+    instances
+}
+
+// Picks the input a day reads: the file given on the command line or, when the
+// day was built with its embed-input feature, the input.txt next to its
+// manifest, compiled into the binary. The feature belongs to the crate that
+// expands this, so each day opts in on its own.
+#[macro_export]
+macro_rules! resolve_input {
+    ($filename:expr) => {
+        match $filename {
+            Some(filename) => filename,
+            #[cfg(feature = "embed-input")]
+            None => $crate::input::embed(include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/input.txt"
+            ))),
+            #[cfg(not(feature = "embed-input"))]
+            None => panic!("No input file given"),
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_text_reads_like_a_file() {
+        assert_eq!(embed("first\nsecond\n"), EMBEDDED);
+        assert_eq!(read_to_string(EMBEDDED).unwrap(), "first\nsecond\n");
+        assert_eq!(size(EMBEDDED).unwrap(), 13);
+        let lines: Vec<String> = open(EMBEDDED)
+            .unwrap()
+            .lines()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(lines, ["first", "second"]);
+
+        // Other names still go to the file system
+        assert!(read_to_string("no such file").is_err());
+    }
+
+    #[test]
+    fn splits_instances() {
+        assert_eq!(split_instances("a\nb\n"), ["a\nb\n"]);
+        assert_eq!(split_instances("a\n%%\r\nb\nc\n%%\n"), ["a\n", "b\nc\n"]);
+        assert_eq!(split_instances("%%\na\n"), ["", "a\n"]);
+    }
+
+    #[test]
+    fn matches_wildcards() {
+        let matches = |pattern: &str, name: &str| {
+            let pattern: Vec<char> = pattern.chars().collect();
+            let name: Vec<char> = name.chars().collect();
+            matches_glob(&pattern, &name)
+        };
+        assert!(matches("*.txt", "input.txt"));
+        assert!(matches("day-?1", "day-11"));
+        assert!(matches("*", ""));
+        assert!(!matches("*.txt", "input.rle"));
+        assert!(!matches("day-?", "day-11"));
+    }
+
+    #[test]
+    fn expands_globs() {
+        let directory = std::env::temp_dir().join(format!("common-globs-{}", std::process::id()));
+        for name in [
+            "day-11/b.txt",
+            "day-11/a.txt",
+            "day-24/a.txt",
+            "day-24/.hidden.txt",
+        ] {
+            let path = directory.join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        let root = directory.to_str().unwrap();
+        let expand = |patterns: &[&str]| -> Result<Vec<String>, GlobError> {
+            let patterns: Vec<String> = patterns
+                .iter()
+                .map(|pattern| format!("{}/{}", root, pattern))
+                .collect();
+            let expanded = expand_globs(patterns.iter().map(String::as_str))?;
+            Ok(expanded
+                .iter()
+                .map(|path| path[root.len() + 1..].to_string())
+                .collect())
+        };
+
+        assert_eq!(
+            expand(&["day-11/*.txt"]).unwrap(),
+            ["day-11/a.txt", "day-11/b.txt"]
+        );
+        assert_eq!(
+            expand(&["day-*/a.txt", "missing.txt"]).unwrap(),
+            ["day-11/a.txt", "day-24/a.txt", "missing.txt"]
+        );
+        assert_eq!(expand(&["day-24/.*"]).unwrap(), ["day-24/.hidden.txt"]);
+        assert_eq!(
+            expand(&["day-?5/*"]).unwrap_err().to_string(),
+            format!("no files match {}/day-?5/*", root)
+        );
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
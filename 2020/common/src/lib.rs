@@ -1,42 +1,147 @@
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-};
+pub mod alloc;
+pub mod bitgrid;
+pub mod cache;
+pub mod cli;
+pub mod combinatorics;
+pub mod coords;
+pub mod counter;
+pub mod csr;
+pub mod digits;
+pub mod download;
+pub mod example;
+pub mod fixtures;
+pub mod gen;
+pub mod golly;
+pub mod graph;
+pub mod grid;
+pub mod hex;
+pub mod input;
+pub mod math;
+pub mod matrix;
+pub mod parse;
+pub mod pq;
+pub mod ranges;
+pub mod report;
+pub mod search;
+pub mod solution;
+pub mod strings;
+pub mod timing;
+pub mod vis;
+pub mod vm;
 
+// So that scope! works in crates that don't depend on tracing themselves
+pub use tracing;
+
+pub use solution::Solution;
+
+use std::io::{BufRead, Cursor};
+
+// Every line handed to a parser comes through here, so none of them see line
+// endings or the stray \r of a file saved on Windows
+pub fn normalize_line(line: &str) -> &str {
+    line.trim()
+}
+
+// For parsers that work on the whole input at once, such as splitting it on
+// blank lines
+pub fn normalize_text(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len());
+    for line in text.lines() {
+        normalized.push_str(normalize_line(line));
+        normalized.push('\n');
+    }
+    normalized
+}
+
+// Iterating yields every normalized line, blank ones included, while read_with
+// stops at the first blank line so that grouped inputs can be read a group at
+// a time
 pub struct LineReader {
-    reader: BufReader<File>,
+    reader: Box<dyn BufRead>,
+    line: String,
 }
 
 impl LineReader {
     pub fn new(filename: &str) -> Self {
-        let file =
-            File::open(filename).unwrap_or_else(|_| panic!("Failed to open file {}", filename));
-        let reader = BufReader::new(file);
-        Self { reader }
+        let reader =
+            input::open(filename).unwrap_or_else(|_| panic!("Failed to open file {}", filename));
+        Self {
+            reader,
+            line: String::new(),
+        }
+    }
+
+    // Reads lines out of text already in memory, such as the input handed to
+    // a Solution
+    pub fn from_text(text: &str) -> Self {
+        Self {
+            reader: Box::new(Cursor::new(text.to_owned())),
+            line: String::new(),
+        }
     }
 
     pub fn read_with<F>(&mut self, mut f: F) -> bool
     where
         F: FnMut(&str),
     {
-        let mut line = String::new();
         loop {
-            let bytes = self
-                .reader
-                .read_line(&mut line)
-                .expect("Failed to read line");
-            if bytes == 0 {
-                return false;
-            }
-
-            let trimmed = line.trim();
-            if trimmed.is_empty() {
-                return true;
+            match self.next() {
+                None => return false,
+                Some(line) if line.is_empty() => return true,
+                Some(line) => f(&line),
             }
+        }
+    }
+}
 
-            f(line.trim());
+impl Iterator for LineReader {
+    type Item = String;
 
-            line.clear();
+    fn next(&mut self) -> Option<String> {
+        self.line.clear();
+        let bytes = self
+            .reader
+            .read_line(&mut self.line)
+            .expect("Failed to read line");
+        if bytes == 0 {
+            return None;
         }
+        Some(String::from(normalize_line(&self.line)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_carriage_returns() {
+        assert_eq!(normalize_line("L.#\r\n"), "L.#");
+        assert_eq!(
+            normalize_text("Player 1:\r\n9\r\n\r\nPlayer 2:\r\n5\r\n"),
+            "Player 1:\n9\n\nPlayer 2:\n5\n"
+        );
+    }
+
+    #[test]
+    fn reads_normalized_lines() {
+        let path = std::env::temp_dir().join(format!("common-lines-{}.txt", std::process::id()));
+        std::fs::write(&path, "a\r\nb \r\n\r\nc").unwrap();
+        let filename = path.to_str().unwrap();
+
+        let lines: Vec<String> = LineReader::new(filename).collect();
+        assert_eq!(lines, ["a", "b", "", "c"]);
+
+        let mut reader = LineReader::new(filename);
+        let mut group = Vec::new();
+        assert!(reader.read_with(|line| group.push(String::from(line))));
+        assert_eq!(group, ["a", "b"]);
+        assert!(!reader.read_with(|line| group.push(String::from(line))));
+        assert_eq!(group, ["a", "b", "c"]);
+
+        std::fs::remove_file(path).unwrap();
+
+        let lines: Vec<String> = LineReader::from_text("x\r\n\ny").collect();
+        assert_eq!(lines, ["x", "", "y"]);
     }
 }
@@ -0,0 +1,165 @@
+// Everything here works on u64 values and widens to u128 wherever a product
+// could overflow
+
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        let remainder = a % b;
+        a = b;
+        b = remainder;
+    }
+    a
+}
+
+// Returns None if the result doesn't fit in a u64
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+pub fn mod_mul(a: u64, b: u64, modulus: u64) -> u64 {
+    (u128::from(a) * u128::from(b) % u128::from(modulus)) as u64
+}
+
+pub fn mod_pow(base: u64, mut exponent: u64, modulus: u64) -> u64 {
+    assert!(modulus > 0, "Modulus must be positive");
+    let mut result = 1 % modulus;
+    let mut base = base % modulus;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mod_mul(result, base, modulus);
+        }
+        base = mod_mul(base, base, modulus);
+        exponent >>= 1;
+    }
+    result
+}
+
+// Returns (g, x, y) such that a * x + b * y == g == gcd(a, b)
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a % b);
+        (g, y, x - (a / b) * y)
+    }
+}
+
+// The x in [0, modulus) with value * x == 1 (mod modulus), if there is one
+pub fn mod_inverse(value: u64, modulus: u64) -> Option<u64> {
+    assert!(modulus > 0, "Modulus must be positive");
+    let (g, x, _) = extended_gcd(i128::from(value % modulus), i128::from(modulus));
+    if g != 1 {
+        return None;
+    }
+    Some(x.rem_euclid(i128::from(modulus)) as u64)
+}
+
+// Finds the x that leaves each (remainder, modulus) pair's remainder, returning
+// it along with the combined modulus. The moduli don't need to be coprime, but
+// then there's only a solution if the remainders agree wherever the moduli
+// overlap. Returns None when there's no solution or the combined modulus
+// doesn't fit in a u64.
+pub fn crt(congruences: &[(u64, u64)]) -> Option<(u64, u64)> {
+    let mut remainder: u128 = 0;
+    let mut modulus: u128 = 1;
+    for (next_remainder, next_modulus) in congruences {
+        assert!(*next_modulus > 0, "Modulus must be positive");
+        let next_modulus = u128::from(*next_modulus);
+        let next_remainder = u128::from(*next_remainder) % next_modulus;
+
+        // Solve remainder + modulus * k == next_remainder (mod next_modulus)
+        let (g, inverse, _) = extended_gcd((modulus % next_modulus) as i128, next_modulus as i128);
+        let g = g as u128;
+        let difference = (next_remainder + next_modulus - remainder % next_modulus) % next_modulus;
+        if !difference.is_multiple_of(g) {
+            return None;
+        }
+
+        let reduced_modulus = next_modulus / g;
+        let inverse = inverse.rem_euclid(reduced_modulus as i128) as u128;
+        let k = (difference / g) % reduced_modulus * inverse % reduced_modulus;
+
+        remainder += modulus * k;
+        modulus *= reduced_modulus;
+        if modulus > u128::from(u64::MAX) {
+            return None;
+        }
+        remainder %= modulus;
+    }
+    Some((remainder as u64, modulus as u64))
+}
+
+// All primes up to and including limit, by the sieve of Eratosthenes
+pub fn primes_up_to(limit: usize) -> Vec<usize> {
+    if limit < 2 {
+        return Vec::new();
+    }
+
+    let mut is_composite = vec![false; limit + 1];
+    let mut primes = Vec::new();
+    for candidate in 2..=limit {
+        if is_composite[candidate] {
+            continue;
+        }
+        primes.push(candidate);
+        for multiple in (candidate * candidate..=limit).step_by(candidate) {
+            is_composite[multiple] = true;
+        }
+    }
+    primes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(48, 18), 6);
+        assert_eq!(gcd(0, 5), 5);
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm(u64::MAX, 2), None);
+    }
+
+    #[test]
+    fn mod_pow_handles_large_moduli() {
+        // 2020 day 25's sample public key
+        assert_eq!(mod_pow(7, 8, 20_201_227), 5_764_801);
+        assert_eq!(mod_pow(2, 64, u64::MAX), 1);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+    }
+
+    #[test]
+    fn mod_inverse_exists_only_for_coprime_values() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(6, 9), None);
+        let inverse = mod_inverse(123_456_789, 1_000_000_007).unwrap();
+        assert_eq!(mod_mul(inverse, 123_456_789, 1_000_000_007), 1);
+    }
+
+    #[test]
+    fn crt_solves_bus_schedule() {
+        // 2020 day 13's sample: 7,13,x,x,59,x,31,19
+        let buses: [(u64, u64); 5] = [(0, 7), (1, 13), (4, 59), (6, 31), (7, 19)];
+        let congruences: Vec<(u64, u64)> = buses
+            .iter()
+            .map(|(offset, bus)| ((bus - offset % bus) % bus, *bus))
+            .collect();
+        assert_eq!(crt(&congruences), Some((1_068_781, 7 * 13 * 59 * 31 * 19)));
+    }
+
+    #[test]
+    fn crt_handles_shared_factors() {
+        assert_eq!(crt(&[(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+    }
+
+    #[test]
+    fn sieve_finds_primes() {
+        assert_eq!(primes_up_to(30), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert_eq!(primes_up_to(1), Vec::<usize>::new());
+        assert_eq!(primes_up_to(10_000).len(), 1229);
+    }
+}
@@ -0,0 +1,230 @@
+use std::ops::Mul;
+
+// Square integer matrices for rotating and reflecting grid coordinates. Points
+// are column vectors, so (a * b).apply(p) applies b first and then a.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Mat<const N: usize> {
+    rows: [[i64; N]; N],
+}
+
+pub type Mat2 = Mat<2>;
+pub type Mat3 = Mat<3>;
+
+impl<const N: usize> Mat<N> {
+    pub const fn new(rows: [[i64; N]; N]) -> Self {
+        Self { rows }
+    }
+
+    pub fn identity() -> Self {
+        let mut rows = [[0; N]; N];
+        for (i, row) in rows.iter_mut().enumerate() {
+            row[i] = 1;
+        }
+        Self { rows }
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> i64 {
+        self.rows[row][column]
+    }
+
+    // For the rotations and reflections built here this is also the inverse
+    pub fn transpose(&self) -> Self {
+        let mut rows = [[0; N]; N];
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = self.rows[j][i];
+            }
+        }
+        Self { rows }
+    }
+
+    pub fn pow(&self, mut exponent: u32) -> Self {
+        let mut result = Self::identity();
+        let mut base = *self;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result * base;
+            }
+            base = base * base;
+            exponent >>= 1;
+        }
+        result
+    }
+
+    pub fn apply(&self, point: [i64; N]) -> [i64; N] {
+        let mut result = [0; N];
+        for (value, row) in result.iter_mut().zip(&self.rows) {
+            *value = row.iter().zip(&point).map(|(a, b)| a * b).sum();
+        }
+        result
+    }
+}
+
+impl<const N: usize> Default for Mat<N> {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl<const N: usize> Mul for Mat<N> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        let mut rows = [[0; N]; N];
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..N).map(|k| self.rows[i][k] * other.rows[k][j]).sum();
+            }
+        }
+        Self { rows }
+    }
+}
+
+// These assume y points up, so on a grid where row numbers grow downwards
+// ROTATE_LEFT turns clockwise on screen
+impl Mat2 {
+    pub const ROTATE_LEFT: Self = Self::new([[0, -1], [1, 0]]);
+    pub const ROTATE_RIGHT: Self = Self::new([[0, 1], [-1, 0]]);
+    pub const FLIP_X: Self = Self::new([[-1, 0], [0, 1]]);
+    pub const FLIP_Y: Self = Self::new([[1, 0], [0, -1]]);
+
+    // Counterclockwise for positive quarter turns, clockwise for negative
+    pub fn rotation(quarter_turns: i64) -> Self {
+        Self::ROTATE_LEFT.pow(quarter_turns.rem_euclid(4) as u32)
+    }
+
+    pub fn determinant(&self) -> i64 {
+        self.rows[0][0] * self.rows[1][1] - self.rows[0][1] * self.rows[1][0]
+    }
+
+    // The eight ways to lay a square tile down: four rotations, each with and
+    // without a flip
+    pub fn orientations() -> [Self; 8] {
+        let mut orientations = [Self::identity(); 8];
+        for quarter_turns in 0..4 {
+            let rotation = Self::rotation(quarter_turns);
+            orientations[quarter_turns as usize] = rotation;
+            orientations[quarter_turns as usize + 4] = rotation * Self::FLIP_X;
+        }
+        orientations
+    }
+}
+
+impl Mat3 {
+    // Counterclockwise when looking down the axis towards the origin
+    pub fn rotation_x(quarter_turns: i64) -> Self {
+        Self::new([[1, 0, 0], [0, 0, -1], [0, 1, 0]]).pow(quarter_turns.rem_euclid(4) as u32)
+    }
+
+    pub fn rotation_y(quarter_turns: i64) -> Self {
+        Self::new([[0, 0, 1], [0, 1, 0], [-1, 0, 0]]).pow(quarter_turns.rem_euclid(4) as u32)
+    }
+
+    pub fn rotation_z(quarter_turns: i64) -> Self {
+        Self::new([[0, -1, 0], [1, 0, 0], [0, 0, 1]]).pow(quarter_turns.rem_euclid(4) as u32)
+    }
+
+    pub fn determinant(&self) -> i64 {
+        let m = &self.rows;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    // The 24 ways to orient a cube without reflecting it. Each one sends the
+    // axes to a signed permutation of themselves, and keeping the determinant
+    // at 1 rules out the mirror images.
+    pub fn rotations() -> Vec<Self> {
+        let permutations = [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ];
+        let mut rotations = Vec::with_capacity(24);
+        for permutation in &permutations {
+            for signs in 0..8 {
+                let mut rows = [[0; 3]; 3];
+                for (row, column) in permutation.iter().enumerate() {
+                    rows[row][*column] = if signs & (1 << row) == 0 { 1 } else { -1 };
+                }
+                let matrix = Self::new(rows);
+                if matrix.determinant() == 1 {
+                    rotations.push(matrix);
+                }
+            }
+        }
+        rotations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn waypoint_rotation() {
+        // 2020 day 12's sample: the waypoint at 4 east, 10 north turns R90
+        assert_eq!(Mat2::ROTATE_RIGHT.apply([10, 4]), [4, -10]);
+        assert_eq!(Mat2::rotation(-1), Mat2::ROTATE_RIGHT);
+        assert_eq!(Mat2::rotation(2).apply([3, -7]), [-3, 7]);
+        assert_eq!(Mat2::ROTATE_LEFT * Mat2::ROTATE_RIGHT, Mat2::identity());
+    }
+
+    #[test]
+    fn transforms_compose_right_to_left() {
+        let flipped_then_rotated = Mat2::ROTATE_LEFT * Mat2::FLIP_X;
+        assert_eq!(
+            flipped_then_rotated.apply([1, 2]),
+            Mat2::ROTATE_LEFT.apply(Mat2::FLIP_X.apply([1, 2]))
+        );
+        assert_eq!(
+            flipped_then_rotated.transpose() * flipped_then_rotated,
+            Mat2::identity()
+        );
+    }
+
+    #[test]
+    fn tile_orientations_are_distinct() {
+        let orientations = Mat2::orientations();
+        let images: HashSet<[i64; 2]> = orientations
+            .iter()
+            .map(|orientation| orientation.apply([1, 2]))
+            .collect();
+        assert_eq!(images.len(), 8);
+        assert_eq!(
+            orientations
+                .iter()
+                .filter(|orientation| orientation.determinant() == -1)
+                .count(),
+            4
+        );
+    }
+
+    #[test]
+    fn cube_rotations() {
+        let rotations = Mat3::rotations();
+        assert_eq!(rotations.len(), 24);
+        let images: HashSet<[i64; 3]> = rotations
+            .iter()
+            .map(|rotation| rotation.apply([1, 2, 3]))
+            .collect();
+        assert_eq!(images.len(), 24);
+
+        // Every product of axis rotations is one of them
+        for x in 0..4 {
+            for y in 0..4 {
+                for z in 0..4 {
+                    let rotation = Mat3::rotation_z(z) * Mat3::rotation_y(y) * Mat3::rotation_x(x);
+                    assert!(rotations.contains(&rotation));
+                }
+            }
+        }
+        assert_eq!(Mat3::rotation_z(1).apply([1, 0, 0]), [0, 1, 0]);
+        assert_eq!(Mat3::rotation_x(1).apply([0, 1, 0]), [0, 0, 1]);
+        assert_eq!(Mat3::rotation_y(1).apply([0, 0, 1]), [1, 0, 0]);
+    }
+}
@@ -0,0 +1,176 @@
+use std::{error::Error, fmt, str::FromStr};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    // Both start from 1, to match how an editor would show the line
+    pub field: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "field {} at column {}: {}",
+            self.field, self.column, self.message
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+// Splits a line into fields separated by runs of any of the delimiter chars and
+// hands them out one at a time, already converted to the type the caller asks
+// for. Errors say which field went wrong and where it starts.
+pub struct Tokenizer<'a> {
+    line: &'a str,
+    delimiters: &'a str,
+    position: usize,
+    field: usize,
+}
+
+impl<'a> Tokenizer<'a> {
+    pub fn new(line: &'a str, delimiters: &'a str) -> Self {
+        Self {
+            line,
+            delimiters,
+            position: 0,
+            field: 0,
+        }
+    }
+
+    fn is_delimiter(&self, c: char) -> bool {
+        self.delimiters.contains(c)
+    }
+
+    fn skip_delimiters(&mut self) {
+        let rest = &self.line[self.position..];
+        self.position += rest.len() - rest.trim_start_matches(|c| self.is_delimiter(c)).len();
+    }
+
+    fn get_error(&self, start: usize, message: String) -> ParseError {
+        ParseError {
+            field: self.field,
+            column: self.line[..start].chars().count() + 1,
+            message,
+        }
+    }
+
+    // True once every field has been read
+    pub fn is_done(&mut self) -> bool {
+        self.skip_delimiters();
+        self.position == self.line.len()
+    }
+
+    pub fn next_str(&mut self) -> Result<&'a str, ParseError> {
+        self.skip_delimiters();
+        self.field += 1;
+        let start = self.position;
+        let rest = &self.line[start..];
+        if rest.is_empty() {
+            return Err(self.get_error(start, String::from("expected a field, found end of line")));
+        }
+
+        let length = rest.find(|c| self.is_delimiter(c)).unwrap_or(rest.len());
+        self.position += length;
+        Ok(&rest[..length])
+    }
+
+    // Parses the next field with FromStr, describing what was expected if it
+    // doesn't parse
+    pub fn next<T: FromStr>(&mut self, description: &str) -> Result<T, ParseError> {
+        let token = self.next_str()?;
+        token.parse().map_err(|_| {
+            self.get_error(
+                self.position - token.len(),
+                format!("expected {}, found '{}'", description, token),
+            )
+        })
+    }
+
+    pub fn next_i64(&mut self) -> Result<i64, ParseError> {
+        self.next("an integer")
+    }
+
+    pub fn next_u64(&mut self) -> Result<u64, ParseError> {
+        self.next("a non-negative integer")
+    }
+
+    pub fn next_usize(&mut self) -> Result<usize, ParseError> {
+        self.next("a non-negative integer")
+    }
+
+    pub fn next_char(&mut self) -> Result<char, ParseError> {
+        self.next("a single character")
+    }
+
+    // Reads the next field and checks that it's exactly literal
+    pub fn expect(&mut self, literal: &str) -> Result<(), ParseError> {
+        let token = self.next_str()?;
+        if token == literal {
+            Ok(())
+        } else {
+            Err(self.get_error(
+                self.position - token.len(),
+                format!("expected '{}', found '{}'", literal, token),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn password_policy_line() {
+        // 2020 day 2's format
+        let mut tokens = Tokenizer::new("1-3 a: abcde", "-: ");
+        assert_eq!(tokens.next_usize(), Ok(1));
+        assert_eq!(tokens.next_usize(), Ok(3));
+        assert_eq!(tokens.next_char(), Ok('a'));
+        assert_eq!(tokens.next_str(), Ok("abcde"));
+        assert!(tokens.is_done());
+    }
+
+    #[test]
+    fn runs_of_delimiters_collapse() {
+        let mut tokens = Tokenizer::new("  mem[8] =  -11 ", "[] =");
+        assert_eq!(tokens.expect("mem"), Ok(()));
+        assert_eq!(tokens.next_u64(), Ok(8));
+        assert_eq!(tokens.next_i64(), Ok(-11));
+        assert!(tokens.is_done());
+    }
+
+    #[test]
+    fn errors_point_at_field() {
+        let mut tokens = Tokenizer::new("move 3 from x", " ");
+        assert_eq!(tokens.expect("move"), Ok(()));
+        assert_eq!(tokens.next_i64(), Ok(3));
+        assert_eq!(
+            tokens.expect("to").unwrap_err().to_string(),
+            "field 3 at column 8: expected 'to', found 'from'"
+        );
+        assert_eq!(
+            tokens.next_usize(),
+            Err(ParseError {
+                field: 4,
+                column: 13,
+                message: String::from("expected a non-negative integer, found 'x'"),
+            })
+        );
+        assert_eq!(
+            tokens.next_str().unwrap_err().to_string(),
+            "field 5 at column 14: expected a field, found end of line"
+        );
+    }
+
+    #[test]
+    fn columns_count_chars() {
+        let mut tokens = Tokenizer::new("héllo wörld", " ");
+        tokens.next_str().unwrap();
+        let error = tokens.next_char().unwrap_err();
+        assert_eq!(error.column, 7);
+    }
+}
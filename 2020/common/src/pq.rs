@@ -0,0 +1,183 @@
+use std::{collections::HashMap, hash::Hash};
+
+// A binary min-heap that remembers where each key sits, so a key's priority can
+// be lowered in place instead of pushing a duplicate and skipping stale entries
+// when they're popped
+#[derive(Clone, Debug)]
+pub struct KeyedHeap<K, P> {
+    heap: Vec<(K, P)>,
+    positions: HashMap<K, usize>,
+}
+
+impl<K, P> KeyedHeap<K, P>
+where
+    K: Clone + Eq + Hash,
+    P: Ord,
+{
+    pub fn new() -> Self {
+        Self {
+            heap: Vec::new(),
+            positions: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn get_priority(&self, key: &K) -> Option<&P> {
+        self.positions
+            .get(key)
+            .map(|position| &self.heap[*position].1)
+    }
+
+    // Adds the key if it isn't queued, or lowers its priority if the new one is
+    // lower. Returns whether anything changed.
+    pub fn push_or_decrease(&mut self, key: K, priority: P) -> bool {
+        match self.positions.get(&key) {
+            Some(position) => {
+                let position = *position;
+                if priority >= self.heap[position].1 {
+                    return false;
+                }
+                self.heap[position].1 = priority;
+                self.sift_up(position);
+            }
+            None => {
+                let position = self.heap.len();
+                self.positions.insert(key.clone(), position);
+                self.heap.push((key, priority));
+                self.sift_up(position);
+            }
+        }
+        true
+    }
+
+    pub fn peek(&self) -> Option<(&K, &P)> {
+        self.heap.first().map(|(key, priority)| (key, priority))
+    }
+
+    pub fn pop(&mut self) -> Option<(K, P)> {
+        if self.heap.is_empty() {
+            return None;
+        }
+
+        let last = self.heap.len() - 1;
+        self.swap(0, last);
+        let (key, priority) = self.heap.pop().unwrap();
+        self.positions.remove(&key);
+        if !self.heap.is_empty() {
+            self.sift_down(0);
+        }
+        Some((key, priority))
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.positions.insert(self.heap[a].0.clone(), a);
+        self.positions.insert(self.heap[b].0.clone(), b);
+    }
+
+    fn sift_up(&mut self, mut position: usize) {
+        while position > 0 {
+            let parent = (position - 1) / 2;
+            if self.heap[position].1 >= self.heap[parent].1 {
+                break;
+            }
+            self.swap(position, parent);
+            position = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut position: usize) {
+        loop {
+            let left = 2 * position + 1;
+            let right = left + 1;
+            let mut smallest = position;
+            if left < self.heap.len() && self.heap[left].1 < self.heap[smallest].1 {
+                smallest = left;
+            }
+            if right < self.heap.len() && self.heap[right].1 < self.heap[smallest].1 {
+                smallest = right;
+            }
+            if smallest == position {
+                break;
+            }
+            self.swap(position, smallest);
+            position = smallest;
+        }
+    }
+}
+
+impl<K, P> Default for KeyedHeap<K, P>
+where
+    K: Clone + Eq + Hash,
+    P: Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KeyedHeap;
+    use crate::gen::Rng;
+
+    #[test]
+    fn pops_in_priority_order() {
+        let mut heap = KeyedHeap::new();
+        for (key, priority) in &[("d", 4), ("b", 2), ("e", 5), ("a", 1), ("c", 3)] {
+            assert!(heap.push_or_decrease(*key, *priority));
+        }
+        let order: Vec<&str> = std::iter::from_fn(|| heap.pop().map(|(key, _)| key)).collect();
+        assert_eq!(order, vec!["a", "b", "c", "d", "e"]);
+    }
+
+    #[test]
+    fn decrease_moves_key_forward() {
+        let mut heap = KeyedHeap::new();
+        heap.push_or_decrease('x', 10);
+        heap.push_or_decrease('y', 5);
+        assert!(!heap.push_or_decrease('x', 20));
+        assert!(heap.push_or_decrease('x', 1));
+        assert_eq!(heap.len(), 2);
+        assert_eq!(heap.get_priority(&'x'), Some(&1));
+        assert_eq!(heap.pop(), Some(('x', 1)));
+        assert_eq!(heap.pop(), Some(('y', 5)));
+        assert_eq!(heap.pop(), None);
+    }
+
+    #[test]
+    fn matches_sorting_under_random_updates() {
+        let mut rng = Rng::new(3);
+        let mut heap = KeyedHeap::new();
+        let mut best = vec![None; 50];
+        for _ in 0..500 {
+            let key = rng.below(50) as usize;
+            let priority = rng.below(1000);
+            heap.push_or_decrease(key, priority);
+            best[key] = Some(best[key].map_or(priority, |current: u64| current.min(priority)));
+        }
+
+        let mut expected: Vec<(u64, usize)> = best
+            .iter()
+            .enumerate()
+            .filter_map(|(key, priority)| priority.map(|priority| (priority, key)))
+            .collect();
+        expected.sort_unstable();
+        let mut popped = Vec::new();
+        while let Some((key, priority)) = heap.pop() {
+            popped.push((priority, key));
+        }
+        let priorities = |entries: &[(u64, usize)]| -> Vec<u64> {
+            entries.iter().map(|(priority, _)| *priority).collect()
+        };
+        assert_eq!(priorities(&popped), priorities(&expected));
+        assert_eq!(popped.len(), expected.len());
+    }
+}
@@ -0,0 +1,181 @@
+use std::{iter::FromIterator, ops::RangeInclusive};
+
+// A set of integers stored as sorted, disjoint, non-adjacent inclusive ranges
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RangeSet {
+    ranges: Vec<(i64, i64)>,
+}
+
+impl RangeSet {
+    pub fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    pub fn insert(&mut self, range: RangeInclusive<i64>) {
+        let (mut start, mut end) = range.into_inner();
+        if start > end {
+            return;
+        }
+
+        // Everything that overlaps or touches the new range gets absorbed
+        let first = self
+            .ranges
+            .partition_point(|(_, existing_end)| existing_end.saturating_add(1) < start);
+        let mut last = first;
+        while last < self.ranges.len() && self.ranges[last].0 <= end.saturating_add(1) {
+            start = start.min(self.ranges[last].0);
+            end = end.max(self.ranges[last].1);
+            last += 1;
+        }
+        self.ranges
+            .splice(first..last, std::iter::once((start, end)));
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        let index = self.ranges.partition_point(|(_, end)| *end < value);
+        self.ranges
+            .get(index)
+            .is_some_and(|(start, _)| *start <= value)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    // Total number of integers in the set, saturating at u64::MAX
+    pub fn len(&self) -> u64 {
+        self.ranges
+            .iter()
+            .map(|(start, end)| end.abs_diff(*start).saturating_add(1))
+            .fold(0, u64::saturating_add)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<i64>> + '_ {
+        self.ranges.iter().map(|(start, end)| *start..=*end)
+    }
+
+    pub fn union(&self, other: &RangeSet) -> RangeSet {
+        let mut union = self.clone();
+        for range in other.iter() {
+            union.insert(range);
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &RangeSet) -> RangeSet {
+        let mut intersection = RangeSet::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a_start, a_end) = self.ranges[i];
+            let (b_start, b_end) = other.ranges[j];
+            let start = a_start.max(b_start);
+            let end = a_end.min(b_end);
+            if start <= end {
+                intersection.ranges.push((start, end));
+            }
+            if a_end < b_end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        intersection
+    }
+
+    // Everything within bounds that isn't in the set
+    pub fn complement(&self, bounds: RangeInclusive<i64>) -> RangeSet {
+        let (bounds_start, bounds_end) = bounds.into_inner();
+        let mut complement = RangeSet::new();
+        let mut next = bounds_start;
+        for (start, end) in &self.ranges {
+            if *end < next {
+                continue;
+            }
+            if *start > bounds_end {
+                break;
+            }
+            if *start > next {
+                complement.ranges.push((next, start - 1));
+            }
+            match end.checked_add(1) {
+                Some(after) => next = after,
+                None => return complement,
+            }
+        }
+        if next <= bounds_end {
+            complement.ranges.push((next, bounds_end));
+        }
+        complement
+    }
+}
+
+impl FromIterator<RangeInclusive<i64>> for RangeSet {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<i64>>>(ranges: I) -> Self {
+        let mut set = RangeSet::new();
+        for range in ranges {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RangeSet;
+
+    fn get_ranges(set: &RangeSet) -> Vec<(i64, i64)> {
+        set.iter()
+            .map(|range| (*range.start(), *range.end()))
+            .collect()
+    }
+
+    #[test]
+    fn insert_merges_overlapping_and_adjacent() {
+        let set: RangeSet = vec![1..=3, 10..=12, 4..=5, 20..=25, 11..=21]
+            .into_iter()
+            .collect();
+        assert_eq!(get_ranges(&set), vec![(1, 5), (10, 25)]);
+        assert_eq!(set.len(), 21);
+        assert!(set.contains(5));
+        assert!(!set.contains(7));
+        assert!(!set.contains(26));
+    }
+
+    #[test]
+    fn ticket_field_ranges() {
+        // 2020 day 16's sample rules
+        let valid: RangeSet = vec![1..=3, 5..=7, 6..=11, 33..=44, 13..=40, 45..=50]
+            .into_iter()
+            .collect();
+        let invalid: i64 = [7, 3, 47, 40, 4, 50, 55, 2, 20, 38, 6, 12]
+            .iter()
+            .filter(|value| !valid.contains(**value))
+            .sum();
+        assert_eq!(invalid, 71);
+    }
+
+    #[test]
+    fn intersection_and_union() {
+        let a: RangeSet = vec![0..=10, 20..=30].into_iter().collect();
+        let b: RangeSet = vec![5..=25].into_iter().collect();
+        assert_eq!(get_ranges(&a.intersection(&b)), vec![(5, 10), (20, 25)]);
+        assert_eq!(get_ranges(&a.union(&b)), vec![(0, 30)]);
+        assert!(a.intersection(&RangeSet::new()).is_empty());
+    }
+
+    #[test]
+    fn complement_within_bounds() {
+        let set: RangeSet = vec![2..=4, 8..=9, 15..=20].into_iter().collect();
+        assert_eq!(
+            get_ranges(&set.complement(0..=16)),
+            vec![(0, 1), (5, 7), (10, 14)]
+        );
+        assert_eq!(get_ranges(&set.complement(3..=8)), vec![(5, 7)]);
+        assert_eq!(
+            get_ranges(&RangeSet::new().complement(i64::MIN..=i64::MAX)).len(),
+            1
+        );
+        let full: RangeSet = vec![i64::MIN..=i64::MAX].into_iter().collect();
+        assert!(full.complement(0..=10).is_empty());
+    }
+}
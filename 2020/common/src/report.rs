@@ -0,0 +1,204 @@
+use std::fmt::{self, Display};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+// Builds a GitHub-flavored Markdown table whose columns are padded to line up,
+// so it reads as well in a terminal as it renders in an issue
+pub struct MarkdownTable {
+    headers: Vec<String>,
+    alignments: Vec<Align>,
+    rows: Vec<Vec<String>>,
+}
+
+// Adds cells one at a time; the row joins the table when finished
+pub struct RowBuilder<'a> {
+    table: &'a mut MarkdownTable,
+    cells: Vec<String>,
+}
+
+impl<'a> RowBuilder<'a> {
+    pub fn cell<T: Display>(mut self, value: T) -> Self {
+        self.cells.push(value.to_string());
+        self
+    }
+
+    pub fn finish(self) {
+        let cells = self.cells;
+        self.table.add_row(cells);
+    }
+}
+
+fn escape(cell: &str) -> String {
+    cell.replace('|', "\\|")
+}
+
+impl MarkdownTable {
+    // Every column starts out left aligned
+    pub fn new(headers: &[&str]) -> Self {
+        Self {
+            headers: headers.iter().map(|header| escape(header)).collect(),
+            alignments: vec![Align::Left; headers.len()],
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn align(mut self, column: usize, align: Align) -> Self {
+        self.alignments[column] = align;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    // Short rows are padded out with empty cells
+    pub fn add_row<I, T>(&mut self, cells: I)
+    where
+        I: IntoIterator<Item = T>,
+        T: Display,
+    {
+        let mut row: Vec<String> = cells
+            .into_iter()
+            .map(|cell| escape(&cell.to_string()))
+            .collect();
+        assert!(
+            row.len() <= self.headers.len(),
+            "Row has {} cells but the table only has {} columns",
+            row.len(),
+            self.headers.len()
+        );
+        row.resize(self.headers.len(), String::new());
+        self.rows.push(row);
+    }
+
+    pub fn row(&mut self) -> RowBuilder<'_> {
+        RowBuilder {
+            table: self,
+            cells: Vec::new(),
+        }
+    }
+
+    fn get_widths(&self) -> Vec<usize> {
+        (0..self.headers.len())
+            .map(|column| {
+                self.rows
+                    .iter()
+                    .map(|row| &row[column])
+                    .chain(std::iter::once(&self.headers[column]))
+                    .map(|cell| cell.chars().count())
+                    .max()
+                    .unwrap_or(0)
+                    // The alignment row needs room for at least ":-:"
+                    .max(3)
+            })
+            .collect()
+    }
+}
+
+fn write_row(
+    f: &mut fmt::Formatter,
+    cells: &[String],
+    widths: &[usize],
+    alignments: &[Align],
+) -> fmt::Result {
+    write!(f, "|")?;
+    for ((cell, width), align) in cells.iter().zip(widths).zip(alignments) {
+        match align {
+            Align::Left => write!(f, " {cell:<width$} |")?,
+            Align::Right => write!(f, " {cell:>width$} |")?,
+            Align::Center => write!(f, " {cell:^width$} |")?,
+        }
+    }
+    writeln!(f)
+}
+
+impl Display for MarkdownTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let widths = self.get_widths();
+        write_row(f, &self.headers, &widths, &self.alignments)?;
+
+        write!(f, "|")?;
+        for (width, align) in widths.iter().zip(&self.alignments) {
+            let dashes = "-".repeat(width - 1);
+            match align {
+                Align::Left => write!(f, " :{dashes} |")?,
+                Align::Right => write!(f, " {dashes}: |")?,
+                Align::Center => write!(f, " :{}: |", &dashes[1..])?,
+            }
+        }
+        writeln!(f)?;
+
+        for row in &self.rows {
+            write_row(f, row, &widths, &self.alignments)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn columns_line_up() {
+        let mut table = MarkdownTable::new(&["Day", "Answer", "Time"])
+            .align(1, Align::Right)
+            .align(2, Align::Center);
+        table.add_row(["day-01", "1007331", "1.2ms"]);
+        table.row().cell("day-11").cell(2361).cell("40ms").finish();
+        assert_eq!(table.len(), 2);
+        assert_eq!(
+            table.to_string(),
+            concat!(
+                "| Day    |  Answer | Time  |\n",
+                "| :----- | ------: | :---: |\n",
+                "| day-01 | 1007331 | 1.2ms |\n",
+                "| day-11 |    2361 | 40ms  |\n",
+            )
+        );
+    }
+
+    #[test]
+    fn table_snapshot() {
+        let mut table = MarkdownTable::new(&["Layout", "Rule", "Occupied", "Took"])
+            .align(2, Align::Right)
+            .align(3, Align::Center);
+        table.add_row(["input.txt", "adjacent", "2361", "12.5ms"]);
+        table.add_row(["input.txt", "line of sight", "2119", "9.8ms"]);
+        table.row().cell("sample.txt").cell("hex").cell(44).finish();
+        table
+            .row()
+            .cell("µ-layout")
+            .cell("adjacent")
+            .cell(0)
+            .cell("0ns")
+            .finish();
+        insta::assert_snapshot!(table.to_string());
+    }
+
+    #[test]
+    fn pipes_are_escaped_and_short_rows_padded() {
+        let mut table = MarkdownTable::new(&["a|b", "c"]);
+        table.row().cell("x|y").finish();
+        assert_eq!(
+            table.to_string(),
+            "| a\\|b | c   |\n| :--- | :-- |\n| x\\|y |     |\n"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Row has 2 cells but the table only has 1 columns")]
+    fn long_rows_panic() {
+        let mut table = MarkdownTable::new(&["only"]);
+        table.add_row(vec![1, 2]);
+    }
+}
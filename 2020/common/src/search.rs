@@ -0,0 +1,373 @@
+use crate::pq::KeyedHeap;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+// Describes a search space so that the same puzzle can be handed to any of the
+// strategies below
+pub trait SearchProblem {
+    type State: Clone + Eq + Hash;
+    type Cost: Copy + Ord + Add<Output = Self::Cost> + Default;
+
+    fn start(&self) -> Self::State;
+
+    // Each state reachable in one step, along with the cost of that step
+    fn successors(&self, state: &Self::State) -> Vec<(Self::State, Self::Cost)>;
+
+    fn is_goal(&self, state: &Self::State) -> bool;
+
+    // A lower bound on the cost from state to the nearest goal. The default of
+    // zero is always safe. Anything higher must never overestimate, and for
+    // dijkstra it also mustn't drop by more than the cost of a step, or the
+    // cheapest goal can be missed.
+    fn heuristic(&self, _state: &Self::State) -> Self::Cost {
+        Self::Cost::default()
+    }
+
+    // Returning true drops a state (reached at the given cost) without
+    // exploring it
+    fn prune(&self, _state: &Self::State, _cost: Self::Cost) -> bool {
+        false
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Solution<S, C> {
+    // From the start state to the goal, inclusive
+    pub path: Vec<S>,
+    pub cost: C,
+    // How many states were expanded along the way
+    pub explored: usize,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Strategy {
+    Bfs,
+    Dfs,
+    Dijkstra,
+    BranchAndBound,
+}
+
+impl Strategy {
+    pub const NAMES: [&'static str; 4] = ["bfs", "dfs", "dijkstra", "branch-and-bound"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "bfs" => Some(Strategy::Bfs),
+            "dfs" => Some(Strategy::Dfs),
+            "dijkstra" => Some(Strategy::Dijkstra),
+            "branch-and-bound" => Some(Strategy::BranchAndBound),
+            _ => None,
+        }
+    }
+}
+
+type SearchSolution<P> = Solution<<P as SearchProblem>::State, <P as SearchProblem>::Cost>;
+
+pub fn solve<P: SearchProblem>(problem: &P, strategy: Strategy) -> Option<SearchSolution<P>> {
+    match strategy {
+        Strategy::Bfs => bfs(problem),
+        Strategy::Dfs => dfs(problem),
+        Strategy::Dijkstra => dijkstra(problem),
+        Strategy::BranchAndBound => branch_and_bound(problem),
+    }
+}
+
+// Walks parent links back from the goal
+fn get_path<S: Clone + Eq + Hash>(parents: &HashMap<S, Option<S>>, goal: S) -> Vec<S> {
+    let mut path = vec![goal];
+    while let Some(Some(parent)) = parents.get(path.last().unwrap()) {
+        path.push(parent.clone());
+    }
+    path.reverse();
+    path
+}
+
+// The goal with the fewest steps, ignoring step costs
+pub fn bfs<P: SearchProblem>(problem: &P) -> Option<SearchSolution<P>> {
+    let start = problem.start();
+    let mut parents = HashMap::new();
+    parents.insert(start.clone(), None);
+    let mut queue = VecDeque::new();
+    queue.push_back((start, P::Cost::default()));
+
+    let mut explored = 0;
+    while let Some((state, cost)) = queue.pop_front() {
+        if problem.is_goal(&state) {
+            return Some(Solution {
+                path: get_path(&parents, state),
+                cost,
+                explored,
+            });
+        }
+
+        explored += 1;
+        for (next, step) in problem.successors(&state) {
+            let next_cost = cost + step;
+            if parents.contains_key(&next) || problem.prune(&next, next_cost) {
+                continue;
+            }
+            parents.insert(next.clone(), Some(state.clone()));
+            queue.push_back((next, next_cost));
+        }
+    }
+    None
+}
+
+// The first goal found by always following the earliest unexplored successor
+pub fn dfs<P: SearchProblem>(problem: &P) -> Option<SearchSolution<P>> {
+    let mut parents = HashMap::new();
+    let mut stack = vec![(problem.start(), None, P::Cost::default())];
+
+    let mut explored = 0;
+    while let Some((state, parent, cost)) = stack.pop() {
+        if parents.contains_key(&state) {
+            continue;
+        }
+        parents.insert(state.clone(), parent);
+        if problem.is_goal(&state) {
+            return Some(Solution {
+                path: get_path(&parents, state),
+                cost,
+                explored,
+            });
+        }
+
+        explored += 1;
+        // Reversed so that the first successor comes off the stack first
+        for (next, step) in problem.successors(&state).into_iter().rev() {
+            let next_cost = cost + step;
+            if !parents.contains_key(&next) && !problem.prune(&next, next_cost) {
+                stack.push((next, Some(state.clone()), next_cost));
+            }
+        }
+    }
+    None
+}
+
+// The cheapest goal. With a heuristic this is A*.
+pub fn dijkstra<P: SearchProblem>(problem: &P) -> Option<SearchSolution<P>> {
+    let start = problem.start();
+    let mut costs = HashMap::new();
+    let mut parents = HashMap::new();
+    let mut done = HashSet::new();
+    costs.insert(start.clone(), P::Cost::default());
+    parents.insert(start.clone(), None);
+    let mut queue = KeyedHeap::new();
+    queue.push_or_decrease(start.clone(), problem.heuristic(&start));
+
+    let mut explored = 0;
+    while let Some((state, _)) = queue.pop() {
+        let cost = costs[&state];
+        if problem.is_goal(&state) {
+            return Some(Solution {
+                path: get_path(&parents, state),
+                cost,
+                explored,
+            });
+        }
+
+        explored += 1;
+        for (next, step) in problem.successors(&state) {
+            let next_cost = cost + step;
+            if done.contains(&next)
+                || costs.get(&next).is_some_and(|best| *best <= next_cost)
+                || problem.prune(&next, next_cost)
+            {
+                continue;
+            }
+            costs.insert(next.clone(), next_cost);
+            parents.insert(next.clone(), Some(state.clone()));
+            let priority = next_cost + problem.heuristic(&next);
+            queue.push_or_decrease(next, priority);
+        }
+        done.insert(state);
+    }
+    None
+}
+
+struct BranchAndBound<'a, P: SearchProblem> {
+    problem: &'a P,
+    // The cheapest cost each state has been reached at so far, so a state is
+    // only explored again if it's reached more cheaply
+    best_costs: HashMap<P::State, P::Cost>,
+    path: Vec<P::State>,
+    best: Option<SearchSolution<P>>,
+    explored: usize,
+}
+
+impl<P: SearchProblem> BranchAndBound<'_, P> {
+    fn visit(&mut self, state: P::State, cost: P::Cost) {
+        if self
+            .best
+            .as_ref()
+            .is_some_and(|best| cost + self.problem.heuristic(&state) >= best.cost)
+        {
+            return;
+        }
+        if self
+            .best_costs
+            .get(&state)
+            .is_some_and(|best| *best <= cost)
+        {
+            return;
+        }
+        self.best_costs.insert(state.clone(), cost);
+
+        self.path.push(state.clone());
+        if self.problem.is_goal(&state) {
+            self.best = Some(Solution {
+                path: self.path.clone(),
+                cost,
+                explored: 0,
+            });
+        } else {
+            self.explored += 1;
+            for (next, step) in self.problem.successors(&state) {
+                let next_cost = cost + step;
+                if !self.problem.prune(&next, next_cost) {
+                    self.visit(next, next_cost);
+                }
+            }
+        }
+        self.path.pop();
+    }
+}
+
+// The cheapest goal, found depth-first: once any goal is known, branches whose
+// cost plus heuristic can't beat it are cut off. Uses far less memory than
+// dijkstra when good solutions turn up early.
+pub fn branch_and_bound<P: SearchProblem>(problem: &P) -> Option<SearchSolution<P>> {
+    let mut search = BranchAndBound {
+        problem,
+        best_costs: HashMap::new(),
+        path: Vec::new(),
+        best: None,
+        explored: 0,
+    };
+    search.visit(problem.start(), P::Cost::default());
+
+    let explored = search.explored;
+    search.best.map(|best| Solution { explored, ..best })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Walking a grid from the top-left to the bottom-right corner, where
+    // entering a cell costs its digit (2021-style chiton risk levels)
+    struct Maze {
+        cells: Vec<Vec<u32>>,
+    }
+
+    impl Maze {
+        fn new(rows: &[&str]) -> Self {
+            Self {
+                cells: rows
+                    .iter()
+                    .map(|row| row.chars().map(|c| c.to_digit(10).unwrap()).collect())
+                    .collect(),
+            }
+        }
+    }
+
+    impl SearchProblem for Maze {
+        type State = (usize, usize);
+        type Cost = u32;
+
+        fn start(&self) -> Self::State {
+            (0, 0)
+        }
+
+        fn successors(&self, (row, column): &Self::State) -> Vec<(Self::State, u32)> {
+            let mut successors = Vec::new();
+            let (row, column) = (*row, *column);
+            if row + 1 < self.cells.len() {
+                successors.push((row + 1, column));
+            }
+            if column + 1 < self.cells[0].len() {
+                successors.push((row, column + 1));
+            }
+            if row > 0 {
+                successors.push((row - 1, column));
+            }
+            if column > 0 {
+                successors.push((row, column - 1));
+            }
+            successors
+                .into_iter()
+                .filter(|(row, column)| self.cells[*row][*column] != 0)
+                .map(|(row, column)| ((row, column), self.cells[row][column]))
+                .collect()
+        }
+
+        fn is_goal(&self, state: &Self::State) -> bool {
+            *state == (self.cells.len() - 1, self.cells[0].len() - 1)
+        }
+
+        fn heuristic(&self, (row, column): &Self::State) -> u32 {
+            ((self.cells.len() - 1 - row) + (self.cells[0].len() - 1 - column)) as u32
+        }
+    }
+
+    fn get_sample() -> Maze {
+        Maze::new(&[
+            "1163751742",
+            "1381373672",
+            "2136511328",
+            "3694931569",
+            "7463417111",
+            "1319128137",
+            "1359912421",
+            "3125421639",
+            "1293138521",
+            "2311944581",
+        ])
+    }
+
+    #[test]
+    fn cheapest_strategies_agree() {
+        let maze = get_sample();
+        for strategy in &[Strategy::Dijkstra, Strategy::BranchAndBound] {
+            let solution = solve(&maze, *strategy).unwrap();
+            assert_eq!(solution.cost, 40);
+            assert_eq!(solution.path.first(), Some(&(0, 0)));
+            assert_eq!(solution.path.last(), Some(&(9, 9)));
+            let path_cost: u32 = solution.path[1..]
+                .iter()
+                .map(|(row, column)| maze.cells[*row][*column])
+                .sum();
+            assert_eq!(path_cost, 40);
+        }
+    }
+
+    #[test]
+    fn bfs_finds_fewest_steps() {
+        let solution = bfs(&get_sample()).unwrap();
+        assert_eq!(solution.path.len(), 19);
+    }
+
+    #[test]
+    fn every_strategy_reaches_goal() {
+        let maze = get_sample();
+        for name in &Strategy::NAMES {
+            let solution = solve(&maze, Strategy::from_name(name).unwrap()).unwrap();
+            assert_eq!(solution.path.last(), Some(&(9, 9)));
+            for pair in solution.path.windows(2) {
+                let (a, b) = (pair[0], pair[1]);
+                assert_eq!(a.0.abs_diff(b.0) + a.1.abs_diff(b.1), 1);
+            }
+        }
+    }
+
+    #[test]
+    fn walls_make_goal_unreachable() {
+        let maze = Maze::new(&["110", "000", "001"]);
+        for name in &Strategy::NAMES {
+            assert_eq!(solve(&maze, Strategy::from_name(name).unwrap()), None);
+        }
+    }
+}
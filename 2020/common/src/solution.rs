@@ -0,0 +1,106 @@
+use std::collections::BTreeMap;
+
+// A day's puzzle as a library: each part answered from the text of the input,
+// so that runners, tests and benchmarks can call any day without going through
+// its binary and its command line
+pub trait Solution: Sync {
+    fn part1(&self, input: &str) -> String;
+    fn part2(&self, input: &str) -> String;
+}
+
+// Solutions by year and day, iterated in that order
+#[derive(Default)]
+pub struct Registry {
+    solutions: BTreeMap<(u16, u8), &'static dyn Solution>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, year: u16, day: u8, solution: &'static dyn Solution) {
+        let previous = self.solutions.insert((year, day), solution);
+        assert!(
+            previous.is_none(),
+            "Failed to register {} day {}: it already has a solution",
+            year,
+            day
+        );
+    }
+
+    pub fn get(&self, year: u16, day: u8) -> Option<&'static dyn Solution> {
+        self.solutions.get(&(year, day)).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ((u16, u8), &'static dyn Solution)> + '_ {
+        self.solutions
+            .iter()
+            .map(|(puzzle, solution)| (*puzzle, *solution))
+    }
+
+    pub fn len(&self) -> usize {
+        self.solutions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.solutions.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Lines;
+
+    impl Solution for Lines {
+        fn part1(&self, input: &str) -> String {
+            input.lines().count().to_string()
+        }
+
+        fn part2(&self, input: &str) -> String {
+            input.len().to_string()
+        }
+    }
+
+    struct Words;
+
+    impl Solution for Words {
+        fn part1(&self, input: &str) -> String {
+            input.split_whitespace().count().to_string()
+        }
+
+        fn part2(&self, input: &str) -> String {
+            input
+                .split_whitespace()
+                .last()
+                .unwrap_or_default()
+                .to_owned()
+        }
+    }
+
+    #[test]
+    fn finds_solutions_by_puzzle() {
+        let mut registry = Registry::new();
+        registry.register(2020, 2, &Words);
+        registry.register(2017, 11, &Lines);
+        assert_eq!(registry.len(), 2);
+
+        let solution = registry.get(2020, 2).unwrap();
+        assert_eq!(solution.part1("a b\nc"), "3");
+        assert_eq!(solution.part2("a b\nc"), "c");
+        assert!(registry.get(2020, 3).is_none());
+
+        let puzzles: Vec<(u16, u8)> = registry.iter().map(|(puzzle, _)| puzzle).collect();
+        assert_eq!(puzzles, [(2017, 11), (2020, 2)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Failed to register 2020 day 2")]
+    fn puzzles_have_one_solution() {
+        let mut registry = Registry::new();
+        registry.register(2020, 2, &Words);
+        registry.register(2020, 2, &Lines);
+    }
+}
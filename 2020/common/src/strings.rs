@@ -0,0 +1,174 @@
+// Everything here works on chars rather than bytes, so multi-byte text is
+// compared the way it reads
+
+// Every run of size consecutive chars, in order. Like slice::windows, a size of
+// zero isn't allowed.
+pub fn windows(text: &str, size: usize) -> impl Iterator<Item = &str> {
+    assert!(size > 0, "Window size must be positive");
+    let boundaries: Vec<usize> = text
+        .char_indices()
+        .map(|(index, _)| index)
+        .chain(std::iter::once(text.len()))
+        .collect();
+    let count = boundaries.len().saturating_sub(size);
+    (0..count).map(move |start| &text[boundaries[start]..boundaries[start + size]])
+}
+
+// How many times each char appears, sorted by char. Two strings have the same
+// signature exactly when they're anagrams of each other.
+pub fn frequency_signature(text: &str) -> Vec<(char, usize)> {
+    let mut chars: Vec<char> = text.chars().collect();
+    chars.sort_unstable();
+    let mut signature: Vec<(char, usize)> = Vec::new();
+    for c in chars {
+        match signature.last_mut() {
+            Some((last, count)) if *last == c => *count += 1,
+            _ => signature.push((c, 1)),
+        }
+    }
+    signature
+}
+
+// Number of positions where the chars differ, or None if the lengths do
+pub fn hamming_distance(a: &str, b: &str) -> Option<usize> {
+    let mut a = a.chars();
+    let mut b = b.chars();
+    let mut distance = 0;
+    loop {
+        match (a.next(), b.next()) {
+            (Some(a), Some(b)) => distance += usize::from(a != b),
+            (None, None) => return Some(distance),
+            _ => return None,
+        }
+    }
+}
+
+// The chars that match at the same position in both strings
+pub fn matching_chars(a: &str, b: &str) -> String {
+    a.chars()
+        .zip(b.chars())
+        .filter(|(a, b)| a == b)
+        .map(|(c, _)| c)
+        .collect()
+}
+
+// Levenshtein distance: the fewest single-char insertions, deletions and
+// substitutions that turn a into b
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    // Only the previous row of the table is ever needed
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, a_char) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+// One of the longest sequences of chars that appears in both strings in order,
+// though not necessarily contiguously. When there are several, the one that
+// takes chars from a earliest is returned.
+pub fn longest_common_subsequence(a: &str, b: &str) -> String {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    // lengths[i][j] is the LCS length of a[i..] and b[j..]
+    let mut lengths = vec![vec![0_usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lengths[i][j] = if a[i] == b[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut subsequence = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            subsequence.push(a[i]);
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    subsequence
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn windows_follow_chars() {
+        assert_eq!(
+            windows("abcde", 3).collect::<Vec<_>>(),
+            vec!["abc", "bcd", "cde"]
+        );
+        assert_eq!(windows("héllo", 2).nth(1), Some("él"));
+        assert_eq!(windows("ab", 3).count(), 0);
+        assert_eq!(windows("", 1).count(), 0);
+    }
+
+    #[test]
+    fn anagram_passphrases() {
+        // 2017 day 4 part 2's samples
+        let is_valid = |passphrase: &str| {
+            let mut seen = HashSet::new();
+            passphrase
+                .split_whitespace()
+                .all(|word| seen.insert(frequency_signature(word)))
+        };
+        assert!(is_valid("abcde fghij"));
+        assert!(!is_valid("abcde xyz ecdab"));
+        assert!(is_valid("a ab abc abd abf abj"));
+        assert!(is_valid("iiii oiii ooii oooi oooo"));
+        assert!(!is_valid("oiii ioii iioi iiio"));
+    }
+
+    #[test]
+    fn box_ids_differing_by_one() {
+        // 2018 day 2 part 2's sample
+        let ids = [
+            "abcde", "fghij", "klmno", "pqrst", "fguij", "axcye", "wvxyz",
+        ];
+        let mut common = None;
+        for (index, a) in ids.iter().enumerate() {
+            for b in &ids[index + 1..] {
+                if hamming_distance(a, b) == Some(1) {
+                    common = Some(matching_chars(a, b));
+                }
+            }
+        }
+        assert_eq!(common.as_deref(), Some("fgij"));
+        assert_eq!(hamming_distance("abc", "abcd"), None);
+    }
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("flaw", "lawn"), 2);
+        assert_eq!(edit_distance("same", "same"), 0);
+    }
+
+    #[test]
+    fn common_subsequences() {
+        assert_eq!(longest_common_subsequence("ABCBDAB", "BDCABA").len(), 4);
+        assert_eq!(longest_common_subsequence("AGGTAB", "GXTXAYB"), "GTAB");
+        assert_eq!(longest_common_subsequence("abc", "xyz"), "");
+    }
+}
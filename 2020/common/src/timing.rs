@@ -0,0 +1,393 @@
+use std::{
+    cell::RefCell,
+    fmt::{self, Display, Write},
+    hint,
+    time::{Duration, Instant},
+};
+
+use tracing::{span::EnteredSpan, Span};
+use tracing_chrome::{ChromeLayerBuilder, FlushGuard};
+use tracing_subscriber::{layer::SubscriberExt, registry::Registry};
+
+// Scopes that are open at the same time nest, so timing a phase inside another
+// timed phase records it as a child. Repeated scopes with the same name under
+// the same parent share a node, which keeps per-generation phases down to one
+// line each in the report.
+
+struct Node {
+    name: &'static str,
+    children: Vec<usize>,
+    parent: Option<usize>,
+    total: Duration,
+    calls: usize,
+}
+
+#[derive(Default)]
+struct Tree {
+    nodes: Vec<Node>,
+    roots: Vec<usize>,
+    current: Option<usize>,
+}
+
+impl Tree {
+    fn enter(&mut self, name: &'static str) -> usize {
+        let siblings = match self.current {
+            Some(parent) => &self.nodes[parent].children,
+            None => &self.roots,
+        };
+        let existing = siblings
+            .iter()
+            .copied()
+            .find(|index| self.nodes[*index].name == name);
+
+        let index = existing.unwrap_or_else(|| {
+            let index = self.nodes.len();
+            self.nodes.push(Node {
+                name,
+                children: Vec::new(),
+                parent: self.current,
+                total: Duration::default(),
+                calls: 0,
+            });
+            match self.current {
+                Some(parent) => self.nodes[parent].children.push(index),
+                None => self.roots.push(index),
+            }
+            index
+        });
+        self.current = Some(index);
+        index
+    }
+
+    fn exit(&mut self, index: usize, elapsed: Duration) {
+        let node = &mut self.nodes[index];
+        node.total += elapsed;
+        node.calls += 1;
+        self.current = node.parent;
+    }
+
+    fn write_node(&self, report: &mut String, index: usize, depth: usize) {
+        let node = &self.nodes[index];
+        write!(
+            report,
+            "{:indent$}{}: {:.2?}",
+            "",
+            node.name,
+            node.total,
+            indent = depth * 2
+        )
+        .unwrap();
+        if node.calls > 1 {
+            write!(report, " over {} calls", node.calls).unwrap();
+        }
+        if let Some(parent) = node.parent {
+            let parent_total = self.nodes[parent].total.as_secs_f64();
+            if parent_total > 0.0 {
+                write!(
+                    report,
+                    " ({:.1}%)",
+                    100.0 * node.total.as_secs_f64() / parent_total
+                )
+                .unwrap();
+            }
+        }
+        report.push('\n');
+
+        for child in &node.children {
+            self.write_node(report, *child, depth + 1);
+        }
+    }
+}
+
+thread_local! {
+    static TREE: RefCell<Tree> = RefCell::new(Tree::default());
+}
+
+// Times everything until it's dropped
+pub struct Scope {
+    index: usize,
+    start: Instant,
+    _span: EnteredSpan,
+}
+
+impl Scope {
+    pub fn new(name: &'static str) -> Self {
+        Self::traced(name, Span::none())
+    }
+
+    // Also stays inside span until dropped, which is what puts the scope in a
+    // trace. scope! builds both from the one name.
+    pub fn traced(name: &'static str, span: Span) -> Self {
+        let index = TREE.with(|tree| tree.borrow_mut().enter(name));
+        Self {
+            index,
+            start: Instant::now(),
+            _span: span.entered(),
+        }
+    }
+}
+
+// A Scope that also shows up in a trace written by trace_to. The name has to
+// be a literal, since tracing fixes span names at compile time.
+#[macro_export]
+macro_rules! scope {
+    ($name:literal) => {
+        $crate::timing::Scope::traced($name, $crate::tracing::info_span!($name))
+    };
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed();
+        TREE.with(|tree| tree.borrow_mut().exit(self.index, elapsed));
+    }
+}
+
+// An indented breakdown of every scope on this thread so far, each with its
+// share of its parent's time
+pub fn report() -> String {
+    TREE.with(|tree| {
+        let tree = tree.borrow();
+        let mut report = String::new();
+        for root in &tree.roots {
+            tree.write_node(&mut report, *root, 0);
+        }
+        report
+    })
+}
+
+// Scopes on every thread get written to filename in Chrome's trace format,
+// which chrome://tracing and Perfetto can open, until the guard is dropped.
+// Anything that exits the process first leaves the file incomplete.
+pub struct TraceGuard {
+    _flush: FlushGuard,
+}
+
+pub fn trace_to(filename: &str) -> TraceGuard {
+    let (layer, flush) = ChromeLayerBuilder::new()
+        .file(filename)
+        .include_args(true)
+        .build();
+    tracing::subscriber::set_global_default(Registry::default().with(layer))
+        .expect("Failed to install trace subscriber");
+    TraceGuard { _flush: flush }
+}
+
+pub fn reset() {
+    TREE.with(|tree| *tree.borrow_mut() = Tree::default());
+}
+
+// How long repeated runs of the same work took
+#[derive(Debug)]
+pub struct Summary {
+    pub runs: usize,
+    pub min: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "min {:.2?}, mean {:.2?}, stddev {:.2?} over {} runs",
+            self.min, self.mean, self.stddev, self.runs
+        )
+    }
+}
+
+// Runs f once to warm up caches and the allocator, then measures it
+pub fn repeat<T, F>(runs: usize, mut f: F) -> Summary
+where
+    F: FnMut() -> T,
+{
+    hint::black_box(f());
+    measure(runs, f)
+}
+
+// Times runs calls to f, for callers that have already warmed it up. Whatever
+// f returns is dropped outside the timing, and only after going through
+// black_box so the work can't be optimized away.
+pub fn measure<T, F>(runs: usize, mut f: F) -> Summary
+where
+    F: FnMut() -> T,
+{
+    assert!(runs > 0, "Failed to repeat zero times");
+    let mut elapsed = Vec::with_capacity(runs);
+    for _ in 0..runs {
+        let start = Instant::now();
+        let result = f();
+        elapsed.push(start.elapsed());
+        hint::black_box(result);
+    }
+    summarize(&elapsed)
+}
+
+fn summarize(elapsed: &[Duration]) -> Summary {
+    let runs = elapsed.len();
+    let seconds: Vec<f64> = elapsed.iter().map(Duration::as_secs_f64).collect();
+    let mean = seconds.iter().sum::<f64>() / runs as f64;
+    let variance = seconds
+        .iter()
+        .map(|seconds| (seconds - mean).powi(2))
+        .sum::<f64>()
+        / runs as f64;
+    Summary {
+        runs,
+        min: elapsed.iter().copied().min().unwrap(),
+        mean: Duration::from_secs_f64(mean),
+        stddev: Duration::from_secs_f64(variance.sqrt()),
+    }
+}
+
+// Reads a number followed by a unit (ns, us, ms, s or m), such as 500ms or
+// 1.5s, for command-line time limits
+pub fn parse_duration(text: &str) -> Option<Duration> {
+    let text = text.trim();
+    let unit_start = text.find(|c: char| c.is_ascii_alphabetic())?;
+    let value: f64 = text[..unit_start].trim().parse().ok()?;
+    let scale = match &text[unit_start..] {
+        "ns" => 1e-9,
+        "us" => 1e-6,
+        "ms" => 1e-3,
+        "s" => 1.0,
+        "m" => 60.0,
+        _ => return None,
+    };
+    Duration::try_from_secs_f64(value * scale).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Drops the timings from a report so it can be compared exactly
+    fn get_shape(report: &str) -> Vec<String> {
+        report
+            .lines()
+            .map(|line| {
+                let name_end = line.find(':').unwrap();
+                let calls = line
+                    .split(" over ")
+                    .nth(1)
+                    .map(|rest| rest.split(' ').next().unwrap());
+                match calls {
+                    Some(calls) => format!("{} x{}", &line[..name_end], calls),
+                    None => line[..name_end].to_string(),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn summarizes_runs() {
+        let summary = summarize(&[
+            Duration::from_millis(2),
+            Duration::from_millis(4),
+            Duration::from_millis(6),
+        ]);
+        assert_eq!(summary.runs, 3);
+        assert_eq!(summary.min, Duration::from_millis(2));
+        assert_eq!(summary.mean.as_micros(), 4000);
+        // Population standard deviation of 2, 4 and 6 is sqrt(8/3)
+        assert_eq!(summary.stddev.as_micros(), 1632);
+
+        let mut calls = 0;
+        let summary = repeat(5, || calls += 1);
+        assert_eq!(summary.runs, 5);
+        assert_eq!(calls, 6);
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("500ms"), Some(Duration::from_millis(500)));
+        assert_eq!(parse_duration("1.5s"), Some(Duration::from_millis(1500)));
+        assert_eq!(parse_duration("2m"), Some(Duration::from_secs(120)));
+        assert_eq!(parse_duration("250us"), Some(Duration::from_micros(250)));
+        assert_eq!(parse_duration("500"), None);
+        assert_eq!(parse_duration("fast"), None);
+        assert_eq!(parse_duration("5h"), None);
+        assert_eq!(parse_duration("-1s"), None);
+    }
+
+    #[test]
+    fn nested_scopes_build_tree() {
+        reset();
+        {
+            let _parse = Scope::new("parse");
+        }
+        {
+            let _run = Scope::new("run");
+            for _ in 0..3 {
+                let _generation = Scope::new("generation");
+                {
+                    let _count = Scope::new("count");
+                }
+                let _apply = Scope::new("apply");
+            }
+        }
+
+        assert_eq!(
+            get_shape(&report()),
+            vec![
+                "parse",
+                "run",
+                "  generation x3",
+                "    count x3",
+                "    apply x3"
+            ]
+        );
+    }
+
+    #[test]
+    fn same_name_under_different_parents_is_separate() {
+        reset();
+        {
+            let _a = Scope::new("a");
+            let _work = Scope::new("work");
+        }
+        {
+            let _b = Scope::new("b");
+            let _work = Scope::new("work");
+        }
+        assert_eq!(get_shape(&report()), vec!["a", "  work", "b", "  work"]);
+
+        reset();
+        assert!(report().is_empty());
+    }
+
+    #[test]
+    fn children_report_share_of_parent() {
+        reset();
+        {
+            let _outer = Scope::new("outer");
+            let _inner = Scope::new("inner");
+            std::thread::sleep(Duration::from_millis(2));
+        }
+        let report = report();
+        let inner = report.lines().nth(1).unwrap();
+        assert!(inner.starts_with("  inner: "));
+        assert!(inner.ends_with("%)"));
+    }
+
+    // The only test allowed to install the global subscriber
+    #[test]
+    fn traced_scopes_reach_the_trace() {
+        let path = std::env::temp_dir().join(format!("common-trace-{}.json", std::process::id()));
+        let trace = trace_to(path.to_str().unwrap());
+        {
+            let _outer = crate::scope!("outer");
+            std::thread::spawn(|| {
+                let _worker = crate::scope!("worker");
+            })
+            .join()
+            .unwrap();
+        }
+        drop(trace);
+
+        let trace = std::fs::read_to_string(&path).unwrap();
+        assert!(trace.contains("\"name\":\"outer\""), "{}", trace);
+        assert!(trace.contains("\"name\":\"worker\""), "{}", trace);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,78 @@
+use std::fmt;
+
+pub mod ansi;
+mod dot;
+mod frames;
+pub mod png;
+pub mod svg;
+pub mod term;
+
+pub use dot::dot;
+pub use frames::FrameWriter;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+
+    // Blends towards other as t goes from 0 to 1
+    pub fn lerp(self, other: Rgb, t: f64) -> Rgb {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| (f64::from(a) + (f64::from(b) - f64::from(a)) * t).round() as u8;
+        Rgb(
+            mix(self.0, other.0),
+            mix(self.1, other.1),
+            mix(self.2, other.2),
+        )
+    }
+}
+
+// Formats as a CSS hex color
+impl fmt::Display for Rgb {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+// One block character per value, scaled between the smallest and largest
+pub fn sparkline<T: Copy + Into<f64>>(values: &[T]) -> String {
+    let values: Vec<f64> = values.iter().map(|value| (*value).into()).collect();
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let range = max - min;
+    values
+        .iter()
+        .map(|value| {
+            if range > 0.0 {
+                let level = ((value - min) / range * (SPARKS.len() - 1) as f64).round();
+                SPARKS[level as usize]
+            } else {
+                SPARKS[0]
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sparkline, Rgb};
+
+    #[test]
+    fn display_and_lerp() {
+        assert_eq!(Rgb(255, 128, 0).to_string(), "#ff8000");
+        assert_eq!(Rgb::BLACK.lerp(Rgb::WHITE, 0.5), Rgb(128, 128, 128));
+        assert_eq!(Rgb::BLACK.lerp(Rgb::WHITE, 2.0), Rgb::WHITE);
+    }
+
+    #[test]
+    fn sparklines() {
+        assert_eq!(sparkline(&[0, 1, 2, 3, 4, 5, 6, 7]), "▁▂▃▄▅▆▇█");
+        assert_eq!(sparkline(&[10.0, 40.0, 25.0]), "▁█▅");
+        assert_eq!(sparkline(&[3_u32, 3, 3]), "▁▁▁");
+        assert_eq!(sparkline::<i32>(&[]), "");
+    }
+}
@@ -0,0 +1,141 @@
+use std::fmt::Write;
+
+use super::Rgb;
+use crate::grid::Grid;
+
+const RESET: &str = "\x1b[0m";
+
+// What a cell means, which a theme turns into a color. Automata only need a
+// handful: live and dead cells, cells that never change, and anything the
+// caller wants to draw attention to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Role {
+    On,
+    Off,
+    Fixed,
+    Highlight,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Theme {
+    pub on: Rgb,
+    pub off: Rgb,
+    pub fixed: Rgb,
+    pub highlight: Rgb,
+}
+
+impl Theme {
+    pub const NAMES: [&'static str; 3] = ["default", "high-contrast", "colorblind"];
+
+    pub const DEFAULT: Theme = Theme {
+        on: Rgb(0x56, 0xb6, 0xc2),
+        off: Rgb(0x98, 0xc3, 0x79),
+        fixed: Rgb(0x5c, 0x63, 0x70),
+        highlight: Rgb(0xe5, 0xc0, 0x7b),
+    };
+
+    pub const HIGH_CONTRAST: Theme = Theme {
+        on: Rgb::WHITE,
+        off: Rgb(0x80, 0x80, 0x80),
+        fixed: Rgb(0x40, 0x40, 0x40),
+        highlight: Rgb(0xff, 0xff, 0x00),
+    };
+
+    // Okabe and Ito's palette, which stays distinguishable under the common
+    // forms of color blindness
+    pub const COLORBLIND: Theme = Theme {
+        on: Rgb(0x00, 0x72, 0xb2),
+        off: Rgb(0xe6, 0x9f, 0x00),
+        fixed: Rgb(0x99, 0x99, 0x99),
+        highlight: Rgb(0xcc, 0x79, 0xa7),
+    };
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Theme::DEFAULT),
+            "high-contrast" => Some(Theme::HIGH_CONTRAST),
+            "colorblind" => Some(Theme::COLORBLIND),
+            _ => None,
+        }
+    }
+
+    pub fn color(&self, role: Role) -> Rgb {
+        match role {
+            Role::On => self.on,
+            Role::Off => self.off,
+            Role::Fixed => self.fixed,
+            Role::Highlight => self.highlight,
+        }
+    }
+
+    // Wraps text in 24-bit foreground color escapes
+    pub fn paint(&self, role: Role, text: &str) -> String {
+        let Rgb(r, g, b) = self.color(role);
+        format!("\x1b[38;2;{r};{g};{b}m{text}{RESET}")
+    }
+}
+
+// Draws one character per cell, colored by role. Escapes are only emitted
+// when the role changes, and every line ends reset so a cut-off render can't
+// leave the terminal colored.
+pub fn render<T, F>(grid: &Grid<T>, theme: &Theme, style: F) -> String
+where
+    F: Fn(&T) -> (char, Role),
+{
+    let mut text = String::new();
+    for row in grid.rows() {
+        let mut current = None;
+        for cell in row {
+            let (glyph, role) = style(cell);
+            if current != Some(role) {
+                let Rgb(r, g, b) = theme.color(role);
+                write!(text, "\x1b[38;2;{r};{g};{b}m").unwrap();
+                current = Some(role);
+            }
+            text.push(glyph);
+        }
+        if current.is_some() {
+            text.push_str(RESET);
+        }
+        text.push('\n');
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn themes_by_name() {
+        for name in &Theme::NAMES {
+            assert!(Theme::from_name(name).is_some());
+        }
+        assert_eq!(Theme::from_name("neon"), None);
+        assert_eq!(Theme::COLORBLIND.color(Role::On), Rgb(0, 114, 178));
+    }
+
+    #[test]
+    fn paint_wraps_in_escapes() {
+        assert_eq!(
+            Theme::HIGH_CONTRAST.paint(Role::Highlight, "hi"),
+            "\x1b[38;2;255;255;0mhi\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn render_only_switches_on_role_changes() {
+        let grid = Grid::parse("##.\n...\n");
+        let theme = Theme::HIGH_CONTRAST;
+        let rendered = render(&grid, &theme, |cell| {
+            (*cell, if *cell == '#' { Role::On } else { Role::Fixed })
+        });
+        assert_eq!(
+            rendered,
+            concat!(
+                "\x1b[38;2;255;255;255m##\x1b[38;2;64;64;64m.\x1b[0m\n",
+                "\x1b[38;2;64;64;64m...\x1b[0m\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use std::{
+    collections::HashMap,
+    fmt::{Display, Write},
+    hash::Hash,
+    ops::Add,
+};
+
+use crate::graph::DiGraph;
+
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// Describes the graph in Graphviz's DOT language, for rendering with something
+// like `dot -Tsvg`. Nodes are named by labels, edges carry their weights if
+// asked for, and highlighted nodes are filled in.
+pub fn dot<N, W, L>(graph: &DiGraph<N, W>, labels: L, weights: bool, highlighted: &[N]) -> String
+where
+    N: Copy + Eq + Hash,
+    W: Copy + Ord + Add<Output = W> + Default + Display,
+    L: Fn(N) -> String,
+{
+    let mut dot = String::from("digraph {\n");
+    for (index, node) in graph.nodes().iter().enumerate() {
+        write!(dot, "    n{} [label=\"{}\"", index, escape(&labels(*node))).unwrap();
+        if highlighted.contains(node) {
+            dot.push_str(", style=filled, fillcolor=\"#f4d03f\"");
+        }
+        dot.push_str("];\n");
+    }
+
+    let indices: HashMap<N, usize> = graph
+        .nodes()
+        .iter()
+        .enumerate()
+        .map(|(index, node)| (*node, index))
+        .collect();
+    for (from, node) in graph.nodes().iter().enumerate() {
+        for (to, weight) in graph.neighbors(*node) {
+            write!(dot, "    n{} -> n{}", from, indices[&to]).unwrap();
+            if weights {
+                write!(dot, " [label=\"{}\"]", weight).unwrap();
+            }
+            dot.push_str(";\n");
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_weights_and_highlights() {
+        let mut graph = DiGraph::new();
+        graph.add_edge("shiny gold", "dark red", 2);
+        graph.add_edge("dark red", "say \"hi\"", 3);
+        graph.add_edge("shiny gold", "say \"hi\"", 1);
+
+        assert_eq!(
+            dot(&graph, String::from, true, &["shiny gold"]),
+            concat!(
+                "digraph {\n",
+                "    n0 [label=\"shiny gold\", style=filled, fillcolor=\"#f4d03f\"];\n",
+                "    n1 [label=\"dark red\"];\n",
+                "    n2 [label=\"say \\\"hi\\\"\"];\n",
+                "    n0 -> n1 [label=\"2\"];\n",
+                "    n0 -> n2 [label=\"1\"];\n",
+                "    n1 -> n2 [label=\"3\"];\n",
+                "}\n"
+            )
+        );
+    }
+
+    #[test]
+    fn unweighted() {
+        let mut graph: DiGraph<u8> = DiGraph::new();
+        graph.add_edge(1, 2, 5);
+        graph.add_node(3);
+        assert_eq!(
+            dot(&graph, |node| format!("#{node}"), false, &[]),
+            "digraph {\n    n0 [label=\"#1\"];\n    n1 [label=\"#2\"];\n    n2 [label=\"#3\"];\n    n0 -> n1;\n}\n"
+        );
+    }
+}
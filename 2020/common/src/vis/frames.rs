@@ -0,0 +1,147 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::{png, Rgb};
+use crate::grid::Grid;
+
+const FRAMERATE: usize = 10;
+
+// Writes a run of grids as frame_0000.png, frame_0001.png and so on, each
+// cell blown up to a square of scale pixels. Finishing adds frames.txt with
+// the dimensions and count, and a render.sh that hands the frames to ffmpeg.
+pub struct FrameWriter {
+    directory: PathBuf,
+    scale: usize,
+    size: Option<(usize, usize)>,
+    count: usize,
+}
+
+impl FrameWriter {
+    pub fn new(directory: &str, scale: usize) -> Self {
+        assert!(scale > 0, "Scale must be positive");
+        fs::create_dir_all(directory)
+            .unwrap_or_else(|_| panic!("Failed to create directory {}", directory));
+        Self {
+            directory: PathBuf::from(directory),
+            scale,
+            size: None,
+            count: 0,
+        }
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.count
+    }
+
+    pub fn get_filename(directory: &Path, frame: usize) -> PathBuf {
+        directory.join(format!("frame_{frame:04}.png"))
+    }
+
+    // Every frame has to be the same size, since that's what a video needs
+    pub fn write<T, F>(&mut self, grid: &Grid<T>, palette: F)
+    where
+        F: Fn(&T) -> Rgb,
+    {
+        let size = (grid.width(), grid.height());
+        let expected = *self.size.get_or_insert(size);
+        assert_eq!(size, expected, "Frame size changed");
+
+        let scale = self.scale;
+        let width = grid.width() * scale;
+        let mut pixels = Vec::with_capacity(width * grid.height() * scale);
+        for row in grid.rows() {
+            let start = pixels.len();
+            for cell in row {
+                let color = palette(cell);
+                pixels.extend(std::iter::repeat_n(color, scale));
+            }
+            for _ in 1..scale {
+                pixels.extend_from_within(start..start + width);
+            }
+        }
+
+        let filename = Self::get_filename(&self.directory, self.count);
+        fs::write(
+            &filename,
+            png::encode(width, grid.height() * scale, &pixels),
+        )
+        .unwrap_or_else(|_| panic!("Failed to write frame {}", filename.display()));
+        self.count += 1;
+    }
+
+    pub fn finish(self) {
+        let (width, height) = self.size.unwrap_or((0, 0));
+        let metadata = format!(
+            "frames={}\nwidth={}\nheight={}\nscale={}\nframerate={}\n",
+            self.count,
+            width * self.scale,
+            height * self.scale,
+            self.scale,
+            FRAMERATE
+        );
+        let filename = self.directory.join("frames.txt");
+        fs::write(&filename, metadata)
+            .unwrap_or_else(|_| panic!("Failed to write {}", filename.display()));
+
+        // libx264 wants even dimensions, so pad odd ones by a pixel
+        let script = format!(
+            "#!/bin/sh\n\
+             cd \"$(dirname \"$0\")\" || exit 1\n\
+             ffmpeg -y -framerate {FRAMERATE} -i frame_%04d.png \
+             -vf 'pad=ceil(iw/2)*2:ceil(ih/2)*2' -pix_fmt yuv420p \"${{1:-animation.mp4}}\"\n"
+        );
+        let filename = self.directory.join("render.sh");
+        fs::write(&filename, script)
+            .unwrap_or_else(|_| panic!("Failed to write {}", filename.display()));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&filename, fs::Permissions::from_mode(0o755))
+                .unwrap_or_else(|_| panic!("Failed to make {} executable", filename.display()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_numbered_frames() {
+        let directory = std::env::temp_dir().join(format!("frames-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        let mut writer = FrameWriter::new(directory.to_str().unwrap(), 2);
+
+        let mut grid = Grid::new(3, 1, false);
+        for x in 0..3 {
+            grid[(x, 0)] = true;
+            writer.write(&grid, |on| if *on { Rgb::WHITE } else { Rgb::BLACK });
+        }
+        assert_eq!(writer.frame_count(), 3);
+        writer.finish();
+
+        let frame = fs::read(FrameWriter::get_filename(&directory, 2)).unwrap();
+        assert_eq!(frame, png::encode(6, 2, &[Rgb::WHITE; 12]));
+        assert!(!FrameWriter::get_filename(&directory, 3).exists());
+        assert_eq!(
+            fs::read_to_string(directory.join("frames.txt")).unwrap(),
+            "frames=3\nwidth=6\nheight=2\nscale=2\nframerate=10\n"
+        );
+        assert!(fs::read_to_string(directory.join("render.sh"))
+            .unwrap()
+            .contains("-i frame_%04d.png"));
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Frame size changed")]
+    fn frames_keep_their_size() {
+        let directory = std::env::temp_dir().join(format!("frames-size-{}", std::process::id()));
+        let mut writer = FrameWriter::new(directory.to_str().unwrap(), 1);
+        writer.write(&Grid::new(2, 2, ()), |_| Rgb::BLACK);
+        let _ = fs::remove_dir_all(&directory);
+        writer.write(&Grid::new(3, 2, ()), |_| Rgb::BLACK);
+    }
+}
@@ -0,0 +1,136 @@
+use std::convert::TryInto;
+
+use super::Rgb;
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+// Deflate's stored blocks can't hold more than this
+const MAX_BLOCK: usize = 0xffff;
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0_u32;
+    for byte in bytes {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1_u32, 0_u32);
+    for byte in bytes {
+        a = (a + u32::from(*byte)) % 65521;
+        b = (b + a) % 65521;
+    }
+    b << 16 | a
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    let length: u32 = data.len().try_into().expect("PNG chunk is too large");
+    png.extend_from_slice(&length.to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+// Wraps data in a zlib stream without compressing it, which keeps this short
+// and is plenty for frames that get re-encoded into a video anyway
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut stream = vec![0x78, 0x01];
+    let mut blocks = data.chunks(MAX_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        stream.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        stream.push(u8::from(blocks.peek().is_none()));
+        let length = block.len() as u16;
+        stream.extend_from_slice(&length.to_le_bytes());
+        stream.extend_from_slice(&(!length).to_le_bytes());
+        stream.extend_from_slice(block);
+    }
+    stream.extend_from_slice(&adler32(data).to_be_bytes());
+    stream
+}
+
+// Encodes an 8-bit RGB image, with pixels given row by row
+pub fn encode(width: usize, height: usize, pixels: &[Rgb]) -> Vec<u8> {
+    assert_eq!(
+        pixels.len(),
+        width * height,
+        "Expected {}x{} pixels",
+        width,
+        height
+    );
+
+    let mut header = Vec::with_capacity(13);
+    let dimension = |value: usize| -> u32 { value.try_into().expect("Image is too large") };
+    header.extend_from_slice(&dimension(width).to_be_bytes());
+    header.extend_from_slice(&dimension(height).to_be_bytes());
+    // Bit depth 8, truecolor, then the only defined compression, filter and
+    // interlace methods
+    header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+    let mut raw = Vec::with_capacity(height * (1 + 3 * width));
+    for row in pixels.chunks(width.max(1)).take(height) {
+        // No filtering
+        raw.push(0);
+        for pixel in row {
+            raw.extend_from_slice(&[pixel.0, pixel.1, pixel.2]);
+        }
+    }
+
+    let mut png = SIGNATURE.to_vec();
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn chunks_are_well_formed() {
+        let png = encode(2, 1, &[Rgb(255, 0, 0), Rgb(0, 0, 255)]);
+        assert_eq!(&png[..8], &SIGNATURE);
+
+        let mut kinds = Vec::new();
+        let mut position = 8;
+        while position < png.len() {
+            let length = u32::from_be_bytes(png[position..position + 4].try_into().unwrap());
+            let end = position + 8 + length as usize;
+            let crc = u32::from_be_bytes(png[end..end + 4].try_into().unwrap());
+            assert_eq!(crc, crc32(&png[position + 4..end]));
+            kinds.push(String::from_utf8(png[position + 4..position + 8].to_vec()).unwrap());
+            if kinds.last().unwrap() == "IDAT" {
+                // Header, one final stored block of 7 bytes, then the checksum
+                let data = &png[position + 8..end];
+                assert_eq!(&data[..7], &[0x78, 0x01, 1, 7, 0, 0xf8, 0xff]);
+                assert_eq!(&data[7..14], &[0, 255, 0, 0, 0, 0, 255]);
+            }
+            position = end + 4;
+        }
+        assert_eq!(kinds, vec!["IHDR", "IDAT", "IEND"]);
+    }
+
+    #[test]
+    fn large_images_span_blocks() {
+        let stream = zlib_stored(&vec![7; MAX_BLOCK + 1]);
+        assert_eq!(stream.len(), 2 + 5 + MAX_BLOCK + 5 + 1 + 4);
+        assert_eq!(stream[2], 0);
+        assert_eq!(stream[2 + 5 + MAX_BLOCK], 1);
+    }
+}
@@ -0,0 +1,192 @@
+use std::fmt::Write;
+
+use super::Rgb;
+use crate::grid::Grid;
+
+// Shapes for drawing point sets. Each is centered on its point and sized by
+// the radius given to render_points: circles and squares touch their
+// neighbors one unit apart at radius 0.5, and pointy-topped hexes tile when
+// their centers come from hex_center with radius 1.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Marker {
+    Circle,
+    Square,
+    Hex,
+}
+
+fn write_header(svg: &mut String, width: f64, height: f64) {
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w:.0}" height="{h:.0}" viewBox="0 0 {w:.2} {h:.2}">"#,
+        w = width,
+        h = height
+    )
+    .unwrap();
+}
+
+// Draws each cell as a square, scaled so that the longer side of the grid is
+// max_size pixels. Cells the palette maps to None are left transparent.
+pub fn render_grid<T, F>(grid: &Grid<T>, max_size: u32, palette: F) -> String
+where
+    F: Fn(&T) -> Option<Rgb>,
+{
+    let longest = grid.width().max(grid.height()).max(1) as f64;
+    let cell = f64::from(max_size) / longest;
+
+    let mut svg = String::new();
+    write_header(
+        &mut svg,
+        cell * grid.width() as f64,
+        cell * grid.height() as f64,
+    );
+    for ((x, y), value) in grid.iter() {
+        if let Some(color) = palette(value) {
+            writeln!(
+                svg,
+                r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="{}"/>"#,
+                cell * x as f64,
+                cell * y as f64,
+                cell,
+                cell,
+                color
+            )
+            .unwrap();
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// Where to put a pointy-topped hex of radius 1 given its cube coordinates,
+// with y growing downwards as it does in SVG
+pub fn hex_center(x: i64, z: i64) -> (f64, f64) {
+    (3_f64.sqrt() * (x as f64 + z as f64 / 2.0), 1.5 * z as f64)
+}
+
+fn hex_points(cx: f64, cy: f64, radius: f64) -> String {
+    (0..6)
+        .map(|corner| {
+            let angle = (30.0 + 60.0 * f64::from(corner)).to_radians();
+            format!(
+                "{:.2},{:.2}",
+                cx + radius * angle.cos(),
+                cy + radius * angle.sin()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Draws a marker at each (x, y), scaled and translated so that the markers
+// fill a box whose longer side is max_size pixels
+pub fn render_points(
+    points: &[(f64, f64, Rgb)],
+    marker: Marker,
+    radius: f64,
+    max_size: u32,
+) -> String {
+    let bounds = points.iter().fold(None, |bounds, (x, y, _)| {
+        Some(match bounds {
+            None => (*x, *y, *x, *y),
+            Some((min_x, min_y, max_x, max_y)) => {
+                (x.min(min_x), y.min(min_y), x.max(max_x), y.max(max_y))
+            }
+        })
+    });
+    let (min_x, min_y, max_x, max_y) = bounds.unwrap_or((0.0, 0.0, 0.0, 0.0));
+    let (min_x, min_y) = (min_x - radius, min_y - radius);
+    let (extent_x, extent_y) = (max_x + radius - min_x, max_y + radius - min_y);
+    let longest = extent_x.max(extent_y);
+    let scale = if longest > 0.0 {
+        f64::from(max_size) / longest
+    } else {
+        1.0
+    };
+
+    let mut svg = String::new();
+    write_header(&mut svg, scale * extent_x, scale * extent_y);
+    let radius = scale * radius;
+    for (x, y, color) in points {
+        let (cx, cy) = (scale * (x - min_x), scale * (y - min_y));
+        match marker {
+            Marker::Circle => writeln!(
+                svg,
+                r#"<circle cx="{:.2}" cy="{:.2}" r="{:.2}" fill="{}"/>"#,
+                cx, cy, radius, color
+            ),
+            Marker::Square => writeln!(
+                svg,
+                r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="{}"/>"#,
+                cx - radius,
+                cy - radius,
+                2.0 * radius,
+                2.0 * radius,
+                color
+            ),
+            Marker::Hex => writeln!(
+                svg,
+                r#"<polygon points="{}" fill="{}"/>"#,
+                hex_points(cx, cy, radius),
+                color
+            ),
+        }
+        .unwrap();
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_scales_longer_side() {
+        let grid = Grid::parse("#.\n.#\n#.\n");
+        let svg = render_grid(&grid, 30, |cell| {
+            if *cell == '#' {
+                Some(Rgb(255, 0, 0))
+            } else {
+                None
+            }
+        });
+        let lines: Vec<&str> = svg.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="30" viewBox="0 0 20.00 30.00">"#,
+                r##"<rect x="0.00" y="0.00" width="10.00" height="10.00" fill="#ff0000"/>"##,
+                r##"<rect x="10.00" y="10.00" width="10.00" height="10.00" fill="#ff0000"/>"##,
+                r##"<rect x="0.00" y="20.00" width="10.00" height="10.00" fill="#ff0000"/>"##,
+                "</svg>",
+            ]
+        );
+    }
+
+    #[test]
+    fn points_are_translated_into_view() {
+        let points = [(-1.0, 5.0, Rgb::BLACK), (1.0, 5.0, Rgb::WHITE)];
+        let svg = render_points(&points, Marker::Circle, 1.0, 40);
+        assert!(svg.starts_with(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="20" viewBox="0 0 40.00 20.00">"#
+        ));
+        assert!(svg.contains(r##"<circle cx="10.00" cy="10.00" r="10.00" fill="#000000"/>"##));
+        assert!(svg.contains(r##"<circle cx="30.00" cy="10.00" r="10.00" fill="#ffffff"/>"##));
+    }
+
+    #[test]
+    fn hexes_are_polygons() {
+        let center = hex_center(1, -1);
+        assert!((center.0 - 3_f64.sqrt() / 2.0).abs() < 1e-9);
+        assert!((center.1 + 1.5).abs() < 1e-9);
+
+        let svg = render_points(&[(0.0, 0.0, Rgb::BLACK)], Marker::Hex, 1.0, 2);
+        let polygon = svg.lines().nth(1).unwrap();
+        assert!(polygon.starts_with(r#"<polygon points="1.87,1.50 1.00,2.00 "#));
+        assert_eq!(polygon.matches(',').count(), 6);
+        assert_eq!(
+            render_points(&[], Marker::Square, 1.0, 10).lines().count(),
+            2
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use crate::grid::Grid;
+
+// Braille glyphs are a 2x4 block of dots, numbered down the left column and
+// then the right, with the bottom row added later as bits 6 and 7
+const BRAILLE_BASE: u32 = 0x2800;
+const BRAILLE_DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+// Packs the grid into braille glyphs, each showing which of a 2x4 block of
+// cells are filled, which fits 8x as many cells in the same terminal
+pub fn braille<T, F>(grid: &Grid<T>, filled: F) -> String
+where
+    F: Fn(&T) -> bool,
+{
+    let columns = grid.width().div_ceil(2);
+    let rows = grid.height().div_ceil(4);
+    let mut text = String::with_capacity(rows * (columns * 3 + 1));
+    for row in 0..rows {
+        for column in 0..columns {
+            let mut glyph = BRAILLE_BASE;
+            for (dy, dots) in BRAILLE_DOTS.iter().enumerate() {
+                for (dx, dot) in dots.iter().enumerate() {
+                    let cell = grid.get(column * 2 + dx, row * 4 + dy);
+                    if cell.is_some_and(&filled) {
+                        glyph |= dot;
+                    }
+                }
+            }
+            text.push(std::char::from_u32(glyph).unwrap());
+        }
+        text.push('\n');
+    }
+    text
+}
+
+// One character per cell, a row per line
+pub fn text<T, F>(grid: &Grid<T>, glyph: F) -> String
+where
+    F: Fn(&T) -> char,
+{
+    let mut text = String::with_capacity(grid.height() * (grid.width() + 1));
+    for row in grid.rows() {
+        text.extend(row.iter().map(&glyph));
+        text.push('\n');
+    }
+    text
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Render {
+    Text,
+    Braille,
+}
+
+impl Render {
+    pub const NAMES: [&'static str; 2] = ["text", "braille"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(Render::Text),
+            "braille" => Some(Render::Braille),
+            _ => None,
+        }
+    }
+
+    // Text shows each cell's glyph, while braille can only show whether a
+    // cell is filled
+    pub fn render<T, G, F>(self, grid: &Grid<T>, glyph: G, filled: F) -> String
+    where
+        G: Fn(&T) -> char,
+        F: Fn(&T) -> bool,
+    {
+        match self {
+            Render::Text => text(grid, glyph),
+            Render::Braille => braille(grid, filled),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn braille_packs_blocks() {
+        let grid = Grid::parse(
+            "#.#
+             .#.
+             #..
+             ...
+             ##.",
+        );
+        // Left block: dots 1, 5 and 3; right block: dot 1. The last row starts
+        // a second line of glyphs with dots 1 and 4, then an empty block.
+        assert_eq!(
+            braille(&grid, |cell| *cell == '#'),
+            "\u{2815}\u{2801}\n\u{2809}\u{2800}\n"
+        );
+    }
+
+    #[test]
+    fn full_block() {
+        let grid = Grid::new(2, 4, true);
+        assert_eq!(braille(&grid, |cell| *cell), "⣿\n");
+    }
+
+    #[test]
+    fn render_modes() {
+        let grid = Grid::parse("L#\n.L\n");
+        assert_eq!(
+            Render::from_name("text")
+                .unwrap()
+                .render(&grid, |cell| *cell, |_| false),
+            "L#\n.L\n"
+        );
+        assert_eq!(
+            Render::from_name("braille")
+                .unwrap()
+                .render(&grid, |cell| *cell, |cell| *cell == '#'),
+            "\u{2808}\n"
+        );
+        assert_eq!(Render::from_name("ascii"), None);
+    }
+}
@@ -0,0 +1,314 @@
+use std::{collections::VecDeque, convert::TryInto};
+
+// What an instruction asks the machine to do once it has run
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Effect {
+    // Move on to the following instruction
+    Next,
+    // Move the program counter by this much
+    Jump(i64),
+    // Wait here until more input arrives, then run this instruction again
+    Block,
+    Halt,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum State {
+    Running,
+    Blocked,
+    // Either a Halt instruction ran or the program counter left the program
+    Halted,
+}
+
+// The parts of the machine an instruction can touch
+#[derive(Clone, Debug, Default)]
+pub struct Cpu {
+    pub registers: Vec<i64>,
+    input: VecDeque<i64>,
+    output: VecDeque<i64>,
+}
+
+impl Cpu {
+    pub fn send(&mut self, value: i64) {
+        self.output.push_back(value);
+    }
+
+    pub fn receive(&mut self) -> Option<i64> {
+        self.input.pop_front()
+    }
+}
+
+pub trait Instruction {
+    fn execute(&self, cpu: &mut Cpu) -> Effect;
+}
+
+type Tracer<I> = Box<dyn FnMut(usize, &I, &[i64])>;
+
+// Runs a program for any instruction set. Each day only has to say how its
+// instructions are parsed and what they do to the registers.
+pub struct Machine<I: Instruction> {
+    program: Vec<I>,
+    pc: usize,
+    cpu: Cpu,
+    state: State,
+    steps: usize,
+    tracer: Option<Tracer<I>>,
+}
+
+impl<I: Instruction> Machine<I> {
+    pub fn new(program: Vec<I>, register_count: usize) -> Self {
+        Self {
+            program,
+            pc: 0,
+            cpu: Cpu {
+                registers: vec![0; register_count],
+                ..Cpu::default()
+            },
+            state: State::Running,
+            steps: 0,
+            tracer: None,
+        }
+    }
+
+    pub fn program(&self) -> &[I] {
+        &self.program
+    }
+
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    // How many instructions have completed
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub fn register(&self, index: usize) -> i64 {
+        self.cpu.registers[index]
+    }
+
+    pub fn set_register(&mut self, index: usize, value: i64) {
+        self.cpu.registers[index] = value;
+    }
+
+    pub fn registers(&self) -> &[i64] {
+        &self.cpu.registers
+    }
+
+    // Wakes the machine up if it was waiting for input
+    pub fn push_input(&mut self, value: i64) {
+        self.cpu.input.push_back(value);
+        if self.state == State::Blocked {
+            self.state = State::Running;
+        }
+    }
+
+    pub fn pop_output(&mut self) -> Option<i64> {
+        self.cpu.output.pop_front()
+    }
+
+    pub fn take_output(&mut self) -> Vec<i64> {
+        self.cpu.output.drain(..).collect()
+    }
+
+    // Called with the program counter, instruction and registers before every
+    // instruction runs
+    pub fn set_tracer<F: FnMut(usize, &I, &[i64]) + 'static>(&mut self, tracer: F) {
+        self.tracer = Some(Box::new(tracer));
+    }
+
+    pub fn step(&mut self) -> State {
+        if self.state != State::Running {
+            return self.state;
+        }
+        let Some(instruction) = self.program.get(self.pc) else {
+            self.state = State::Halted;
+            return self.state;
+        };
+
+        if let Some(tracer) = &mut self.tracer {
+            tracer(self.pc, instruction, &self.cpu.registers);
+        }
+        match instruction.execute(&mut self.cpu) {
+            Effect::Next => self.pc += 1,
+            // A jump before the start leaves the program counter where it was
+            Effect::Jump(offset) => match (self.pc as i64 + offset).try_into() {
+                Ok(pc) => self.pc = pc,
+                Err(_) => self.state = State::Halted,
+            },
+            Effect::Block => {
+                self.state = State::Blocked;
+                return self.state;
+            }
+            Effect::Halt => self.state = State::Halted,
+        }
+        self.steps += 1;
+
+        if self.pc >= self.program.len() {
+            self.state = State::Halted;
+        }
+        self.state
+    }
+
+    // Runs until the machine halts or needs input
+    pub fn run(&mut self) -> State {
+        while self.step() == State::Running {}
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2017 day 18's duet instructions
+    enum Operand {
+        Register(usize),
+        Value(i64),
+    }
+
+    impl Operand {
+        fn parse(text: &str) -> Self {
+            text.parse().map_or_else(
+                |_| Operand::Register((text.as_bytes()[0] - b'a') as usize),
+                Operand::Value,
+            )
+        }
+
+        fn get(&self, cpu: &Cpu) -> i64 {
+            match self {
+                Operand::Register(index) => cpu.registers[*index],
+                Operand::Value(value) => *value,
+            }
+        }
+    }
+
+    enum Duet {
+        Snd(Operand),
+        Set(usize, Operand),
+        Add(usize, Operand),
+        Mul(usize, Operand),
+        Mod(usize, Operand),
+        Rcv(usize),
+        Jgz(Operand, Operand),
+    }
+
+    impl Duet {
+        fn parse(line: &str) -> Self {
+            let words: Vec<&str> = line.split(' ').collect();
+            let register = || (words[1].as_bytes()[0] - b'a') as usize;
+            let operand = |index: usize| Operand::parse(words[index]);
+            match words[0] {
+                "snd" => Duet::Snd(operand(1)),
+                "set" => Duet::Set(register(), operand(2)),
+                "add" => Duet::Add(register(), operand(2)),
+                "mul" => Duet::Mul(register(), operand(2)),
+                "mod" => Duet::Mod(register(), operand(2)),
+                "rcv" => Duet::Rcv(register()),
+                "jgz" => Duet::Jgz(operand(1), operand(2)),
+                _ => panic!("Unexpected instruction {}", line),
+            }
+        }
+    }
+
+    impl Instruction for Duet {
+        fn execute(&self, cpu: &mut Cpu) -> Effect {
+            match self {
+                Duet::Snd(value) => cpu.send(value.get(cpu)),
+                Duet::Set(register, value) => cpu.registers[*register] = value.get(cpu),
+                Duet::Add(register, value) => cpu.registers[*register] += value.get(cpu),
+                Duet::Mul(register, value) => cpu.registers[*register] *= value.get(cpu),
+                Duet::Mod(register, value) => {
+                    cpu.registers[*register] = cpu.registers[*register].rem_euclid(value.get(cpu));
+                }
+                Duet::Rcv(register) => match cpu.receive() {
+                    Some(value) => cpu.registers[*register] = value,
+                    None => return Effect::Block,
+                },
+                Duet::Jgz(condition, offset) => {
+                    if condition.get(cpu) > 0 {
+                        return Effect::Jump(offset.get(cpu));
+                    }
+                }
+            }
+            Effect::Next
+        }
+    }
+
+    fn load(source: &[&str], id: i64) -> Machine<Duet> {
+        let program = source.iter().map(|line| Duet::parse(line)).collect();
+        let mut machine = Machine::new(program, 26);
+        machine.set_register((b'p' - b'a') as usize, id);
+        machine
+    }
+
+    #[test]
+    fn duet_pair_deadlocks() {
+        let source = [
+            "snd 1", "snd 2", "snd p", "rcv a", "rcv b", "rcv c", "rcv d",
+        ];
+        let mut machines = [load(&source, 0), load(&source, 1)];
+        let mut sent_by_one = 0;
+        loop {
+            let states = [machines[0].run(), machines[1].run()];
+            let from_zero = machines[0].take_output();
+            let from_one = machines[1].take_output();
+            if from_zero.is_empty() && from_one.is_empty() {
+                assert_eq!(states, [State::Blocked, State::Blocked]);
+                break;
+            }
+            sent_by_one += from_one.len();
+            from_zero
+                .into_iter()
+                .for_each(|value| machines[1].push_input(value));
+            from_one
+                .into_iter()
+                .for_each(|value| machines[0].push_input(value));
+        }
+        assert_eq!(sent_by_one, 3);
+        assert_eq!(machines[0].register(2), 1);
+    }
+
+    #[test]
+    fn jumps_off_either_end_halt() {
+        let mut machine = load(&["set a 1", "jgz a -2", "snd a"], 0);
+        assert_eq!(machine.run(), State::Halted);
+        assert_eq!(machine.pc(), 1);
+        assert_eq!(machine.steps(), 2);
+
+        let mut machine = load(&["set a 1", "jgz a 2", "snd a"], 0);
+        assert_eq!(machine.run(), State::Halted);
+        assert_eq!(machine.pc(), 3);
+        assert!(machine.take_output().is_empty());
+    }
+
+    #[test]
+    fn tracer_sees_every_instruction() {
+        use std::{cell::RefCell, rc::Rc};
+
+        let trace = Rc::new(RefCell::new(Vec::new()));
+        let mut machine = load(&["set a 3", "add a -1", "jgz a -1", "snd a"], 0);
+        let recorder = Rc::clone(&trace);
+        machine.set_tracer(move |pc, _, registers| recorder.borrow_mut().push((pc, registers[0])));
+        machine.run();
+        assert_eq!(
+            *trace.borrow(),
+            vec![
+                (0, 0),
+                (1, 3),
+                (2, 2),
+                (1, 2),
+                (2, 1),
+                (1, 1),
+                (2, 0),
+                (3, 0)
+            ]
+        );
+        assert_eq!(machine.steps(), 8);
+        assert_eq!(machine.take_output(), vec![0]);
+    }
+}
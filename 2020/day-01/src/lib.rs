@@ -0,0 +1,106 @@
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(clippy::missing_panics_doc)]
+#![cfg_attr(feature = "nightly", feature(test))]
+
+#[cfg(feature = "nightly")]
+extern crate test;
+
+use common::{LineReader, Solution};
+
+// The entries that sum to 2020, multiplied together
+pub const TARGET: i32 = 2020;
+
+#[must_use]
+pub fn sum_product2(sorted: &[i32], target: i32) -> Option<i32> {
+    let mut candidate_index = sorted.len() - 1;
+    for number in sorted {
+        while number + sorted[candidate_index] > target {
+            if candidate_index == 0 {
+                return None;
+            }
+
+            candidate_index -= 1;
+        }
+
+        if number + sorted[candidate_index] == target {
+            return Some(number * sorted[candidate_index]);
+        }
+    }
+
+    None
+}
+
+#[must_use]
+pub fn sum_product3(sorted: &[i32], target: i32) -> Option<i32> {
+    let mut end = sorted.len() - 1;
+    for number in sorted {
+        while number + sorted[end] > target {
+            end -= 1;
+        }
+
+        if let Some(product2) = sum_product2(&sorted[0..end], target - number) {
+            return Some(product2 * number);
+        }
+    }
+
+    None
+}
+
+// The expense report, sorted
+#[must_use]
+pub fn parse_array(text: &str) -> Vec<i32> {
+    let mut reader = LineReader::from_text(text);
+    let mut array = Vec::<i32>::new();
+    reader.read_with(|line| {
+        array.push(
+            line.parse()
+                .unwrap_or_else(|_| panic!("Failed to parse {}", line)),
+        );
+    });
+
+    array.sort_unstable();
+    array
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    fn part1(&self, input: &str) -> String {
+        sum_product2(&parse_array(input), TARGET)
+            .expect("Failed to find sum product")
+            .to_string()
+    }
+
+    fn part2(&self, input: &str) -> String {
+        sum_product3(&parse_array(input), TARGET)
+            .expect("Failed to find sum product")
+            .to_string()
+    }
+}
+
+// Benchmarks only, which need the nightly feature
+#[cfg(all(test, feature = "nightly"))]
+mod tests {
+    use common::input;
+    use test::Bencher;
+
+    #[bench]
+    fn sum_product2(bencher: &mut Bencher) {
+        let array = super::parse_array(&input::load("input.txt"));
+        bencher.iter(|| {
+            let mut array = array.clone();
+            array.sort_unstable();
+            assert_eq!(super::sum_product2(&array, 2020).unwrap(), 1_019_904);
+        });
+    }
+
+    #[bench]
+    fn sum_product3(bencher: &mut Bencher) {
+        let array = super::parse_array(&input::load("input.txt"));
+        bencher.iter(|| {
+            let mut array = array.clone();
+            array.sort_unstable();
+            assert_eq!(super::sum_product3(&array, 2020).unwrap(), 176_647_680);
+        });
+    }
+}
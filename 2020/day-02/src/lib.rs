@@ -0,0 +1,108 @@
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(clippy::missing_panics_doc)]
+
+use common::{
+    parse::{ParseError, Tokenizer},
+    LineReader, Solution,
+};
+
+#[derive(Clone, Copy)]
+pub enum PolicyType {
+    Range,
+    Position,
+}
+
+trait Policy {
+    fn allows(&self, password: &str) -> bool;
+}
+
+struct PositionPolicy {
+    first: usize,
+    second: usize,
+    character: char,
+}
+
+impl PositionPolicy {
+    fn new(tokens: &mut Tokenizer) -> Result<Self, ParseError> {
+        Ok(Self {
+            first: tokens.next_usize()?,
+            second: tokens.next_usize()?,
+            character: tokens.next_char()?,
+        })
+    }
+}
+
+impl Policy for PositionPolicy {
+    fn allows(&self, password: &str) -> bool {
+        let first_matches = password.chars().nth(self.first - 1) == Some(self.character);
+        let second_matches = password.chars().nth(self.second - 1) == Some(self.character);
+        first_matches ^ second_matches
+    }
+}
+
+struct RangePolicy {
+    min: usize,
+    max: usize,
+    character: char,
+}
+
+impl RangePolicy {
+    fn new(tokens: &mut Tokenizer) -> Result<Self, ParseError> {
+        Ok(Self {
+            min: tokens.next_usize()?,
+            max: tokens.next_usize()?,
+            character: tokens.next_char()?,
+        })
+    }
+}
+
+impl Policy for RangePolicy {
+    fn allows(&self, password: &str) -> bool {
+        let mut count = 0_usize;
+        for c in password.chars() {
+            if c == self.character {
+                count += 1;
+            }
+            if count > self.max {
+                return false;
+            }
+        }
+        count >= self.min
+    }
+}
+
+// Lines look like "1-3 a: abcde"
+fn password_is_valid(line: &str, policy_type: PolicyType) -> Result<bool, ParseError> {
+    let mut tokens = Tokenizer::new(line, "-: ");
+    let policy = {
+        match policy_type {
+            PolicyType::Position => Box::new(PositionPolicy::new(&mut tokens)?) as Box<dyn Policy>,
+            PolicyType::Range => Box::new(RangePolicy::new(&mut tokens)?) as Box<dyn Policy>,
+        }
+    };
+    let password = tokens.next_str()?;
+
+    Ok(policy.allows(password))
+}
+
+#[must_use]
+pub fn count_valid_passwords(text: &str, policy_type: PolicyType) -> usize {
+    LineReader::from_text(text)
+        .filter(|line| {
+            password_is_valid(line, policy_type)
+                .unwrap_or_else(|error| panic!("Failed to parse [{}]: {}", line, error))
+        })
+        .count()
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    fn part1(&self, input: &str) -> String {
+        count_valid_passwords(input, PolicyType::Range).to_string()
+    }
+
+    fn part2(&self, input: &str) -> String {
+        count_valid_passwords(input, PolicyType::Position).to_string()
+    }
+}
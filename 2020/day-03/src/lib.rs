@@ -0,0 +1,76 @@
+#![deny(clippy::all, clippy::pedantic)]
+
+use common::{LineReader, Solution};
+
+struct PathFollower {
+    right: usize,
+    down: usize,
+    column: usize,
+    row: usize,
+    tree_count: usize,
+}
+
+impl PathFollower {
+    fn new(right: usize, down: usize) -> Self {
+        Self {
+            right,
+            down,
+            column: 0,
+            row: 0,
+            tree_count: 0,
+        }
+    }
+
+    fn add_line(&mut self, line: &[u8]) {
+        if !self.row.is_multiple_of(self.down) {
+            self.row += 1;
+            return;
+        }
+
+        if line[self.column % line.len()] == b'#' {
+            self.tree_count += 1;
+        }
+
+        self.column += self.right;
+        self.row += 1;
+    }
+
+    fn get_tree_count(&self) -> usize {
+        self.tree_count
+    }
+}
+
+// As (right, down), with part 1's slope first
+pub const SLOPES: [(usize, usize); 5] = [(3, 1), (1, 1), (5, 1), (7, 1), (1, 2)];
+
+// The trees hit on each slope, in the same order
+#[must_use]
+pub fn count_trees(text: &str, slopes: &[(usize, usize)]) -> Vec<usize> {
+    let mut followers: Vec<PathFollower> = slopes
+        .iter()
+        .map(|(right, down)| PathFollower::new(*right, *down))
+        .collect();
+
+    for line in LineReader::from_text(text) {
+        for follower in &mut followers {
+            follower.add_line(line.as_bytes());
+        }
+    }
+
+    followers.iter().map(PathFollower::get_tree_count).collect()
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    fn part1(&self, input: &str) -> String {
+        count_trees(input, &SLOPES[..1])[0].to_string()
+    }
+
+    fn part2(&self, input: &str) -> String {
+        count_trees(input, &SLOPES)
+            .into_iter()
+            .product::<usize>()
+            .to_string()
+    }
+}
@@ -0,0 +1,79 @@
+#![deny(clippy::all, clippy::pedantic)]
+
+use bit_set::BitSet;
+use common::{LineReader, Solution};
+
+fn parse_row(line: &[u8]) -> usize {
+    let mut row = 0;
+    let mut factor = 64;
+    for c in line {
+        if *c == b'B' {
+            row += factor;
+        }
+        factor /= 2;
+    }
+    row
+}
+
+fn parse_column(line: &[u8]) -> usize {
+    let mut column = 0;
+    let mut factor = 4;
+    for c in line {
+        if *c == b'R' {
+            column += factor;
+        }
+        factor /= 2;
+    }
+    column
+}
+
+fn parse_seat(line: &str) -> usize {
+    let bytes = line.as_bytes();
+    parse_row(&bytes[0..7]) * 8 + parse_column(&bytes[7..])
+}
+
+pub struct Seats {
+    pub max_seat: usize,
+    occupied: BitSet,
+}
+
+impl Seats {
+    #[must_use]
+    pub fn from_text(text: &str) -> Self {
+        let mut max_seat = 0;
+        let mut occupied = BitSet::new();
+
+        for line in LineReader::from_text(text) {
+            let seat = parse_seat(&line);
+
+            max_seat = max_seat.max(seat);
+            occupied.insert(seat);
+        }
+
+        Self { max_seat, occupied }
+    }
+
+    // The one empty seat with occupied seats on both sides
+    #[must_use]
+    pub fn find_my_seat(&self) -> Option<usize> {
+        self.occupied
+            .iter()
+            .find(|seat| !self.occupied.contains(seat + 1) && self.occupied.contains(seat + 2))
+            .map(|seat| seat + 1)
+    }
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    fn part1(&self, input: &str) -> String {
+        Seats::from_text(input).max_seat.to_string()
+    }
+
+    fn part2(&self, input: &str) -> String {
+        Seats::from_text(input)
+            .find_my_seat()
+            .expect("Failed to find my seat")
+            .to_string()
+    }
+}
@@ -1,42 +1,9 @@
 #![deny(clippy::all, clippy::pedantic)]
 
-use std::{
-    cmp::max,
-    env,
-    fs::File,
-    io::{BufRead, BufReader},
-};
+use std::env;
 
-use bit_set::BitSet;
-
-fn parse_row(line: &[u8]) -> usize {
-    let mut row = 0;
-    let mut factor = 64;
-    for c in line {
-        if *c == b'B' {
-            row += factor;
-        }
-        factor /= 2;
-    }
-    row
-}
-
-fn parse_column(line: &[u8]) -> usize {
-    let mut column = 0;
-    let mut factor = 4;
-    for c in line {
-        if *c == b'R' {
-            column += factor;
-        }
-        factor /= 2;
-    }
-    column
-}
-
-fn parse_seat(line: &str) -> usize {
-    let bytes = line.as_bytes();
-    parse_row(&bytes[0..7]) * 8 + parse_column(&bytes[7..])
-}
+use common::input;
+use day_05::Seats;
 
 fn main() {
     let args: Vec<String> = env::args().collect();
@@ -45,34 +12,10 @@ fn main() {
     }
 
     let filename = &args[1];
-    let file = File::open(filename).unwrap_or_else(|_| panic!("Failed to open file {}", filename));
-    let mut reader = BufReader::new(file);
-
-    let mut max_seat = 0;
-    let mut occupied = BitSet::new();
-
-    let mut line = String::new();
-    loop {
-        let bytes = reader
-            .read_line(&mut line)
-            .unwrap_or_else(|_| panic!("Failed to read line"));
-        if bytes == 0 {
-            break;
-        }
-
-        let seat = parse_seat(&line.trim());
-
-        max_seat = max(max_seat, seat);
-        occupied.insert(seat);
-
-        line.clear();
-    }
+    let seats = Seats::from_text(&input::load(filename));
 
-    println!("Max seat: {}", max_seat);
-    for seat in &occupied {
-        if !occupied.contains(seat + 1) && occupied.contains(seat + 2) {
-            println!("My seat: {}", seat + 1);
-            break;
-        }
+    println!("Max seat: {}", seats.max_seat);
+    if let Some(seat) = seats.find_my_seat() {
+        println!("My seat: {seat}");
     }
 }
@@ -0,0 +1,91 @@
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(clippy::missing_panics_doc)]
+
+use std::ops::AddAssign;
+
+use common::{LineReader, Solution};
+
+struct QuestionCounter {
+    any_person: u32,
+    all_people: u32,
+}
+
+// Summed over every group
+pub struct Counts {
+    pub any_person: u32,
+    pub all_people: u32,
+}
+
+impl AddAssign for Counts {
+    fn add_assign(&mut self, other: Self) {
+        self.any_person += other.any_person;
+        self.all_people += other.all_people;
+    }
+}
+
+impl QuestionCounter {
+    fn new() -> Self {
+        Self {
+            any_person: 0_u32,
+            all_people: u32::MAX,
+        }
+    }
+
+    fn parse_questions(&mut self, line: &str) {
+        let mut individual = 0;
+        for byte in line.as_bytes() {
+            let offset = byte - b'a';
+            assert!(offset < 32, "Byte out of range");
+            individual |= 1 << offset;
+        }
+
+        self.any_person |= individual;
+        self.all_people &= individual;
+    }
+
+    fn add_line(&mut self, line: &str) -> Option<Counts> {
+        if !line.trim().is_empty() {
+            self.parse_questions(line);
+            return None;
+        }
+
+        let counts = Some(Counts {
+            any_person: self.any_person.count_ones(),
+            all_people: self.all_people.count_ones(),
+        });
+
+        *self = Self::new();
+
+        counts
+    }
+}
+
+#[must_use]
+pub fn count_questions(text: &str) -> Counts {
+    let mut counter = QuestionCounter::new();
+    let mut counts = Counts {
+        any_person: 0,
+        all_people: 0,
+    };
+
+    for line in LineReader::from_text(text) {
+        if let Some(group) = counter.add_line(&line) {
+            counts += group;
+        }
+    }
+
+    counts += counter.add_line("").expect("Failed to find last record");
+    counts
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    fn part1(&self, input: &str) -> String {
+        count_questions(input).any_person.to_string()
+    }
+
+    fn part2(&self, input: &str) -> String {
+        count_questions(input).all_people.to_string()
+    }
+}
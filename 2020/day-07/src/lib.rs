@@ -0,0 +1,164 @@
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(clippy::missing_panics_doc)]
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use common::{graph::DiGraph, LineReader, Solution};
+
+// The bag both parts ask about
+pub const SHINY_GOLD: &str = "shiny gold";
+
+struct Bag {
+    name: String,
+    count: i32,
+}
+
+pub struct BagTracker {
+    held_by: HashMap<String, Vec<String>>,
+    holds: HashMap<String, Vec<Bag>>,
+}
+
+impl BagTracker {
+    fn new() -> Self {
+        Self {
+            held_by: HashMap::new(),
+            holds: HashMap::new(),
+        }
+    }
+
+    fn parse_line(&mut self, line: &str) {
+        let mut split = line.split("contain");
+        let container = split
+            .next()
+            .expect("Failed to find container")
+            .strip_suffix(" bags ")
+            .expect("Failed to strip 'bags' suffix");
+
+        split
+            .next()
+            .expect("Failed to find containees")
+            .split(',')
+            .filter_map(|token| {
+                let description = token
+                    .trim()
+                    .trim_end_matches('.')
+                    .trim_end_matches('s')
+                    .strip_suffix(" bag")
+                    .expect("Failed to strip 'bags' suffix");
+
+                if description == "no other" {
+                    return None;
+                }
+
+                Some(Bag {
+                    name: String::from(&description[2..]),
+                    count: description[0..1]
+                        .parse()
+                        .expect("Failed to parse count as i32"),
+                })
+            })
+            .for_each(|containee| {
+                self.held_by
+                    .entry(containee.name.clone())
+                    .or_default()
+                    .push(String::from(container));
+
+                self.holds
+                    .entry(String::from(container))
+                    .or_default()
+                    .push(containee);
+            });
+    }
+
+    #[must_use]
+    pub fn from_text(text: &str) -> Self {
+        let mut tracker = Self::new();
+        for line in LineReader::from_text(text) {
+            tracker.parse_line(&line);
+        }
+        tracker
+    }
+
+    #[must_use]
+    pub fn compute_container_count(&self, name: &str) -> usize {
+        let mut work_queue = VecDeque::new();
+        work_queue.push_back(name);
+
+        let mut containers = HashSet::new();
+
+        while !work_queue.is_empty() {
+            let current = work_queue
+                .pop_front()
+                .expect("Failed to pop front of queue");
+            if let Some(parents) = self.held_by.get(current) {
+                for parent in parents {
+                    if containers.insert(parent) {
+                        work_queue.push_back(parent);
+                    }
+                }
+            }
+        }
+
+        containers.len()
+    }
+
+    fn compute_containee_count(
+        &self,
+        container: &Bag,
+        containee_counts: &mut HashMap<String, i32>,
+    ) -> i32 {
+        if let Some(count) = containee_counts.get(&container.name) {
+            return container.count * (1 + *count);
+        }
+
+        let mut containee_count = 0;
+        for containee in self.holds.get(&container.name).unwrap_or(&Vec::new()) {
+            containee_count += self.compute_containee_count(containee, containee_counts);
+        }
+
+        containee_counts.insert(container.name.clone(), containee_count);
+        container.count * (1 + containee_count)
+    }
+
+    // Bags inside the named one, not counting the bag itself
+    #[must_use]
+    pub fn count_bags_inside(&self, name: &str) -> i32 {
+        self.compute_containee_count(
+            &Bag {
+                name: String::from(name),
+                count: 1,
+            },
+            &mut HashMap::new(),
+        ) - 1
+    }
+
+    // Edges point from each bag to the bags it holds, weighted by how many
+    #[must_use]
+    pub fn get_graph(&self) -> DiGraph<&str, i32> {
+        let mut graph = DiGraph::new();
+        let mut containers: Vec<&String> = self.holds.keys().collect();
+        containers.sort();
+        for container in containers {
+            for containee in &self.holds[container] {
+                graph.add_edge(container.as_str(), containee.name.as_str(), containee.count);
+            }
+        }
+        graph
+    }
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    fn part1(&self, input: &str) -> String {
+        BagTracker::from_text(input)
+            .compute_container_count(SHINY_GOLD)
+            .to_string()
+    }
+
+    fn part2(&self, input: &str) -> String {
+        BagTracker::from_text(input)
+            .count_bags_inside(SHINY_GOLD)
+            .to_string()
+    }
+}
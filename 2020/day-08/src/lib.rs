@@ -0,0 +1,123 @@
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(clippy::missing_panics_doc)]
+
+use common::{
+    vm::{Cpu, Effect, Instruction, Machine, State},
+    LineReader, Solution,
+};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Operation {
+    Accumulate(i64),
+    Jump(i64),
+    None(i64),
+}
+
+impl Operation {
+    fn from_line(line: &str) -> Operation {
+        let mut split = line.split(' ');
+
+        let mnemonic = split.next().expect("Failed to parse mnemonic");
+        let payload = split
+            .next()
+            .expect("Failed to parse payload")
+            .parse()
+            .expect("Failed to parse payload as i64");
+
+        match mnemonic {
+            "acc" => Operation::Accumulate(payload),
+            "jmp" => Operation::Jump(payload),
+            "nop" => Operation::None(payload),
+            _ => panic!("Unexpected mnemonic [{}]", mnemonic),
+        }
+    }
+
+    fn flipped(self) -> Self {
+        match self {
+            Operation::Accumulate(payload) => Operation::Accumulate(payload),
+            Operation::Jump(payload) => Operation::None(payload),
+            Operation::None(payload) => Operation::Jump(payload),
+        }
+    }
+}
+
+impl Instruction for Operation {
+    fn execute(&self, cpu: &mut Cpu) -> Effect {
+        match self {
+            Operation::Accumulate(payload) => {
+                cpu.registers[0] += payload;
+                Effect::Next
+            }
+            Operation::Jump(payload) => Effect::Jump(*payload),
+            Operation::None(_) => Effect::Next,
+        }
+    }
+}
+
+// Ok with the accumulator if the program runs off its end, or Err with the
+// accumulator from just before an instruction would run a second time
+fn run_program(program: Vec<Operation>) -> Result<i64, i64> {
+    let mut visited = vec![false; program.len()];
+    let mut machine = Machine::new(program, 1);
+    while machine.state() == State::Running {
+        if visited[machine.pc()] {
+            return Err(machine.register(0));
+        }
+        visited[machine.pc()] = true;
+        machine.step();
+    }
+
+    if machine.pc() == machine.program().len() {
+        Ok(machine.register(0))
+    } else {
+        Err(-1)
+    }
+}
+
+#[must_use]
+pub fn parse_program(text: &str) -> Vec<Operation> {
+    let mut program = Vec::new();
+    let mut reader = LineReader::from_text(text);
+    reader.read_with(|line| program.push(Operation::from_line(line)));
+    program
+}
+
+// The accumulator when the program first tries to repeat an instruction
+#[must_use]
+pub fn find_loop_accumulator(program: &[Operation]) -> Option<i64> {
+    run_program(program.to_vec()).err()
+}
+
+// The first jmp or nop that lets the program terminate once flipped, and the
+// accumulator it finishes with
+#[must_use]
+pub fn repair_program(program: &[Operation]) -> Option<(usize, i64)> {
+    for skip_pc in 0..program.len() {
+        if let Operation::Accumulate(_) = program[skip_pc] {
+            continue;
+        }
+
+        let mut patched = program.to_vec();
+        patched[skip_pc] = patched[skip_pc].flipped();
+        if let Ok(accumulator) = run_program(patched) {
+            return Some((skip_pc, accumulator));
+        }
+    }
+    None
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    fn part1(&self, input: &str) -> String {
+        find_loop_accumulator(&parse_program(input))
+            .expect("Failed to find an infinite loop")
+            .to_string()
+    }
+
+    fn part2(&self, input: &str) -> String {
+        let (_, accumulator) =
+            repair_program(&parse_program(input)).expect("Failed to repair the program");
+        accumulator.to_string()
+    }
+}
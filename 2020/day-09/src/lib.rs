@@ -0,0 +1,119 @@
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(clippy::comparison_chain, clippy::missing_panics_doc)]
+
+use std::collections::{HashMap, VecDeque};
+
+use common::{LineReader, Solution};
+
+// How many numbers come before the first one that has to be a sum
+pub const PREAMBLE_LENGTH: usize = 25;
+
+struct XmasValidator {
+    preamble_length: usize,
+    valid_sums: HashMap<i64, usize>,
+    window: VecDeque<i64>,
+    values: Vec<i64>,
+}
+
+impl XmasValidator {
+    fn new(preamble_length: usize) -> Self {
+        Self {
+            preamble_length,
+            valid_sums: HashMap::new(),
+            window: VecDeque::new(),
+            values: Vec::new(),
+        }
+    }
+
+    fn remove_oldest(&mut self) {
+        let removed = self
+            .window
+            .pop_front()
+            .expect("Failed to pop front when at preamble length");
+        for previous_value in &self.window {
+            let sum = removed + previous_value;
+
+            let sum_count = self
+                .valid_sums
+                .get_mut(&sum)
+                .expect("Failed to find sum in map");
+
+            *sum_count -= 1;
+            if *sum_count == 0 {
+                self.valid_sums.remove(&sum);
+            }
+        }
+    }
+
+    fn add_value(&mut self, value: i64) -> bool {
+        self.values.push(value);
+
+        let is_valid =
+            self.window.len() < self.preamble_length || self.valid_sums.contains_key(&value);
+
+        if self.window.len() == self.preamble_length {
+            self.remove_oldest();
+        }
+
+        for previous_value in &self.window {
+            *self.valid_sums.entry(value + previous_value).or_default() += 1;
+        }
+        self.window.push_back(value);
+
+        is_valid
+    }
+
+    fn find_weakness(&self, invalid_number: i64) -> i64 {
+        let mut first = 0_usize;
+        let mut last = 1_usize;
+        let mut sum = self.values[first] + self.values[last];
+        while sum != invalid_number {
+            if sum < invalid_number {
+                last += 1;
+                sum += self.values[last];
+            } else if sum > invalid_number {
+                sum -= self.values[first];
+                first += 1;
+            }
+        }
+
+        let mut min = self.values[first];
+        let mut max = self.values[first];
+        for value in &self.values[first..=last] {
+            min = min.min(*value);
+            max = max.max(*value);
+        }
+        min + max
+    }
+}
+
+// The first value that isn't a sum of two in the window before it, along with
+// the weakness that value exposes
+#[must_use]
+pub fn find_invalid_value(text: &str, preamble_length: usize) -> Option<(i64, i64)> {
+    let mut validator = XmasValidator::new(preamble_length);
+
+    for line in LineReader::from_text(text) {
+        let value = line.parse().expect("Failed to parse line as i64");
+        if !validator.add_value(value) {
+            return Some((value, validator.find_weakness(value)));
+        }
+    }
+    None
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    fn part1(&self, input: &str) -> String {
+        let (value, _) =
+            find_invalid_value(input, PREAMBLE_LENGTH).expect("Failed to find an invalid value");
+        value.to_string()
+    }
+
+    fn part2(&self, input: &str) -> String {
+        let (_, weakness) =
+            find_invalid_value(input, PREAMBLE_LENGTH).expect("Failed to find an invalid value");
+        weakness.to_string()
+    }
+}
@@ -0,0 +1,85 @@
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(clippy::missing_panics_doc)]
+
+use common::{LineReader, Solution};
+
+pub struct AdapterChainer {
+    adapters: Vec<usize>,
+}
+
+impl AdapterChainer {
+    fn new() -> Self {
+        Self {
+            adapters: Vec::new(),
+        }
+    }
+
+    fn add_adapter(&mut self, adapter: usize) {
+        self.adapters.push(adapter);
+    }
+
+    #[must_use]
+    pub fn from_text(text: &str) -> Self {
+        let mut chainer = Self::new();
+        for line in LineReader::from_text(text) {
+            chainer.add_adapter(line.parse().expect("Failed to parse adapter"));
+        }
+        chainer
+    }
+
+    pub fn get_difference_product(&mut self) -> i32 {
+        self.adapters.sort_unstable();
+
+        // Add the first and last differences
+        let mut differences = [0, 0, 1];
+        differences[self.adapters[0] - 1] += 1;
+
+        for window in self.adapters.windows(2) {
+            differences[window[1] - window[0] - 1] += 1;
+        }
+
+        differences[0] * differences[2]
+    }
+
+    // Only meaningful once, after get_difference_product has sorted the adapters
+    pub fn get_arrangement_count(&mut self) -> usize {
+        let back = self.adapters[self.adapters.len() - 1];
+        // Add one value outside the range [1,3] to break out of the inner loop below
+        self.adapters.push(back + 4);
+        self.adapters.reverse();
+        // Add the implicit 0 for the outlet
+        self.adapters.push(0);
+
+        let mut arrangements = vec![0_usize; self.adapters.len()];
+        // This is the final adapter, which always hooks directly to the device
+        arrangements[1] = 1;
+
+        for index in 2..self.adapters.len() {
+            for offset in 1..=3 {
+                if self.adapters[index - offset] - self.adapters[index] < 4 {
+                    arrangements[index] += arrangements[index - offset];
+                } else {
+                    break;
+                }
+            }
+        }
+
+        arrangements[arrangements.len() - 1]
+    }
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    fn part1(&self, input: &str) -> String {
+        AdapterChainer::from_text(input)
+            .get_difference_product()
+            .to_string()
+    }
+
+    fn part2(&self, input: &str) -> String {
+        let mut chainer = AdapterChainer::from_text(input);
+        chainer.get_difference_product();
+        chainer.get_arrangement_count().to_string()
+    }
+}
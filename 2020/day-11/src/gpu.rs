@@ -0,0 +1,360 @@
+use std::{
+    borrow::Cow,
+    convert::{TryFrom, TryInto},
+    sync::mpsc,
+};
+
+use wgpu::util::DeviceExt;
+
+use super::{Cell, History, Layout, Outcome};
+
+const WORKGROUP_SIZE: u32 = 64;
+const MAX_WORKGROUPS_PER_DIMENSION: u32 = 65535;
+
+const SHADER: &str = r"
+struct Params {
+    cell_count: u32,
+    threshold: u32,
+    row_stride: u32,
+    padding: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> adjacent_indices: array<u32>;
+@group(0) @binding(2) var<storage, read> seats_in: array<u32>;
+@group(0) @binding(3) var<storage, read_write> seats_out: array<u32>;
+@group(0) @binding(4) var<storage, read_write> counters: array<atomic<u32>, 4>;
+
+const FLOOR: u32 = 0u;
+const EMPTY: u32 = 1u;
+const OCCUPIED: u32 = 2u;
+const NO_SEAT: u32 = 0xFFFFFFFFu;
+
+fn mix(value: u32) -> u32 {
+    var hash = value;
+    hash ^= hash >> 16u;
+    hash *= 0x85EBCA6Bu;
+    hash ^= hash >> 13u;
+    hash *= 0xC2B2AE35u;
+    hash ^= hash >> 16u;
+    return hash;
+}
+
+@compute @workgroup_size(64)
+fn evolve(@builtin(global_invocation_id) id: vec3<u32>) {
+    let index = id.y * params.row_stride + id.x;
+    if (index >= params.cell_count) {
+        return;
+    }
+
+    let seat = seats_in[index];
+    if (seat == FLOOR) {
+        seats_out[index] = FLOOR;
+        return;
+    }
+
+    var count = 0u;
+    for (var offset = 0u; offset < 8u; offset++) {
+        let adjacent_index = adjacent_indices[index * 8u + offset];
+        if (adjacent_index == NO_SEAT) {
+            break;
+        }
+        if (seats_in[adjacent_index] == OCCUPIED) {
+            count++;
+        }
+    }
+
+    var next = seat;
+    if (seat == OCCUPIED && count >= params.threshold) {
+        next = EMPTY;
+    } else if (seat == EMPTY && count == 0u) {
+        next = OCCUPIED;
+    }
+
+    seats_out[index] = next;
+    if (next != seat) {
+        atomicAdd(&counters[0], 1u);
+    }
+    if (next == OCCUPIED) {
+        atomicAdd(&counters[1], 1u);
+        atomicXor(&counters[2], mix(index));
+        atomicXor(&counters[3], mix(index + 0x9E3779B9u));
+    }
+}
+";
+
+// The shader hashes the state the same way Layout does, as the XOR of a key
+// for every occupied seat, but builds each key from two 32-bit halves since
+// WGSL has no 64-bit integers. This is the murmur3 finalizer it uses for them.
+fn mix(value: u32) -> u32 {
+    let mut hash = value;
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85EB_CA6B);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xC2B2_AE35);
+    hash ^ (hash >> 16)
+}
+
+fn get_state_hash(seats: &[u32]) -> u64 {
+    (0_u32..)
+        .zip(seats)
+        .filter(|(_, seat)| **seat == 2)
+        .fold(0, |hash, (index, _)| {
+            let high = u64::from(mix(index.wrapping_add(0x9E37_79B9)));
+            hash ^ (high << 32 | u64::from(mix(index)))
+        })
+}
+
+fn get_initial_seats(layout: &Layout) -> Vec<u32> {
+    layout
+        .map
+        .iter()
+        .zip(&layout.occupied_seats)
+        .map(|(cell, occupied)| match cell {
+            Cell::Floor => 0,
+            Cell::Empty | Cell::Occupied => {
+                if *occupied {
+                    2
+                } else {
+                    1
+                }
+            }
+        })
+        .collect()
+}
+
+fn as_bytes(words: &[u32]) -> Vec<u8> {
+    words.iter().flat_map(|word| word.to_ne_bytes()).collect()
+}
+
+struct Engine {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+    bind_groups: [wgpu::BindGroup; 2],
+    counters: wgpu::Buffer,
+    readback: wgpu::Buffer,
+    workgroups: (u32, u32),
+}
+
+fn request_device() -> (wgpu::Device, wgpu::Queue) {
+    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::new_without_display_handle());
+    let adapter =
+        pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
+            .expect("Failed to find a GPU adapter");
+    pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default()))
+        .expect("Failed to create GPU device")
+}
+
+fn get_workgroups(cell_count: u32) -> (u32, u32) {
+    let group_count = cell_count.div_ceil(WORKGROUP_SIZE);
+    if group_count > MAX_WORKGROUPS_PER_DIMENSION {
+        (
+            MAX_WORKGROUPS_PER_DIMENSION,
+            group_count.div_ceil(MAX_WORKGROUPS_PER_DIMENSION),
+        )
+    } else {
+        (group_count.max(1), 1)
+    }
+}
+
+// Buffers are bound in shader binding order: params, adjacent_indices,
+// seats_in, seats_out, counters
+fn create_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    buffers: [&wgpu::Buffer; 5],
+) -> wgpu::BindGroup {
+    let entries: Vec<wgpu::BindGroupEntry> = (0..)
+        .zip(&buffers)
+        .map(|(binding, buffer)| wgpu::BindGroupEntry {
+            binding,
+            resource: buffer.as_entire_binding(),
+        })
+        .collect();
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: None,
+        layout,
+        entries: &entries,
+    })
+}
+
+impl Engine {
+    fn new(layout: &Layout) -> Self {
+        let (device, queue) = request_device();
+
+        let cell_count: u32 = layout
+            .map
+            .len()
+            .try_into()
+            .expect("Couldn't store cell count in u32");
+        let workgroups = get_workgroups(cell_count);
+
+        let threshold: u32 = layout
+            .rule
+            .abandonment_threshold
+            .try_into()
+            .expect("Couldn't store threshold in u32");
+        let params = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("params"),
+            contents: &as_bytes(&[cell_count, threshold, workgroups.0 * WORKGROUP_SIZE, 0]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+
+        // The shader reads eight slots per cell, with NO_SEAT filling any
+        // that don't see a seat
+        let adjacent_indices: Vec<u32> = layout
+            .adjacent_indices
+            .iter()
+            .flat_map(|seats| {
+                let mut slots = [u32::MAX; 8];
+                for (slot, index) in slots.iter_mut().zip(seats) {
+                    *slot = u32::try_from(*index).expect("Couldn't store seat index in u32");
+                }
+                slots
+            })
+            .collect();
+        let adjacent_indices = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("adjacent_indices"),
+            contents: &as_bytes(&adjacent_indices),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+
+        let initial_seats = as_bytes(&get_initial_seats(layout));
+        let seats = [
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("seats_a"),
+                contents: &initial_seats,
+                usage: wgpu::BufferUsages::STORAGE,
+            }),
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("seats_b"),
+                contents: &initial_seats,
+                usage: wgpu::BufferUsages::STORAGE,
+            }),
+        ];
+
+        let counters = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("counters"),
+            size: 16,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_SRC
+                | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("readback"),
+            size: 16,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("evolve"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(SHADER)),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("evolve"),
+            layout: None,
+            module: &module,
+            entry_point: Some("evolve"),
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+            cache: None,
+        });
+
+        let bind_group_layout = pipeline.get_bind_group_layout(0);
+        let bind_groups = [
+            create_bind_group(
+                &device,
+                &bind_group_layout,
+                [&params, &adjacent_indices, &seats[0], &seats[1], &counters],
+            ),
+            create_bind_group(
+                &device,
+                &bind_group_layout,
+                [&params, &adjacent_indices, &seats[1], &seats[0], &counters],
+            ),
+        ];
+
+        Self {
+            device,
+            queue,
+            pipeline,
+            bind_groups,
+            counters,
+            readback,
+            workgroups,
+        }
+    }
+
+    // Returns the number of seats that changed, the number of occupied seats
+    // and the hash of the new state
+    fn evolve(&self, generation: usize) -> (u32, u32, u64) {
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        encoder.clear_buffer(&self.counters, 0, None);
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &self.bind_groups[generation % 2], &[]);
+            pass.dispatch_workgroups(self.workgroups.0, self.workgroups.1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&self.counters, 0, &self.readback, 0, 16);
+        self.queue.submit(Some(encoder.finish()));
+
+        let (sender, receiver) = mpsc::channel();
+        self.readback
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                sender.send(result).expect("Failed to send map result");
+            });
+        self.device
+            .poll(wgpu::PollType::wait_indefinitely())
+            .expect("Failed to poll GPU device");
+        receiver
+            .recv()
+            .expect("Failed to receive map result")
+            .expect("Failed to map readback buffer");
+
+        let counts = {
+            let data = self
+                .readback
+                .slice(..)
+                .get_mapped_range()
+                .expect("Failed to read mapped buffer");
+            let word = |index: usize| {
+                u32::from_ne_bytes(data[4 * index..4 * index + 4].try_into().unwrap())
+            };
+            let state_hash = u64::from(word(3)) << 32 | u64::from(word(2));
+            (word(0), word(1), state_hash)
+        };
+        self.readback.unmap();
+        counts
+    }
+}
+
+// Records every generation in the same History as Layout::run, so the GPU
+// finds the same cycles and stops at the same limits
+pub fn settle(layout: &Layout) -> Outcome {
+    let engine = Engine::new(layout);
+    let mut history = History::new(layout.max_generations, layout.time_budget);
+
+    let mut occupants = layout.count_occupants();
+    let mut state_hash = get_state_hash(&get_initial_seats(layout));
+    for generation in 0.. {
+        if let Some(outcome) = history.record(Some(state_hash), occupants) {
+            return outcome;
+        }
+
+        let (changes, next_occupants, next_hash) = engine.evolve(generation);
+        if changes == 0 {
+            break;
+        }
+        occupants = next_occupants
+            .try_into()
+            .expect("Couldn't store occupant count in usize");
+        state_hash = next_hash;
+    }
+    Outcome::Stable(occupants)
+}
@@ -0,0 +1,1406 @@
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+#![cfg_attr(feature = "nightly", feature(test))]
+
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    error::Error,
+    fmt::{Display, Formatter},
+    io::BufRead,
+    path::Path,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use common::{csr::Csr, gen::Rng, golly, grid::Grid, hex::Cube, input, normalize_line, Solution};
+
+#[cfg(feature = "gpu")]
+mod gpu;
+pub mod sweep;
+pub mod tiled;
+
+#[cfg(feature = "nightly")]
+extern crate test;
+
+#[derive(Clone, Copy)]
+enum Cell {
+    Floor,
+    Empty,
+    Occupied,
+}
+
+// How the map's rows and columns connect. On a hex grid, odd rows sit half a
+// seat to the right of even ones, so each seat has six neighbors instead of
+// eight and six lines of sight.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Topology {
+    Square,
+    Hex,
+}
+
+impl Topology {
+    pub const NAMES: [&'static str; 2] = ["square", "hex"];
+}
+
+impl FromStr for Topology {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "square" => Ok(Self::Square),
+            "hex" => Ok(Self::Hex),
+            _ => Err(format!("unknown topology {text}")),
+        }
+    }
+}
+
+// Where generations are computed. The GPU engine only exists when built with
+// the gpu feature.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Engine {
+    Cpu,
+    #[cfg(feature = "gpu")]
+    Gpu,
+}
+
+impl Engine {
+    #[cfg(feature = "gpu")]
+    pub const NAMES: [&'static str; 2] = ["cpu", "gpu"];
+    #[cfg(not(feature = "gpu"))]
+    pub const NAMES: [&'static str; 1] = ["cpu"];
+}
+
+impl FromStr for Engine {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "cpu" => Ok(Self::Cpu),
+            #[cfg(feature = "gpu")]
+            "gpu" => Ok(Self::Gpu),
+            _ => Err(format!("unknown engine {text}")),
+        }
+    }
+}
+
+// East, southeast, southwest, west, northwest and northeast
+const HEX_STEPS: [Cube; 6] = [
+    Cube { x: 1, y: -1, z: 0 },
+    Cube { x: 0, y: -1, z: 1 },
+    Cube { x: -1, y: 0, z: 1 },
+    Cube { x: -1, y: 1, z: 0 },
+    Cube { x: 0, y: 1, z: -1 },
+    Cube { x: 1, y: 0, z: -1 },
+];
+
+// Rows run along z, and odd rows are pushed half a seat right
+fn offset_to_cube(row: usize, column: usize) -> Cube {
+    let (row, column) = (
+        i64::try_from(row).expect("Row out of range"),
+        i64::try_from(column).expect("Column out of range"),
+    );
+    let x = column - (row - (row & 1)) / 2;
+    Cube::new(x, -x - row, row)
+}
+
+fn cube_to_offset(cube: Cube) -> Option<(usize, usize)> {
+    let column = cube.x + (cube.z - (cube.z & 1)) / 2;
+    Some((usize::try_from(cube.z).ok()?, usize::try_from(column).ok()?))
+}
+
+#[derive(Clone, Copy)]
+pub struct Rule {
+    // None means seats are visible along the whole line of sight
+    pub radius: Option<usize>,
+    pub abandonment_threshold: usize,
+    pub topology: Topology,
+}
+
+impl Rule {
+    #[must_use]
+    pub fn new(line_of_sight: bool) -> Self {
+        if line_of_sight {
+            Self {
+                radius: None,
+                abandonment_threshold: 5,
+                topology: Topology::Square,
+            }
+        } else {
+            Self {
+                radius: Some(1),
+                abandonment_threshold: 4,
+                topology: Topology::Square,
+            }
+        }
+    }
+}
+
+// Each flip mandated by the rule is skipped with the given probability
+#[derive(Clone)]
+pub struct Noise {
+    probability: f64,
+    rng: Rng,
+}
+
+impl Noise {
+    #[must_use]
+    pub fn new(probability: f64, seed: u64) -> Self {
+        Self {
+            probability,
+            rng: Rng::new(seed),
+        }
+    }
+}
+
+// Seats are addressed by their index in the map, and adjacent_indices holds
+// the seats each cell can see, which is none for floor
+#[derive(Clone)]
+pub struct Layout {
+    rule: Rule,
+    noise: Option<Noise>,
+    max_generations: Option<usize>,
+    time_budget: Option<Duration>,
+    map: Vec<Cell>,
+    column_count: usize,
+    row_count: usize,
+    adjacent_indices: Csr<usize>,
+    candidate_indices: Vec<usize>,
+    is_candidate: Vec<bool>,
+    occupied_seats: Vec<bool>,
+    // Updated from each generation's changes, so neither the count nor the
+    // hash has to visit every seat
+    occupants: usize,
+    state_hash: u64,
+    // Kept between generations so evolve doesn't allocate
+    changes: Vec<usize>,
+    deferred: Vec<usize>,
+}
+
+impl Layout {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            rule: Rule::new(false),
+            noise: None,
+            max_generations: None,
+            time_budget: None,
+            map: Vec::new(),
+            column_count: 0,
+            row_count: 0,
+            adjacent_indices: Csr::new(),
+            candidate_indices: Vec::new(),
+            is_candidate: Vec::new(),
+            occupied_seats: Vec::new(),
+            occupants: 0,
+            state_hash: 0,
+            changes: Vec::new(),
+            deferred: Vec::new(),
+        }
+    }
+
+    // A random board for benchmarks, where each cell is an empty seat with
+    // probability seat_density and floor otherwise. It still needs finalizing
+    // with a rule like one that was read from a file.
+    #[must_use]
+    pub fn synthesize(rows: usize, columns: usize, seat_density: f64, seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        let mut layout = Self::new();
+        layout.map = (0..rows * columns)
+            .map(|_| {
+                if rng.chance(seat_density) {
+                    Cell::Empty
+                } else {
+                    Cell::Floor
+                }
+            })
+            .collect();
+        layout.row_count = rows;
+        layout.column_count = columns;
+        layout
+    }
+
+    // Rows are numbered from 1 in errors, which matches the input file as long
+    // as every row has come from it
+    pub fn add_line(&mut self, line: &str) -> Result<(), LayoutError> {
+        check_row(
+            self.row_count + 1,
+            line,
+            (self.row_count > 0).then_some(self.column_count),
+        )?;
+
+        for byte in line.as_bytes() {
+            self.map.push(match byte {
+                b'.' => Cell::Floor,
+                b'L' => Cell::Empty,
+                b'#' => Cell::Occupied,
+                _ => unreachable!(),
+            });
+        }
+
+        self.column_count = line.len();
+        self.row_count += 1;
+        Ok(())
+    }
+
+    fn get_index(&self, row: usize, column: usize) -> usize {
+        row * self.column_count + column
+    }
+
+    // Follows step from the given seat until it finds another seat, leaves
+    // the map or goes past the rule's radius
+    fn find_seat<F>(&self, mut row: usize, mut column: usize, step: F) -> Option<usize>
+    where
+        F: Fn(usize, usize) -> Option<(usize, usize)>,
+    {
+        let mut steps = 0;
+        loop {
+            steps += 1;
+            (row, column) = step(row, column)
+                .filter(|(row, column)| *row < self.row_count && *column < self.column_count)?;
+
+            let index = self.get_index(row, column);
+            match self
+                .map
+                .get(index)
+                .unwrap_or_else(|| panic!("Index {} not found in map", index))
+            {
+                Cell::Floor => (),
+                Cell::Empty | Cell::Occupied => return Some(index),
+            }
+
+            if self.rule.radius == Some(steps) {
+                return None;
+            }
+        }
+    }
+
+    fn get_adjacent_indices(&self, row: usize, column: usize) -> Vec<usize> {
+        match self.rule.topology {
+            Topology::Square => (-1..=1)
+                .flat_map(|delta_y| (-1..=1).map(move |delta_x| (delta_x, delta_y)))
+                .filter(|delta| *delta != (0, 0))
+                .filter_map(|(delta_x, delta_y)| {
+                    self.find_seat(row, column, |row, column| {
+                        Some((
+                            row.checked_add_signed(delta_y)?,
+                            column.checked_add_signed(delta_x)?,
+                        ))
+                    })
+                })
+                .collect(),
+            Topology::Hex => HEX_STEPS
+                .iter()
+                .filter_map(|step| {
+                    self.find_seat(row, column, |row, column| {
+                        cube_to_offset(offset_to_cube(row, column) + *step)
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    pub fn finalize(&mut self, rule: Rule) {
+        let _scope = common::scope!("adjacency");
+        // find_seat only stops a ray once it has taken radius steps, and it
+        // always takes at least one, so 0 would silently mean no limit at all
+        assert_ne!(
+            rule.radius,
+            Some(0),
+            "Failed to finalize: radius can't be 0"
+        );
+        self.rule = rule;
+        for row in 0..self.row_count {
+            for column in 0..self.column_count {
+                let index = self.get_index(row, column);
+                if let Cell::Floor = self.map[index] {
+                    self.adjacent_indices.push_node(std::iter::empty());
+                    continue;
+                }
+
+                let adjacent_indices = self.get_adjacent_indices(row, column);
+                self.adjacent_indices.push_node(adjacent_indices);
+                self.candidate_indices.push(index);
+            }
+        }
+        self.occupied_seats = self
+            .map
+            .iter()
+            .map(|cell| matches!(cell, Cell::Occupied))
+            .collect();
+        self.is_candidate.resize(self.occupied_seats.len(), false);
+
+        self.occupants = 0;
+        self.state_hash = 0;
+        for (index, occupied) in self.occupied_seats.iter().enumerate() {
+            if *occupied {
+                self.occupants += 1;
+                self.state_hash ^= get_seat_key(index);
+            }
+        }
+    }
+
+    // The map holds the initial layout; once finalized, occupancy is tracked separately
+    fn get_cell(&self, index: usize) -> Cell {
+        let cell = *self
+            .map
+            .get(index)
+            .unwrap_or_else(|| panic!("Index {} not found in map", index));
+        match (cell, self.occupied_seats.get(index)) {
+            (Cell::Floor, _) | (_, None) => cell,
+            (_, Some(true)) => Cell::Occupied,
+            (_, Some(false)) => Cell::Empty,
+        }
+    }
+
+    fn adjacent_seats(&self, index: usize) -> impl Iterator<Item = &usize> {
+        self.adjacent_indices.neighbors(index).iter()
+    }
+
+    fn count_adjacent_occupants(&self, index: usize) -> usize {
+        self.adjacent_seats(index)
+            .filter(|adjacent_index| self.occupied_seats[**adjacent_index])
+            .count()
+    }
+
+    // Replaces the contents of changes with the seats that flip this generation
+    fn collect_changes_into(&self, changes: &mut Vec<usize>) {
+        changes.clear();
+
+        let abandonment_threshold = self.rule.abandonment_threshold;
+
+        for index in &self.candidate_indices {
+            if self.occupied_seats[*index] {
+                if self.count_adjacent_occupants(*index) >= abandonment_threshold {
+                    changes.push(*index);
+                }
+            } else if self.count_adjacent_occupants(*index) == 0 {
+                changes.push(*index);
+            }
+        }
+    }
+
+    fn mark_candidate(&mut self, index: usize) {
+        if !self.is_candidate[index] {
+            self.is_candidate[index] = true;
+            self.candidate_indices.push(index);
+        }
+    }
+
+    fn apply_changes(&mut self, changes: &[usize], deferred: &[usize]) {
+        for change in changes {
+            self.occupied_seats[*change] ^= true;
+            if self.occupied_seats[*change] {
+                self.occupants += 1;
+            } else {
+                self.occupants -= 1;
+            }
+            self.state_hash ^= get_seat_key(*change);
+        }
+
+        // Visibility is symmetric, so only seats that can see a changed seat
+        // (plus the changed seats themselves) may change next generation.
+        // Deferred seats still owe a flip, so they stay candidates.
+        self.candidate_indices.clear();
+        for index in deferred {
+            self.mark_candidate(*index);
+        }
+        for change in changes {
+            self.mark_candidate(*change);
+            for offset in 0..self.adjacent_indices.neighbors(*change).len() {
+                let adjacent_index = self.adjacent_indices.neighbors(*change)[offset];
+                self.mark_candidate(adjacent_index);
+            }
+        }
+
+        for index in &self.candidate_indices {
+            self.is_candidate[*index] = false;
+        }
+    }
+
+    pub fn evolve(&mut self) -> bool {
+        let _scope = common::scope!("evolve");
+        let mut changes = std::mem::take(&mut self.changes);
+        let mut deferred = std::mem::take(&mut self.deferred);
+        self.collect_changes_into(&mut changes);
+
+        deferred.clear();
+        if let Some(noise) = &mut self.noise {
+            changes.retain(|index| {
+                let skipped = noise.rng.chance(noise.probability);
+                if skipped {
+                    deferred.push(*index);
+                }
+                !skipped
+            });
+        }
+
+        let any_flips = !changes.is_empty() || !deferred.is_empty();
+        if any_flips {
+            self.apply_changes(&changes, &deferred);
+        }
+        self.changes = changes;
+        self.deferred = deferred;
+        any_flips
+    }
+
+    #[must_use]
+    pub fn count_occupants(&self) -> usize {
+        self.occupants
+    }
+
+    pub fn set_noise(&mut self, noise: Option<Noise>) {
+        self.noise = noise;
+    }
+
+    // Gives up with Outcome::Diverged after this many generations, since a
+    // noisy layout or an unusual rule might never settle
+    pub fn set_max_generations(&mut self, max_generations: Option<usize>) {
+        self.max_generations = max_generations;
+    }
+
+    // Gives up with Outcome::OutOfTime once a run has taken this long
+    pub fn set_time_budget(&mut self, time_budget: Option<Duration>) {
+        self.time_budget = time_budget;
+    }
+
+    // Evolves until the layout stops changing or revisits an earlier
+    // generation, calling observe with each generation along the way
+    pub fn run_with_observer<F>(&mut self, mut observe: F) -> Outcome
+    where
+        F: FnMut(usize, &Layout),
+    {
+        let _scope = common::scope!("run");
+        let mut history = History::new(self.max_generations, self.time_budget);
+
+        loop {
+            observe(history.get_generation(), self);
+
+            // A noisy layout can revisit a state without being stuck in it
+            let state_hash = self.noise.is_none().then_some(self.state_hash);
+            if let Some(outcome) = history.record(state_hash, self.occupants) {
+                return outcome;
+            }
+
+            if !self.evolve() {
+                return Outcome::Stable(self.count_occupants());
+            }
+        }
+    }
+
+    pub fn run(&mut self) -> Outcome {
+        self.run_with_observer(|_, _| ())
+    }
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// The state hash is the XOR of the keys of every occupied seat, so flipping a
+// seat changes it by that seat's key alone. SplitMix64 scatters neighboring
+// indices across all 64 bits.
+fn get_seat_key(index: usize) -> u64 {
+    Rng::new(u64::try_from(index).unwrap()).next_u64()
+}
+
+// Tracks how often each seat is occupied across the generations it observes
+#[derive(Default)]
+pub struct OccupancyFrequency {
+    column_count: usize,
+    seats: Vec<bool>,
+    occupied_generations: Vec<u32>,
+    generations: u32,
+}
+
+impl OccupancyFrequency {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn observe(&mut self, layout: &Layout) {
+        if self.generations == 0 {
+            self.column_count = layout.column_count;
+            self.seats = layout
+                .map
+                .iter()
+                .map(|cell| !matches!(cell, Cell::Floor))
+                .collect();
+            self.occupied_generations = vec![0; self.seats.len()];
+        }
+
+        for (count, occupied) in self
+            .occupied_generations
+            .iter_mut()
+            .zip(&layout.occupied_seats)
+        {
+            *count += u32::from(*occupied);
+        }
+        self.generations += 1;
+    }
+
+    // Rows of per-seat occupied fractions, with None for floor cells
+    #[must_use]
+    pub fn get_fractions(&self) -> Vec<Vec<Option<f64>>> {
+        self.seats
+            .iter()
+            .zip(&self.occupied_generations)
+            .map(|(seat, count)| {
+                if *seat {
+                    Some(f64::from(*count) / f64::from(self.generations))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>()
+            .chunks(self.column_count.max(1))
+            .map(<[Option<f64>]>::to_vec)
+            .collect()
+    }
+}
+
+impl Layout {
+    // The current state as '.', 'L' and '#', the same characters as the input
+    #[must_use]
+    pub fn to_grid(&self) -> Grid<char> {
+        let cells = (0..self.map.len())
+            .map(|index| match self.get_cell(index) {
+                Cell::Floor => '.',
+                Cell::Empty => 'L',
+                Cell::Occupied => '#',
+            })
+            .collect();
+        Grid::from_cells(self.column_count, cells)
+    }
+}
+
+impl Display for Layout {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in 0..self.row_count {
+            for column in 0..self.column_count {
+                let index = self.get_index(row, column);
+                write!(
+                    f,
+                    "{}",
+                    match self.get_cell(index) {
+                        Cell::Floor => '.',
+                        Cell::Empty => 'L',
+                        Cell::Occupied => '#',
+                    }
+                )?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+// What's wrong with a layout. Lines and columns count from 1.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LayoutError {
+    Unreadable(String),
+    Pattern(golly::DecodeError),
+    EmptyInput,
+    EmptyLine(usize),
+    UnexpectedCharacter {
+        line: usize,
+        column: usize,
+        character: char,
+    },
+    WrongWidth {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl Display for LayoutError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unreadable(reason) => write!(f, "failed to read: {reason}"),
+            Self::Pattern(error) => write!(f, "{error}"),
+            Self::EmptyInput => write!(f, "no rows in layout"),
+            Self::EmptyLine(line) => write!(f, "line {line}: empty row"),
+            Self::UnexpectedCharacter {
+                line,
+                column,
+                character,
+            } => write!(
+                f,
+                "line {line}, column {column}: unexpected character {character:?}, expected '.', 'L' or '#'"
+            ),
+            Self::WrongWidth {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {line}: row has {found} columns but earlier rows have {expected}"
+            ),
+        }
+    }
+}
+
+impl Error for LayoutError {}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InputError {
+    pub filename: String,
+    pub error: LayoutError,
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.filename, self.error)
+    }
+}
+
+impl Error for InputError {}
+
+// Checks that a row only holds floor and seats, and is as wide as the rows
+// before it if there were any
+pub fn check_row(line: usize, row: &str, expected: Option<usize>) -> Result<(), LayoutError> {
+    if row.is_empty() {
+        return Err(LayoutError::EmptyLine(line));
+    }
+
+    if let Some((index, character)) = row
+        .chars()
+        .enumerate()
+        .find(|(_, character)| !matches!(character, '.' | 'L' | '#'))
+    {
+        return Err(LayoutError::UnexpectedCharacter {
+            line,
+            column: index + 1,
+            character,
+        });
+    }
+
+    match expected {
+        Some(expected) if row.len() != expected => Err(LayoutError::WrongWidth {
+            line,
+            expected,
+            found: row.len(),
+        }),
+        _ => Ok(()),
+    }
+}
+
+// Passes each row of a puzzle input to add_row along with its line number.
+// Blank lines are only allowed at the end of the file.
+fn read_rows<F>(filename: &str, mut add_row: F) -> Result<(), InputError>
+where
+    F: FnMut(usize, &str) -> Result<(), LayoutError>,
+{
+    let error = |error| InputError {
+        filename: String::from(filename),
+        error,
+    };
+
+    let reader =
+        input::open(filename).map_err(|e| error(LayoutError::Unreadable(e.to_string())))?;
+
+    let mut rows = 0;
+    let mut first_blank = None;
+    for (index, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| error(LayoutError::Unreadable(e.to_string())))?;
+        let line = normalize_line(&line);
+        if line.is_empty() {
+            first_blank = first_blank.or(Some(index + 1));
+            continue;
+        }
+        if let Some(blank) = first_blank {
+            return Err(error(LayoutError::EmptyLine(blank)));
+        }
+
+        add_row(index + 1, line).map_err(error)?;
+        rows += 1;
+    }
+
+    if rows == 0 {
+        return Err(error(LayoutError::EmptyInput));
+    }
+    Ok(())
+}
+
+// Golly patterns in the lettered states use 1 for empty seats and 2 for
+// occupied ones, matching what --emit-rle writes. Plain live/dead patterns
+// are read as a hall full of seats with the live cells occupied. Any other
+// state is reported by add_line, counting lines as rows of the pattern.
+fn read_pattern(filename: &str) -> Result<Layout, InputError> {
+    let error = |error| InputError {
+        filename: String::from(filename),
+        error,
+    };
+
+    let text = input::read_to_string(filename)
+        .map_err(|e| error(LayoutError::Unreadable(e.to_string())))?;
+    let pattern = golly::decode(&text).map_err(|e| error(LayoutError::Pattern(e)))?;
+    if pattern.grid.width() == 0 || pattern.grid.height() == 0 {
+        return Err(error(LayoutError::EmptyInput));
+    }
+
+    let mut layout = Layout::new();
+    for row in pattern.grid.rows() {
+        let line: String = row
+            .iter()
+            .map(|state| match (pattern.multi_state, state) {
+                (true, 0) => '.',
+                (true, 1) | (false, 0) => 'L',
+                (true, 2) | (false, 1) => '#',
+                _ => '?',
+            })
+            .collect();
+        layout.add_line(&line).map_err(error)?;
+    }
+    Ok(layout)
+}
+
+// Reads either a puzzle input or, for files ending in .rle, a Golly pattern
+pub fn read_layout(filename: &str) -> Result<Layout, InputError> {
+    let _scope = common::scope!("parse");
+    if Path::new(filename)
+        .extension()
+        .is_some_and(|extension| extension == "rle")
+    {
+        return read_pattern(filename);
+    }
+
+    let mut layout = Layout::new();
+    read_rows(filename, |_, line| layout.add_line(line))?;
+    Ok(layout)
+}
+
+pub enum Outcome {
+    Stable(usize),
+    Cycle {
+        start: usize,
+        length: usize,
+        occupancy: Vec<usize>,
+    },
+    Diverged {
+        generations: usize,
+    },
+    // How far a run got before its time budget ran out
+    OutOfTime {
+        generations: usize,
+        occupants: usize,
+    },
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Stable(occupants) => write!(f, "Occupied seats: {occupants}"),
+            Outcome::Cycle {
+                start,
+                length,
+                occupancy,
+            } => {
+                let occupancy: Vec<String> = occupancy.iter().map(usize::to_string).collect();
+                write!(
+                    f,
+                    "Entered cycle of length {} at generation {} (occupied seats: {})",
+                    length,
+                    start,
+                    occupancy.join(", ")
+                )
+            }
+            Outcome::Diverged { generations } => {
+                write!(f, "Exceeded {generations} generations without converging")
+            }
+            Outcome::OutOfTime {
+                generations,
+                occupants,
+            } => write!(
+                f,
+                "Ran out of time after {generations} generations with {occupants} seats occupied"
+            ),
+        }
+    }
+}
+
+// Remembers a hash of every generation to spot when a layout starts repeating
+struct History {
+    seen_generations: HashMap<u64, usize>,
+    occupancy: Vec<usize>,
+    max_generations: Option<usize>,
+    deadline: Option<Instant>,
+}
+
+impl History {
+    fn new(max_generations: Option<usize>, time_budget: Option<Duration>) -> Self {
+        Self {
+            seen_generations: HashMap::new(),
+            occupancy: Vec::new(),
+            max_generations,
+            deadline: time_budget.map(|time_budget| Instant::now() + time_budget),
+        }
+    }
+
+    fn get_generation(&self) -> usize {
+        self.occupancy.len()
+    }
+
+    fn record(&mut self, state_hash: Option<u64>, occupants: usize) -> Option<Outcome> {
+        let generation = self.occupancy.len();
+        self.occupancy.push(occupants);
+
+        if let Some(start) =
+            state_hash.and_then(|state_hash| self.seen_generations.insert(state_hash, generation))
+        {
+            return Some(Outcome::Cycle {
+                start,
+                length: generation - start,
+                occupancy: self.occupancy[start..generation].to_vec(),
+            });
+        }
+
+        // Every generation recorded so far changed something, so this one
+        // is past the limit rather than just reaching it
+        match self.max_generations {
+            Some(max_generations) if generation > max_generations => Some(Outcome::Diverged {
+                generations: max_generations,
+            }),
+            _ if self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline) =>
+            {
+                Some(Outcome::OutOfTime {
+                    generations: generation,
+                    occupants,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+// Only the CPU engine supports noise
+#[must_use]
+pub fn solve(mut layout: Layout, engine: Engine) -> Outcome {
+    match engine {
+        Engine::Cpu => layout.run(),
+        #[cfg(feature = "gpu")]
+        Engine::Gpu => {
+            assert!(
+                layout.noise.is_none(),
+                "Noise is only supported by the CPU engine"
+            );
+            gpu::settle(&layout)
+        }
+    }
+}
+
+// The puzzle's own rules on a layout given as text, which has to settle
+fn count_settled_occupants(text: &str, line_of_sight: bool) -> usize {
+    let mut layout = Layout::new();
+    for line in text
+        .lines()
+        .map(normalize_line)
+        .filter(|line| !line.is_empty())
+    {
+        layout
+            .add_line(line)
+            .unwrap_or_else(|error| panic!("Failed to parse layout: {}", error));
+    }
+    settle_puzzle(layout, line_of_sight)
+}
+
+// Part 1 looks at adjacent seats and part 2 along lines of sight
+#[must_use]
+pub fn settle_puzzle(mut layout: Layout, line_of_sight: bool) -> usize {
+    layout.finalize(Rule::new(line_of_sight));
+    match layout.run() {
+        Outcome::Stable(occupants) => occupants,
+        outcome => panic!("Failed to settle the layout: {}", outcome),
+    }
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    fn part1(&self, input: &str) -> String {
+        count_settled_occupants(input, false).to_string()
+    }
+
+    fn part2(&self, input: &str) -> String {
+        count_settled_occupants(input, true).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{fixtures, LineReader};
+    use std::fs;
+    #[cfg(feature = "nightly")]
+    use test::Bencher;
+
+    fn fixture(filename: &str) -> String {
+        fixtures::path(&format!("2020/day-11/{filename}"))
+    }
+
+    fn get_layout(line_of_sight: bool) -> Layout {
+        let mut layout = Layout::new();
+        for line in LineReader::new(&fixture("input.txt")) {
+            layout.add_line(&line).unwrap();
+        }
+
+        layout.finalize(Rule::new(line_of_sight));
+
+        layout
+    }
+
+    #[cfg(feature = "nightly")]
+    #[bench]
+    fn bench_adjacent(bencher: &mut Bencher) {
+        let layout = get_layout(false);
+        bencher.iter(|| {
+            let mut cloned = layout.clone();
+            while cloned.evolve() {}
+            assert_eq!(cloned.count_occupants(), 2361);
+        });
+    }
+
+    #[cfg(feature = "nightly")]
+    #[bench]
+    fn bench_line_of_sight(bencher: &mut Bencher) {
+        let layout = get_layout(true);
+        bencher.iter(|| {
+            let mut cloned = layout.clone();
+            while cloned.evolve() {}
+            assert_eq!(cloned.count_occupants(), 2119);
+        });
+    }
+
+    #[cfg(feature = "nightly")]
+    #[bench]
+    fn bench_synthesized(bencher: &mut Bencher) {
+        let mut layout = Layout::synthesize(200, 200, 0.7, 11);
+        layout.finalize(Rule::new(true));
+        bencher.iter(|| {
+            let mut cloned = layout.clone();
+            while cloned.evolve() {}
+            cloned.count_occupants()
+        });
+    }
+
+    #[test]
+    fn synthesized_boards_follow_seed_and_density() {
+        let layout = Layout::synthesize(100, 300, 0.25, 7);
+        let grid = layout.to_grid();
+        assert_eq!((grid.width(), grid.height()), (300, 100));
+        let seats = grid.iter().filter(|(_, cell)| **cell == 'L').count();
+        assert!((7000..8000).contains(&seats), "{} seats", seats);
+        assert_eq!(Layout::synthesize(100, 300, 0.25, 7).to_grid(), grid);
+        assert_ne!(Layout::synthesize(100, 300, 0.25, 8).to_grid(), grid);
+
+        let mut layout = Layout::synthesize(20, 20, 1.0, 0);
+        layout.finalize(Rule::new(false));
+        assert!(matches!(layout.run(), Outcome::Stable(_)));
+    }
+
+    #[test]
+    fn evolve_reuses_change_buffer() {
+        // Every seat fills in the first generation, so no later one needs more room
+        let mut layout = get_layout(false);
+        layout.evolve();
+        let buffer = layout.changes.as_ptr();
+        while layout.evolve() {}
+        assert_eq!(layout.changes.as_ptr(), buffer);
+        assert_eq!(layout.count_occupants(), 2361);
+    }
+
+    #[cfg(feature = "gpu")]
+    #[test]
+    fn gpu_outcomes_match_cpu() {
+        let sample = fs::read_to_string(fixture("sample.txt")).unwrap();
+        // Pairs of seats that fill together and then crowd each other out
+        let flashing = "LL.LL\n.....\nLL.LL\n";
+        for (text, threshold, max_generations) in [
+            (sample.as_str(), 4, None),
+            (sample.as_str(), 4, Some(2)),
+            (flashing, 1, None),
+            (flashing, 1, Some(1)),
+            (flashing, 1, Some(0)),
+        ] {
+            let mut layout = Layout::new();
+            for line in text.lines() {
+                layout.add_line(line).unwrap();
+            }
+            layout.finalize(Rule {
+                abandonment_threshold: threshold,
+                ..Rule::new(false)
+            });
+            layout.set_max_generations(max_generations);
+            assert_eq!(
+                solve(layout.clone(), Engine::Gpu).to_string(),
+                solve(layout, Engine::Cpu).to_string(),
+                "threshold {threshold} and limit {max_generations:?} on\n{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn running_count_and_hash_match_the_seats() {
+        let full_hash = |layout: &Layout| {
+            (0..layout.occupied_seats.len())
+                .filter(|index| layout.occupied_seats[*index])
+                .fold(0, |hash, index| hash ^ get_seat_key(index))
+        };
+        let mut layout = get_layout(true);
+        layout.set_noise(Some(Noise::new(0.3, 5)));
+        for _ in 0..20 {
+            layout.evolve();
+            let occupants = layout.occupied_seats.iter().filter(|seat| **seat).count();
+            assert_eq!(layout.count_occupants(), occupants);
+            assert_eq!(layout.state_hash, full_hash(&layout));
+        }
+    }
+
+    #[test]
+    fn tiled_matches_untiled() {
+        assert!(tiled::supports(&Rule::new(false)));
+        assert!(!tiled::supports(&Rule::new(true)));
+        let layout = tiled::TiledLayout::read(&fixture("input.txt"), 7).unwrap();
+        match tiled::settle(layout, Rule::new(false), 3, None, None) {
+            Outcome::Stable(occupants) => assert_eq!(occupants, 2361),
+            _ => panic!("Expected a stable layout"),
+        }
+    }
+
+    #[test]
+    fn tiled_rows_from_memory_match_the_file() {
+        let text = fs::read_to_string(fixture("input.txt")).unwrap();
+        let layout = tiled::TiledLayout::from_rows(text.lines(), 5).unwrap();
+        assert!(matches!(
+            tiled::settle(layout, Rule::new(false), 2, None, None),
+            Outcome::Stable(2361)
+        ));
+        assert!(matches!(
+            tiled::TiledLayout::from_rows(["L.L", "LL"], 1),
+            Err(LayoutError::WrongWidth { line: 2, .. })
+        ));
+        assert!(matches!(
+            tiled::TiledLayout::from_rows([], 1),
+            Err(LayoutError::EmptyInput)
+        ));
+    }
+
+    #[test]
+    fn gives_up_after_max_generations() {
+        let layout = get_layout(false);
+        let mut generations = 0;
+        layout
+            .clone()
+            .run_with_observer(|generation, _| generations = generation);
+
+        // The last generation changes nothing, so a limit of exactly the
+        // generations that do change is enough
+        let mut limited = layout.clone();
+        limited.set_max_generations(Some(generations));
+        assert!(matches!(limited.run(), Outcome::Stable(2361)));
+
+        let mut limited = layout;
+        limited.set_max_generations(Some(generations - 1));
+        let outcome = limited.run();
+        assert_eq!(
+            outcome.to_string(),
+            format!(
+                "Exceeded {} generations without converging",
+                generations - 1
+            )
+        );
+    }
+
+    #[test]
+    fn out_of_time_reports_progress() {
+        // A budget that's already spent stops before the first evolution
+        let mut layout = get_layout(false);
+        layout.set_time_budget(Some(Duration::ZERO));
+        let outcome = layout.run();
+        assert!(matches!(
+            outcome,
+            Outcome::OutOfTime {
+                generations: 0,
+                occupants: 0
+            }
+        ));
+        assert_eq!(
+            outcome.to_string(),
+            "Ran out of time after 0 generations with 0 seats occupied"
+        );
+
+        let mut layout = get_layout(false);
+        layout.set_time_budget(Some(Duration::from_secs(30)));
+        assert!(matches!(layout.run(), Outcome::Stable(2361)));
+    }
+
+    #[test]
+    fn frequency_covers_every_generation() {
+        let mut layout = Layout::new();
+        layout.add_line("L.L").unwrap();
+        layout.add_line("LLL").unwrap();
+        layout.finalize(Rule::new(false));
+
+        let mut frequency = OccupancyFrequency::new();
+        let mut generations = 0_u32;
+        layout.run_with_observer(|_, layout| {
+            frequency.observe(layout);
+            generations += 1;
+        });
+
+        let fractions = frequency.get_fractions();
+        assert_eq!(fractions.len(), 2);
+        assert_eq!(fractions[0][1], None);
+        // Every seat fills in the first generation, and the corners never empty
+        assert_eq!(
+            fractions[0][0],
+            Some(f64::from(generations - 1) / f64::from(generations))
+        );
+    }
+
+    #[test]
+    fn addresses_boards_past_u16() {
+        let mut layout = Layout::new();
+        for _ in 0..300 {
+            layout.add_line(&"L.".repeat(150)).unwrap();
+        }
+        layout.finalize(Rule::new(false));
+        // Seats only see the ones above and below them, so every seat fills and
+        // none of them ever empties
+        assert!(matches!(layout.run(), Outcome::Stable(45_000)));
+        assert_eq!(layout.to_grid().get(298, 299), Some(&'#'));
+    }
+
+    #[test]
+    fn rejects_malformed_rows() {
+        let mut layout = Layout::new();
+        layout.add_line("L.L").unwrap();
+        assert_eq!(
+            layout.add_line("L#x").unwrap_err().to_string(),
+            "line 2, column 3: unexpected character 'x', expected '.', 'L' or '#'"
+        );
+        assert_eq!(
+            layout.add_line("LL").unwrap_err(),
+            LayoutError::WrongWidth {
+                line: 2,
+                expected: 3,
+                found: 2
+            }
+        );
+        assert_eq!(layout.add_line(""), Err(LayoutError::EmptyLine(2)));
+    }
+
+    #[test]
+    fn reports_file_errors() {
+        let error = read_layout("missing.txt").map(|_| ()).unwrap_err();
+        assert_eq!(error.filename, "missing.txt");
+        assert!(matches!(error.error, LayoutError::Unreadable(_)));
+
+        let directory = std::env::temp_dir().join(format!("day-11-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let check = |name: &str, contents: &str| {
+            let path = directory.join(name);
+            fs::write(&path, contents).unwrap();
+            read_layout(path.to_str().unwrap()).map(|_| ())
+        };
+        assert_eq!(check("trailing.txt", "L.\n.L\n\n"), Ok(()));
+        assert_eq!(
+            check("gap.txt", "L.\n\n.L\n").unwrap_err().error,
+            LayoutError::EmptyLine(2)
+        );
+        assert_eq!(
+            check("empty.txt", "\n").unwrap_err().error,
+            LayoutError::EmptyInput
+        );
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn sweeps_every_combination() {
+        let sweep: sweep::Sweep = "threshold=3..5,los=on/off".parse().unwrap();
+        let rules = sweep.get_rules(&[Rule::new(false)]);
+        let parameters: Vec<_> = rules
+            .iter()
+            .map(|rule| (rule.radius, rule.abandonment_threshold))
+            .collect();
+        assert_eq!(
+            parameters,
+            [
+                (None, 3),
+                (None, 4),
+                (None, 5),
+                (Some(1), 3),
+                (Some(1), 4),
+                (Some(1), 5)
+            ]
+        );
+
+        // Without los, the swept parameters apply to the rules given
+        let sweep: sweep::Sweep = "radius=2/3".parse().unwrap();
+        let rules = sweep.get_rules(&[Rule::new(true)]);
+        assert_eq!(rules[1].radius, Some(3));
+        assert_eq!(rules[1].abandonment_threshold, 5);
+
+        for (spec, error) in [
+            (
+                "size=3",
+                "unknown parameter size, expected threshold, radius or los",
+            ),
+            ("threshold", "threshold has no values"),
+            ("threshold=5..3", "threshold range 5..3 is empty"),
+            ("los=maybe", "los can't be maybe"),
+            ("los=on,radius=1", "los and radius can't both be swept"),
+            ("radius=1,radius=2", "radius is given more than once"),
+            ("radius=0", "radius can't be 0"),
+            ("radius=0..2", "radius can't be 0"),
+        ] {
+            assert_eq!(spec.parse::<sweep::Sweep>().unwrap_err().to_string(), error);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Failed to finalize: radius can't be 0")]
+    fn zero_radius_is_rejected() {
+        let mut layout = read_layout(&fixture("sample.txt")).unwrap();
+        layout.finalize(Rule {
+            radius: Some(0),
+            ..Rule::new(true)
+        });
+    }
+
+    #[test]
+    fn sweep_counts_generations_to_settle() {
+        let layout = read_layout(&fixture("input.txt")).unwrap();
+        let sweep: sweep::Sweep = "threshold=4".parse().unwrap();
+        let settled: Vec<_> = sweep
+            .get_rules(&[Rule::new(false)])
+            .into_iter()
+            .map(|rule| {
+                let mut layout = layout.clone();
+                layout.finalize(rule);
+                sweep::settle(layout, rule)
+            })
+            .collect();
+        assert!(matches!(settled[0].outcome, Outcome::Stable(2361)));
+        assert_eq!(settled[0].generations, 125);
+
+        let table = sweep::to_table(&settled).to_string();
+        assert!(
+            table.lines().nth(2).unwrap().contains("stable"),
+            "{}",
+            table
+        );
+    }
+
+    fn get_neighbors(layout: &Layout, index: usize) -> Vec<usize> {
+        layout.adjacent_seats(index).copied().collect()
+    }
+
+    #[test]
+    fn hex_seats_have_six_neighbors() {
+        for row in 0..4 {
+            for column in 0..4 {
+                let cube = offset_to_cube(row, column);
+                assert_eq!(cube_to_offset(cube), Some((row, column)));
+            }
+        }
+
+        let mut layout = Layout::new();
+        for _ in 0..3 {
+            layout.add_line("LLL").unwrap();
+        }
+        layout.finalize(Rule {
+            topology: Topology::Hex,
+            ..Rule::new(false)
+        });
+        // Row 0 is even, so its corner only touches east and the seat below
+        assert_eq!(get_neighbors(&layout, 0), [1, 3]);
+        // Row 1 is odd and shifted right, so it leans on columns 1 and 2
+        assert_eq!(get_neighbors(&layout, 4), [5, 8, 7, 3, 1, 2]);
+    }
+
+    #[test]
+    fn sample_settles_on_both_topologies() {
+        let layout = read_layout(&fixture("sample.txt")).unwrap();
+        for (topology, line_of_sight, expected) in [
+            (Topology::Square, false, 37),
+            (Topology::Square, true, 26),
+            (Topology::Hex, false, 44),
+            (Topology::Hex, true, 39),
+        ] {
+            let mut layout = layout.clone();
+            layout.finalize(Rule {
+                topology,
+                ..Rule::new(line_of_sight)
+            });
+            assert!(
+                matches!(layout.run(), Outcome::Stable(occupants) if occupants == expected),
+                "{:?} with line of sight {}",
+                topology,
+                line_of_sight
+            );
+        }
+    }
+
+    #[test]
+    fn solver_answers_the_sample() {
+        let text = input::load(&fixture("sample.txt"));
+        assert_eq!(Solver.part1(&text), "37");
+        assert_eq!(Solver.part2(&text), "26");
+    }
+
+    #[test]
+    fn sample_rounds_snapshot() {
+        let mut layout = read_layout(&fixture("sample.txt")).unwrap();
+        layout.finalize(Rule::new(false));
+        let mut rounds = vec![layout.to_string()];
+        for _ in 0..2 {
+            layout.evolve();
+            rounds.push(layout.to_string());
+        }
+        insta::assert_snapshot!(rounds.join("\n"));
+    }
+
+    #[test]
+    fn sweep_table_snapshot() {
+        let layout = read_layout(&fixture("sample.txt")).unwrap();
+        let sweep: sweep::Sweep = "threshold=3..5,los=off/on".parse().unwrap();
+        let settled: Vec<_> = sweep
+            .get_rules(&[Rule::new(false)])
+            .into_iter()
+            .map(|rule| {
+                let mut layout = layout.clone();
+                layout.finalize(rule);
+                sweep::settle(layout, rule)
+            })
+            .collect();
+        insta::assert_snapshot!(sweep::to_table(&settled).to_string());
+    }
+
+    #[test]
+    fn final_map_round_trips() {
+        for line_of_sight in [false, true] {
+            let rule = Rule::new(line_of_sight);
+            let mut layout = read_layout(&fixture("input.txt")).unwrap();
+            layout.finalize(rule);
+            layout.run();
+            let map = layout.to_string();
+
+            // Reading the map back gives a layout that has already settled
+            let mut reread = Layout::new();
+            for line in map.lines() {
+                reread.add_line(line).unwrap();
+            }
+            reread.finalize(rule);
+            assert!(!reread.evolve());
+            assert_eq!(reread.to_string(), map);
+        }
+    }
+}
@@ -1,314 +1,756 @@
 #![deny(clippy::all, clippy::pedantic)]
-#![feature(test)]
 
 use std::{
-    convert::TryInto,
-    env,
-    fmt::{Display, Formatter},
-    fs::File,
-    io::{BufRead, BufReader},
+    convert::TryFrom,
+    fs::{self, File},
+    io::Write,
+    iter, mem,
+    path::{Path, PathBuf},
+    process,
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::Duration,
 };
 
-extern crate test;
+use clap::{crate_name, value_t, App, AppSettings, Arg, ArgMatches, SubCommand};
+use common::{
+    alloc,
+    cli::{self, Part},
+    golly, input,
+    report::MarkdownTable,
+    timing,
+    vis::{
+        ansi::{self, Role, Theme},
+        sparkline, svg,
+        term::{self, Render},
+        FrameWriter, Rgb,
+    },
+};
+use day_11::{
+    read_layout, settle_puzzle, solve,
+    sweep::{self, Sweep},
+    tiled::{self, TiledLayout},
+    Engine, InputError, Layout, Noise, OccupancyFrequency, Outcome, Rule, Topology,
+};
 
-#[derive(Clone, Copy)]
-enum Cell {
-    Floor,
-    Empty,
-    Occupied,
+// Options that follow a single layout generation by generation
+const TRACED_OPTIONS: [&str; 9] = [
+    "csv",
+    "emit-final-map",
+    "emit-rle",
+    "explain",
+    "frames",
+    "frequency",
+    "render",
+    "stats",
+    "svg",
+];
+
+// Layout keeps, for every cell, up to eight usize indices of the seats it can
+// see plus an offset into them, and for every seat a candidate index and a
+// few flags, where the tiled solver gets by on two bytes per cell. Counting
+// each byte of input as a cell, anything that would take Layout past
+// LAYOUT_MEMORY_BUDGET goes to the tiled solver instead.
+const LAYOUT_BYTES_PER_CELL: u64 = 10 * mem::size_of::<usize>() as u64 + 3;
+const LAYOUT_MEMORY_BUDGET: u64 = 1 << 30;
+const TILED_THRESHOLD_BYTES: u64 = LAYOUT_MEMORY_BUDGET / LAYOUT_BYTES_PER_CELL;
+
+fn write_frame(directory: &str, generation: usize, layout: &Layout) {
+    let filename = Path::new(directory).join(format!("gen_{generation:04}.txt"));
+    fs::write(&filename, layout.to_string())
+        .unwrap_or_else(|_| panic!("Failed to write frame {}", filename.display()));
 }
 
-#[derive(Clone)]
-struct Layout {
-    line_of_sight: bool,
-    map: Vec<Cell>,
-    column_count: i32,
-    row_count: i32,
-    adjacent_indices: Vec<u16>,
-    updated_indices: Vec<u16>,
-    occupied_seats: Vec<bool>,
+fn get_seat_color(cell: char) -> Option<Rgb> {
+    match cell {
+        'L' => Some(Rgb(0x9e, 0xc5, 0xe8)),
+        '#' => Some(Rgb(0x1f, 0x4e, 0x79)),
+        _ => None,
+    }
 }
 
-impl Layout {
-    fn new(line_of_sight: bool) -> Self {
-        Self {
-            line_of_sight,
-            map: Vec::new(),
-            column_count: -1,
-            row_count: 0,
-            adjacent_indices: Vec::new(),
-            updated_indices: Vec::new(),
-            occupied_seats: Vec::new(),
+fn print_layout(layout: &Layout, render: Render, theme: Option<&str>) {
+    let grid = layout.to_grid();
+    let theme = theme.map(|name| Theme::from_name(name).unwrap());
+    match (render, theme) {
+        (Render::Text, Some(theme)) => print!(
+            "{}",
+            ansi::render(&grid, &theme, |cell| match cell {
+                '#' => ('#', Role::On),
+                'L' => ('L', Role::Off),
+                _ => (*cell, Role::Fixed),
+            })
+        ),
+        (Render::Braille, Some(theme)) => {
+            let braille = render.render(&grid, |cell| *cell, |cell| *cell == '#');
+            for line in braille.lines() {
+                println!("{}", theme.paint(Role::On, line));
+            }
         }
+        (_, None) => print!(
+            "{}",
+            render.render(&grid, |cell| *cell, |cell| *cell == '#')
+        ),
     }
+}
 
-    fn add_line(&mut self, line: &str) {
-        for byte in line.as_bytes() {
-            self.map.push(match byte {
-                b'.' => Cell::Floor,
-                b'L' => Cell::Empty,
-                b'#' => Cell::Occupied,
-                _ => panic!("Unexpected byte [{}]", byte),
-            })
-        }
+// The same characters and rows as the input, so the map can be read back in
+fn write_final_map(filename: &str, layout: &Layout) {
+    fs::write(filename, layout.to_string())
+        .unwrap_or_else(|_| panic!("Failed to write file {}", filename));
+}
 
-        let incoming_column_count: i32 = line
-            .len()
-            .try_into()
-            .expect("Couldn't store column count in i32");
-        if self.column_count < 0 {
-            self.column_count = incoming_column_count;
-        } else if incoming_column_count != self.column_count {
-            panic!(
-                "Incoming column count {} different from stored column count {}",
-                incoming_column_count, self.column_count
-            );
-        }
+// Floor is dead, and empty and occupied seats are Golly's states 1 and 2. There
+// is no Golly rule for seating, so the pattern doesn't name one.
+fn write_rle(filename: &str, layout: &Layout) {
+    let pattern = golly::encode(&layout.to_grid(), None, |cell| match cell {
+        'L' => 1,
+        '#' => 2,
+        _ => 0,
+    });
+    fs::write(filename, pattern).unwrap_or_else(|_| panic!("Failed to write file {}", filename));
+}
 
-        self.row_count += 1;
-    }
+fn write_svg(filename: &str, layout: &Layout) {
+    let svg = svg::render_grid(&layout.to_grid(), 800, |cell| get_seat_color(*cell));
+    fs::write(filename, svg).unwrap_or_else(|_| panic!("Failed to write file {}", filename));
+}
 
-    fn get_index(&self, row: i32, column: i32) -> u16 {
-        (row * self.column_count + column)
-            .try_into()
-            .expect("Failed to store address in u16")
-    }
+fn get_batch_filenames(directory: &str) -> Vec<PathBuf> {
+    let mut filenames: Vec<PathBuf> = fs::read_dir(directory)
+        .unwrap_or_else(|_| panic!("Failed to read directory {}", directory))
+        .map(|entry| entry.expect("Failed to read directory entry").path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "txt"))
+        .collect();
+    filenames.sort();
+    filenames
+}
 
-    fn get_adjacent_seat_index(
-        &self,
-        mut row: i32,
-        mut column: i32,
-        delta_x: i32,
-        delta_y: i32,
-    ) -> Option<u16> {
-        loop {
-            row += delta_y;
-            column += delta_x;
-
-            if row < 0 || row >= self.row_count {
-                return None;
-            }
-            if column < 0 || column >= self.column_count {
-                return None;
-            }
+// Arguments without a default are only parsed when given, and like the rest
+// exit with a usage error when they don't parse
+fn optional_value<T: FromStr>(args: &ArgMatches, name: &str) -> Option<T> {
+    args.is_present(name)
+        .then(|| value_t!(args, name, T).unwrap_or_else(|error| error.exit()))
+}
 
-            let index = self.get_index(row, column);
-            match self
-                .map
-                .get(index as usize)
-                .unwrap_or_else(|| panic!("Index {} not found in map", index))
-            {
-                Cell::Floor => (),
-                Cell::Empty | Cell::Occupied => return Some(index),
-            }
+struct Options {
+    rules: Vec<(&'static str, Rule)>,
+    noise: Option<(f64, u64)>,
+    max_generations: usize,
+    time_budget: Option<Duration>,
+    engine: Engine,
+    tiled: bool,
+    tile_jobs: usize,
+}
 
-            if !self.line_of_sight {
-                return None;
+impl Options {
+    fn from_args(subcommand: &str, args: &ArgMatches) -> Self {
+        let mut rules = match subcommand {
+            "adjacent" => vec![("adjacent", Rule::new(false))],
+            "los" => vec![("los", Rule::new(true))],
+            "both" => vec![("adjacent", Rule::new(false)), ("los", Rule::new(true))],
+            _ => unreachable!("Impossible subcommand"),
+        };
+        let radius = optional_value(args, "radius");
+        // Leaving the radius out is how to look as far as the next seat, since a
+        // ray always takes at least one step and would never stop at 0
+        if radius == Some(0) {
+            clap::Error::with_description(
+                "--radius has to be at least 1",
+                clap::ErrorKind::InvalidValue,
+            )
+            .exit();
+        }
+        let threshold = optional_value(args, "threshold");
+        let topology = value_t!(args, "topology", Topology).unwrap_or_else(|error| error.exit());
+        for (_, rule) in &mut rules {
+            if radius.is_some() {
+                rule.radius = radius;
+            }
+            if let Some(threshold) = threshold {
+                rule.abandonment_threshold = threshold;
             }
+            rule.topology = topology;
         }
-    }
 
-    fn get_adjacent_indices(&self, row: i32, column: i32) -> Vec<u16> {
-        let mut indices = Vec::new();
+        let noise = optional_value(args, "noise").map(|probability| {
+            (
+                probability,
+                value_t!(args, "seed", u64).unwrap_or_else(|error| error.exit()),
+            )
+        });
 
-        for delta_y in -1..=1 {
-            for delta_x in -1..=1 {
-                if delta_x == 0 && delta_y == 0 {
-                    continue;
-                }
+        let time_budget = args.value_of("time-budget").map(|budget| {
+            timing::parse_duration(budget).unwrap_or_else(|| {
+                clap::Error::with_description(
+                    &format!(
+                        "--time-budget {budget} isn't a number followed by ns, us, ms, s or m"
+                    ),
+                    clap::ErrorKind::InvalidValue,
+                )
+                .exit()
+            })
+        });
 
-                if let Some(index) = self.get_adjacent_seat_index(row, column, delta_x, delta_y) {
-                    indices.push(index);
-                }
-            }
+        Self {
+            rules,
+            noise,
+            max_generations: value_t!(args, "max-generations", usize)
+                .unwrap_or_else(|error| error.exit()),
+            time_budget,
+            engine: value_t!(args, "engine", Engine).unwrap_or_else(|error| error.exit()),
+            tiled: args.is_present("tiled"),
+            tile_jobs: value_t!(args, "jobs", usize).unwrap_or_else(|error| error.exit()),
         }
-
-        indices
     }
 
-    fn finalize(&mut self) {
-        for row in 0..self.row_count {
-            for column in 0..self.column_count {
-                let index = self.get_index(row, column);
-                if let Cell::Floor = self.map[index as usize] {
-                    self.adjacent_indices.append(&mut vec![u16::max_value(); 8]);
-                    continue;
-                }
-
-                let mut adjacent_indices = self.get_adjacent_indices(row, column);
-                adjacent_indices.resize(8, u16::max_value());
-                self.adjacent_indices.append(&mut adjacent_indices);
-                self.updated_indices.push(index);
-            }
+    fn prepare(&self, layout: &Layout, rule: Rule) -> Layout {
+        let mut layout = layout.clone();
+        layout.finalize(rule);
+        if let Some((probability, seed)) = self.noise {
+            layout.set_noise(Some(Noise::new(probability, seed)));
         }
-        self.occupied_seats
-            .resize(self.adjacent_indices.len() / 8, false);
+        layout.set_max_generations(Some(self.max_generations));
+        layout.set_time_budget(self.time_budget);
+        layout
     }
 
-    fn count_adjacent_occupants(&self, index: u16) -> i32 {
-        let mut count = 0;
-        for adjacent_index in
-            &self.adjacent_indices[((index as usize) * 8)..((index as usize) * 8 + 8)]
-        {
-            if *adjacent_index == u16::max_value() {
-                break;
-            }
+    // Solves every requested variant from the same parsed layout
+    fn solve(&self, layout: &Layout) -> Vec<Outcome> {
+        self.rules
+            .iter()
+            .map(|(_, rule)| solve(self.prepare(layout, *rule), self.engine))
+            .collect()
+    }
 
-            if self.occupied_seats[*adjacent_index as usize] {
-                count += 1;
-            }
-        }
-        count
+    // Runs on the CPU, one combination after another, since every combination
+    // needs its generation count
+    fn sweep(&self, layout: &Layout, sweep: &Sweep) -> MarkdownTable {
+        assert_eq!(
+            self.engine,
+            Engine::Cpu,
+            "Sweeps only support the CPU engine"
+        );
+        let base: Vec<Rule> = self.rules.iter().map(|(_, rule)| *rule).collect();
+        let results: Vec<_> = sweep
+            .get_rules(&base)
+            .into_iter()
+            .map(|rule| sweep::settle(self.prepare(layout, rule), rule))
+            .collect();
+        sweep::to_table(&results)
     }
 
-    fn collect_changes(&self) -> Vec<u16> {
-        let mut changes = Vec::new();
+    // The tiled solver runs on the CPU without noise, and only follows rules
+    // that look one seat away on a square grid
+    fn can_tile(&self) -> bool {
+        self.noise.is_none()
+            && self.engine == Engine::Cpu
+            && self.rules.iter().all(|(_, rule)| tiled::supports(rule))
+    }
 
-        let abandonment_threshold = if self.line_of_sight { 5 } else { 4 };
+    // Large layouts are only tiled automatically when the tiled solver can
+    // handle them. Anything else stays a Layout, however big.
+    fn should_tile(&self, filename: &str) -> bool {
+        self.tiled
+            || self.can_tile()
+                && input::size(filename)
+                    .unwrap_or_else(|_| panic!("Failed to read metadata for {}", filename))
+                    > TILED_THRESHOLD_BYTES
+    }
 
-        for index in &self.updated_indices {
-            if self.occupied_seats[*index as usize] {
-                if self.count_adjacent_occupants(*index) >= abandonment_threshold {
-                    changes.push(*index);
-                }
-            } else if self.count_adjacent_occupants(*index) == 0 {
-                changes.push(*index);
-            }
+    fn solve_file(&self, filename: &str) -> Result<Vec<Outcome>, InputError> {
+        if !self.should_tile(filename) {
+            return Ok(self.solve(&read_layout(filename)?));
         }
 
-        changes
+        let layout = TiledLayout::read(filename, tiled::TILE_ROWS)?;
+        Ok(self
+            .rules
+            .iter()
+            .map(|(_, rule)| {
+                tiled::settle(
+                    layout.clone(),
+                    *rule,
+                    self.tile_jobs,
+                    Some(self.max_generations),
+                    self.time_budget,
+                )
+            })
+            .collect())
     }
 
-    fn apply_changes(&mut self, changes: Vec<u16>) {
-        for change in &changes {
-            self.occupied_seats[*change as usize] ^= true;
+    // Layouts that didn't converge go to stderr, and make this return false
+    fn print(&self, prefix: &str, outcomes: &[Outcome]) -> bool {
+        let mut converged = true;
+        for ((name, _), outcome) in self.rules.iter().zip(outcomes) {
+            let label = match (prefix.is_empty(), self.rules.len()) {
+                (true, 1) => String::new(),
+                (true, _) => format!("{name}: "),
+                (false, 1) => format!("{prefix}: "),
+                (false, _) => format!("{prefix} ({name}): "),
+            };
+            if let Outcome::Diverged { .. } | Outcome::OutOfTime { .. } = outcome {
+                eprintln!("{label}{outcome}");
+                converged = false;
+            } else {
+                println!("{label}{outcome}");
+            }
         }
-        self.updated_indices = changes;
+        converged
     }
+}
 
-    fn evolve(&mut self) -> bool {
-        let changes = self.collect_changes();
-        if changes.is_empty() {
-            return false;
-        }
+type Solved = Result<Vec<Outcome>, InputError>;
 
-        self.apply_changes(changes);
-        true
-    }
+// Results line up with filenames, which get_batch_filenames sorts, no matter
+// which worker solved each one or when it finished
+fn solve_batch(filenames: &[PathBuf], options: &Options, jobs: usize) -> Vec<Solved> {
+    let solve = |filename: &PathBuf| {
+        let filename = filename.to_str().expect("Batch path is not valid UTF-8");
+        options.solve_file(filename)
+    };
 
-    fn count_occupants(&self) -> i32 {
-        self.occupied_seats
-            .iter()
-            .map(|occupied| if *occupied { 1 } else { 0 })
-            .sum()
+    if jobs <= 1 {
+        return filenames.iter().map(solve).collect();
     }
-}
 
-impl Display for Layout {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        for row in 0..self.row_count {
-            for column in 0..self.column_count {
-                let index = self.get_index(row, column);
-                write!(
-                    f,
-                    "{}",
-                    match self
-                        .map
-                        .get(index as usize)
-                        .unwrap_or_else(|| panic!("Index {} not found in map", index))
-                    {
-                        Cell::Floor => '.',
-                        Cell::Empty => 'L',
-                        Cell::Occupied => '#',
+    let next_file = AtomicUsize::new(0);
+    let mut results: Vec<Option<Solved>> = (0..filenames.len()).map(|_| None).collect();
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut solved = Vec::new();
+                    loop {
+                        let index = next_file.fetch_add(1, Ordering::Relaxed);
+                        if index >= filenames.len() {
+                            return solved;
+                        }
+                        solved.push((index, solve(&filenames[index])));
                     }
-                )?;
+                })
+            })
+            .collect();
+
+        for worker in workers {
+            for (index, outcomes) in worker.join().expect("Batch worker panicked") {
+                results[index] = Some(outcomes);
             }
-            writeln!(f)?;
         }
-
-        Ok(())
-    }
+    });
+    results
+        .into_iter()
+        .map(|outcomes| outcomes.expect("Batch layout was not solved"))
+        .collect()
 }
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
-    if args.len() < 2 || args.len() > 3 {
-        return;
+fn write_traced(layout: &Layout, options: &Options, args: &ArgMatches) -> Outcome {
+    assert_eq!(
+        options.rules.len(),
+        1,
+        "--csv, --emit-final-map, --emit-rle, --explain, --frames, --frequency, --render, --stats and --svg need a single variant"
+    );
+    let mut layout = options.prepare(layout, options.rules[0].1);
+    let explain = args.is_present("explain");
+    if explain {
+        explain_rule(&options.rules[0].1);
     }
 
-    let line_of_sight = args.len() == 3 && args[2] == "los";
+    let mut csv = args.value_of("csv").map(|filename| {
+        let mut file =
+            File::create(filename).unwrap_or_else(|_| panic!("Failed to create file {}", filename));
+        writeln!(file, "generation,occupied").expect("Failed to write CSV header");
+        file
+    });
+
+    let frames = args.value_of("frames");
+    let mut png_frames = None;
+    if let Some(directory) = frames {
+        if args.value_of("frame-format") == Some("png") {
+            png_frames = Some(FrameWriter::new(directory, 4));
+        } else {
+            fs::create_dir_all(directory)
+                .unwrap_or_else(|_| panic!("Failed to create directory {}", directory));
+        }
+    }
 
-    let filename = &args[1];
-    let file = File::open(filename).unwrap_or_else(|_| panic!("Failed to open file {}", filename));
-    let mut reader = BufReader::new(file);
+    let mut frequency = OccupancyFrequency::new();
+    let mut occupancy = Vec::new();
+    let outcome = layout.run_with_observer(|generation, layout| {
+        if let Some(file) = &mut csv {
+            writeln!(file, "{},{}", generation, layout.count_occupants())
+                .expect("Failed to write CSV row");
+        }
+        if let Some(writer) = &mut png_frames {
+            writer.write(&layout.to_grid(), |cell| {
+                get_seat_color(*cell).unwrap_or(Rgb::WHITE)
+            });
+        } else if let Some(directory) = frames {
+            write_frame(directory, generation, layout);
+        }
+        if explain {
+            explain_round(generation, layout);
+        }
+        frequency.observe(layout);
+        occupancy.push(
+            u32::try_from(layout.count_occupants()).expect("Failed to store occupant count in u32"),
+        );
+    });
+
+    if args.is_present("stats") {
+        eprintln!(
+            "Occupancy over {} generations: {} ({} to {})",
+            occupancy.len(),
+            sparkline(&occupancy),
+            occupancy.iter().min().unwrap_or(&0),
+            occupancy.iter().max().unwrap_or(&0)
+        );
+    }
 
-    let mut layout = Layout::new(line_of_sight);
+    if let Some(writer) = png_frames {
+        writer.finish();
+    }
+    if let Some(filename) = args.value_of("frequency") {
+        write_frequency(filename, &frequency);
+    }
+    if let Some(filename) = args.value_of("emit-final-map") {
+        write_final_map(filename, &layout);
+    }
+    if let Some(filename) = args.value_of("emit-rle") {
+        write_rle(filename, &layout);
+    }
+    if let Some(filename) = args.value_of("svg") {
+        write_svg(filename, &layout);
+    }
+    if let Some(render) = args.value_of("render") {
+        print_layout(
+            &layout,
+            Render::from_name(render).unwrap(),
+            args.value_of("theme"),
+        );
+    }
+    outcome
+}
 
-    let mut line = String::new();
-    loop {
-        let bytes = reader
-            .read_line(&mut line)
-            .unwrap_or_else(|_| panic!("Failed to read line"));
-        if bytes == 0 {
-            break;
+// How many rounds --explain draws before it only counts occupied seats
+const EXPLAIN_ROUNDS: usize = 3;
+
+fn explain_rule(rule: &Rule) {
+    let count = match rule.topology {
+        Topology::Square => "eight",
+        Topology::Hex => "six",
+    };
+    let neighbors = match rule.radius {
+        Some(1) => format!("the {count} seats around it"),
+        Some(radius) => {
+            format!("the first seat within {radius} places in each of the {count} directions")
         }
+        None => format!("the first seat it can see in each of the {count} directions"),
+    };
+    println!("Each round, every seat looks at {neighbors}.");
+    println!(
+        "An empty seat with none of those occupied becomes occupied, and an occupied seat with {} or more occupied becomes empty.",
+        rule.abandonment_threshold
+    );
+}
 
-        layout.add_line(line.trim());
-
-        line.clear();
+// Draws the layout the way the puzzle's example does for the first few rounds,
+// then just counts
+fn explain_round(round: usize, layout: &Layout) {
+    let occupants = layout.count_occupants();
+    match round {
+        0 => println!("\nInitially {occupants} seats are occupied:"),
+        EXPLAIN_ROUNDS.. => {
+            println!("After round {round}, {occupants} seats are occupied");
+            return;
+        }
+        _ => println!("\nAfter round {round}, {occupants} seats are occupied:"),
     }
+    print!("{}", term::text(&layout.to_grid(), |cell| *cell));
+    if round + 1 == EXPLAIN_ROUNDS {
+        println!();
+    }
+}
 
-    layout.finalize();
-
-    while layout.evolve() {}
-    println!("Occupied seats: {}", layout.count_occupants());
+fn write_frequency(filename: &str, frequency: &OccupancyFrequency) {
+    let mut file =
+        File::create(filename).unwrap_or_else(|_| panic!("Failed to create file {}", filename));
+    writeln!(file, "row,column,fraction").expect("Failed to write CSV header");
+    for (row, fractions) in frequency.get_fractions().iter().enumerate() {
+        for (column, fraction) in fractions.iter().enumerate() {
+            if let Some(fraction) = fraction {
+                writeln!(file, "{row},{column},{fraction:.4}").expect("Failed to write CSV row");
+            }
+        }
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use test::Bencher;
+fn layout_subcommand<'a, 'b>(name: &'a str, about: &'b str) -> App<'a, 'b> {
+    SubCommand::with_name(name)
+        .about(about)
+        .arg(
+            Arg::from_usage("[FILE]... 'Layouts to solve, globs such as inputs/*.txt, or - for stdin'")
+                .required(!cfg!(feature = "embed-input")),
+        )
+        .arg(Arg::from_usage(
+            "-r, --radius [RADIUS] 'Maximum number of steps to look for a visible seat'",
+        ))
+        .arg(Arg::from_usage(
+            "-t, --threshold [THRESHOLD] 'Visible occupant count that makes a seat empty'",
+        ))
+        .arg(
+            Arg::from_usage(
+                "--topology [TOPOLOGY] 'Square grid, or hex with odd rows shifted half a seat right'",
+            )
+            .possible_values(&Topology::NAMES)
+            .default_value("square"),
+        )
+        .arg(Arg::from_usage(
+            "-n, --noise [PROBABILITY] 'Probability that a mandated flip is skipped'",
+        ))
+        .arg(Arg::from_usage("-s, --seed [SEED] 'Seed for the noise generator'").default_value("0"))
+        .arg(
+            Arg::from_usage(
+                "--max-generations [N] 'Give up if the layout is still changing after N generations'",
+            )
+            .default_value("100000"),
+        )
+        .arg(Arg::from_usage(
+            "--time-budget [DURATION] 'Give up on each variant after this long, such as 500ms or 2s, reporting how far it got'",
+        ))
+        .arg(Arg::from_usage(
+            "--csv [CSV] 'Write occupancy per generation to a CSV file'",
+        ))
+        .arg(Arg::from_usage(
+            "--emit-final-map [MAP] 'Write the final layout in the input format, so it can be read back in'",
+        ))
+        .arg(Arg::from_usage(
+            "--emit-rle [RLE] 'Write the final layout as a Golly pattern'",
+        ))
+        .arg(Arg::from_usage(
+            "--explain 'Describe the rule and show how the first few rounds play out'",
+        ))
+        .arg(Arg::from_usage(
+            "--frames [DIR] 'Write each generation to DIR as a numbered frame'",
+        ))
+        .arg(
+            Arg::from_usage(
+                "--frame-format [FORMAT] 'PNG frames for ffmpeg, or the layout as text'",
+            )
+            .possible_values(&["png", "txt"])
+            .default_value("png"),
+        )
+        .arg(Arg::from_usage(
+            "--frequency [CSV] 'Write the fraction of generations each seat was occupied'",
+        ))
+        .arg(
+            Arg::from_usage(
+                "--render [MODE] 'Print the final layout, with braille showing occupied seats only'",
+            )
+            .possible_values(&Render::NAMES),
+        )
+        .arg(
+            Arg::from_usage("--theme [THEME] 'Color the rendered layout'")
+                .possible_values(&Theme::NAMES)
+                .requires("render"),
+        )
+        .arg(Arg::from_usage(
+            "--stats 'Plot occupancy per generation to stderr as a sparkline'",
+        ))
+        .arg(Arg::from_usage(
+            "--svg [SVG] 'Draw the final layout as an SVG image'",
+        ))
+        .arg(
+            Arg::from_usage("-e, --engine [ENGINE] 'Simulation engine'")
+                .possible_values(&Engine::NAMES)
+                .default_value("cpu"),
+        )
+        .arg(Arg::from_usage(
+            "-b, --batch 'Treat FILE as a directory and solve every *.txt layout in it'",
+        ))
+        .arg(
+            Arg::from_usage("-j, --jobs [JOBS] 'Number of layouts or tiles to solve in parallel'")
+                .default_value("1"),
+        )
+        .arg(Arg::from_usage(
+            "--tiled 'Process the layout in bands of rows (automatic for large layouts)'",
+        ))
+        .arg(
+            Arg::from_usage(
+                "--sweep [SPEC] 'Solve every combination of rule parameters, such as threshold=3..7,los=on/off, and tabulate the results'",
+            )
+            .conflicts_with("batch"),
+        )
+        .arg(Arg::from_usage(
+            "--timing 'Write a breakdown of where the time went to stderr'",
+        ))
+        .arg(Arg::from_usage(
+            "--trace-out [FILE] 'Write a Chrome trace of parsing and each generation to FILE'",
+        ))
+}
 
-    fn get_layout(line_of_sight: bool) -> Layout {
-        let file = File::open("input.txt").expect("Failed to open input.txt");
-        let mut reader = BufReader::new(file);
+// Malformed input is the user's mistake rather than a bug, so it gets a
+// message instead of a panic
+fn exit_with(error: &InputError) -> ! {
+    eprintln!("{error}");
+    process::exit(1);
+}
 
-        let mut layout = Layout::new(line_of_sight);
+// Combinations the solvers can't handle are the user's mistake, so they get a
+// usage error instead of reaching an assert
+fn check_usage(options: &Options, args: &ArgMatches) {
+    if options.engine != Engine::Cpu && (options.noise.is_some() || args.is_present("sweep")) {
+        clap::Error::with_description(
+            "--noise and --sweep only support the CPU engine",
+            clap::ErrorKind::ArgumentConflict,
+        )
+        .exit();
+    }
+    if options.tiled && !options.can_tile() {
+        clap::Error::with_description(
+            "--tiled only supports a radius of 1 on a square grid, on the CPU engine without --noise",
+            clap::ErrorKind::ArgumentConflict,
+        )
+        .exit();
+    }
+}
 
-        let mut line = String::new();
-        loop {
-            let bytes = reader
-                .read_line(&mut line)
-                .unwrap_or_else(|_| panic!("Failed to read line"));
-            if bytes == 0 {
-                break;
+// Returns false if anything failed to converge
+fn solve_files(filenames: &[String], options: &mut Options, args: &ArgMatches) -> bool {
+    let batch: Option<Vec<PathBuf>> = if args.is_present("batch") {
+        Some(
+            filenames
+                .iter()
+                .flat_map(|directory| get_batch_filenames(directory))
+                .collect(),
+        )
+    } else if filenames.len() > 1 {
+        Some(filenames.iter().map(PathBuf::from).collect())
+    } else {
+        None
+    };
+    if let Some(filenames) = batch {
+        if let Some(name) = iter::once(&"sweep")
+            .chain(&TRACED_OPTIONS)
+            .find(|name| args.is_present(name))
+        {
+            eprintln!("--{name} needs a single layout");
+            process::exit(1);
+        }
+        // Batches already run one layout per job
+        options.tile_jobs = 1;
+        let jobs = value_t!(args, "jobs", usize).unwrap_or_else(|error| error.exit());
+        let results = solve_batch(&filenames, options, jobs);
+        let mut failed = false;
+        for (filename, outcomes) in filenames.iter().zip(results) {
+            match outcomes {
+                Ok(outcomes) => {
+                    failed |= !options.print(&filename.display().to_string(), &outcomes);
+                }
+                Err(error) => {
+                    eprintln!("{error}");
+                    failed = true;
+                }
             }
-            layout.add_line(line.trim());
-            line.clear();
         }
+        return !failed;
+    }
 
-        layout.finalize();
+    let filename = filenames[0].as_str();
+    if let Some(spec) = args.value_of("sweep") {
+        let sweep = spec.parse().unwrap_or_else(|error| {
+            clap::Error::with_description(
+                &format!("Invalid sweep {spec}: {error}"),
+                clap::ErrorKind::InvalidValue,
+            )
+            .exit()
+        });
+        let layout = read_layout(filename).unwrap_or_else(|error| exit_with(&error));
+        print!("{}", options.sweep(&layout, &sweep));
+        return true;
+    }
 
-        layout
+    if TRACED_OPTIONS.iter().any(|name| args.is_present(name)) {
+        let layout = read_layout(filename).unwrap_or_else(|error| exit_with(&error));
+        let outcome = write_traced(&layout, options, args);
+        options.print("", &[outcome])
+    } else {
+        let outcomes = options
+            .solve_file(filename)
+            .unwrap_or_else(|error| exit_with(&error));
+        options.print("", &outcomes)
     }
+}
 
-    #[bench]
-    fn bench_adjacent(bencher: &mut Bencher) {
-        let layout = get_layout(false);
-        bencher.iter(|| {
-            let mut cloned = layout.clone();
-            while cloned.evolve() {}
-            assert_eq!(cloned.count_occupants(), 2361);
-        });
+// Without a subcommand, the puzzle's two parts are answered like any other day
+fn answer_parts(args: &ArgMatches) {
+    let filename = common::resolve_input!(args.value_of("FILE"));
+    let part = value_t!(args, "part", Part).unwrap_or_else(|error| error.exit());
+    cli::run_parts(
+        part,
+        || read_layout(filename),
+        |layout| settle_puzzle(layout.clone(), false),
+        |layout| settle_puzzle(layout.clone(), true),
+    );
+    if args.is_present("timing") {
+        eprintln!("Allocator: {}", alloc::NAME);
+        eprint!("{}", timing::report());
     }
+}
 
-    #[bench]
-    fn bench_line_of_sight(bencher: &mut Bencher) {
-        let layout = get_layout(true);
-        bencher.iter(|| {
-            let mut cloned = layout.clone();
-            while cloned.evolve() {}
-            assert_eq!(cloned.count_occupants(), 2119);
-        });
+fn main() {
+    let matches = App::new(crate_name!())
+        .setting(AppSettings::ArgsNegateSubcommands)
+        .setting(AppSettings::SubcommandsNegateReqs)
+        .arg(
+            Arg::from_usage("[FILE] 'Layout to answer the puzzle for, or - for stdin'")
+                .required(!cfg!(feature = "embed-input")),
+        )
+        .arg(
+            Arg::from_usage("--part [PART] 'Which part to answer'")
+                .possible_values(&Part::NAMES)
+                .default_value("both"),
+        )
+        .arg(Arg::from_usage(
+            "--timing 'Write a breakdown of where the time went to stderr'",
+        ))
+        .subcommand(layout_subcommand(
+            "adjacent",
+            "Seats consider their immediate neighbors",
+        ))
+        .subcommand(layout_subcommand(
+            "los",
+            "Seats consider the first seat along each line of sight",
+        ))
+        .subcommand(layout_subcommand(
+            "both",
+            "Parse the layout once and solve both variants",
+        ))
+        .get_matches();
+
+    let (subcommand, args) = matches.subcommand();
+    let Some(args) = args else {
+        answer_parts(&matches);
+        return;
+    };
+    let _trace = args.value_of("trace-out").map(timing::trace_to);
+    let mut options = Options::from_args(subcommand, args);
+    check_usage(&options, args);
+    let filenames = match args.values_of("FILE") {
+        Some(paths) => input::expand_globs(paths).unwrap_or_else(|error| {
+            eprintln!("{error}");
+            process::exit(1);
+        }),
+        None => vec![String::from(common::resolve_input!(None::<&str>))],
+    };
+
+    let converged = solve_files(&filenames, &mut options, args);
+    if args.is_present("timing") {
+        eprintln!("Allocator: {}", alloc::NAME);
+        eprint!("{}", timing::report());
+    }
+    if !converged {
+        process::exit(1);
     }
 }
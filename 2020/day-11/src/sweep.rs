@@ -0,0 +1,245 @@
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+use common::report::{Align, MarkdownTable};
+
+use super::{Layout, Outcome, Rule};
+
+// The rule parameters to try, written like threshold=3..7,los=on/off: each
+// parameter takes either a range, which includes both ends, or values
+// separated by slashes. Parameters that aren't given keep the rule they start
+// from.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Sweep {
+    line_of_sight: Option<Vec<bool>>,
+    radii: Option<Vec<usize>>,
+    thresholds: Option<Vec<usize>>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SweepError {
+    UnknownParameter(String),
+    MissingValues(String),
+    BadValue { parameter: String, value: String },
+    EmptyRange { parameter: String, range: String },
+    Repeated(String),
+    Conflict,
+}
+
+impl Display for SweepError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownParameter(parameter) => write!(
+                f,
+                "unknown parameter {parameter}, expected threshold, radius or los"
+            ),
+            Self::MissingValues(parameter) => write!(f, "{parameter} has no values"),
+            Self::BadValue { parameter, value } => {
+                write!(f, "{parameter} can't be {value}")
+            }
+            Self::EmptyRange { parameter, range } => {
+                write!(f, "{parameter} range {range} is empty")
+            }
+            Self::Repeated(parameter) => write!(f, "{parameter} is given more than once"),
+            Self::Conflict => write!(f, "los and radius can't both be swept"),
+        }
+    }
+}
+
+impl Error for SweepError {}
+
+fn parse_counts(parameter: &str, values: &str) -> Result<Vec<usize>, SweepError> {
+    let parse = |value: &str| {
+        value.parse().map_err(|_| SweepError::BadValue {
+            parameter: String::from(parameter),
+            value: String::from(value),
+        })
+    };
+
+    if let Some((start, end)) = values.split_once("..") {
+        let (start, end): (usize, usize) = (parse(start)?, parse(end)?);
+        if start > end {
+            return Err(SweepError::EmptyRange {
+                parameter: String::from(parameter),
+                range: String::from(values),
+            });
+        }
+        return Ok((start..=end).collect());
+    }
+    values.split('/').map(parse).collect()
+}
+
+fn parse_switches(parameter: &str, values: &str) -> Result<Vec<bool>, SweepError> {
+    values
+        .split('/')
+        .map(|value| match value {
+            "on" => Ok(true),
+            "off" => Ok(false),
+            _ => Err(SweepError::BadValue {
+                parameter: String::from(parameter),
+                value: String::from(value),
+            }),
+        })
+        .collect()
+}
+
+fn set_once<T>(slot: &mut Option<T>, parameter: &str, values: T) -> Result<(), SweepError> {
+    if slot.is_some() {
+        return Err(SweepError::Repeated(String::from(parameter)));
+    }
+    *slot = Some(values);
+    Ok(())
+}
+
+impl FromStr for Sweep {
+    type Err = SweepError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut sweep = Self::default();
+        for axis in text.split(',') {
+            let (parameter, values) = axis.split_once('=').unwrap_or((axis, ""));
+            let (parameter, values) = (parameter.trim(), values.trim());
+            if values.is_empty() {
+                return Err(SweepError::MissingValues(String::from(parameter)));
+            }
+            match parameter {
+                "threshold" => set_once(
+                    &mut sweep.thresholds,
+                    parameter,
+                    parse_counts(parameter, values)?,
+                )?,
+                "radius" => {
+                    // Rays take at least one step, so a radius of 0 would never
+                    // stop one
+                    let radii = parse_counts(parameter, values)?;
+                    if radii.contains(&0) {
+                        return Err(SweepError::BadValue {
+                            parameter: String::from(parameter),
+                            value: String::from("0"),
+                        });
+                    }
+                    set_once(&mut sweep.radii, parameter, radii)?;
+                }
+                "los" => set_once(
+                    &mut sweep.line_of_sight,
+                    parameter,
+                    parse_switches(parameter, values)?,
+                )?,
+                _ => return Err(SweepError::UnknownParameter(String::from(parameter))),
+            }
+        }
+        if sweep.line_of_sight.is_some() && sweep.radii.is_some() {
+            return Err(SweepError::Conflict);
+        }
+        Ok(sweep)
+    }
+}
+
+impl Sweep {
+    // Every combination of the swept parameters, applied to each of the base
+    // rules in turn. Sweeping los replaces the base rules with the puzzle's
+    // own adjacent and line-of-sight rules, on the first base rule's topology.
+    #[must_use]
+    pub fn get_rules(&self, base: &[Rule]) -> Vec<Rule> {
+        let mut rules = match &self.line_of_sight {
+            Some(line_of_sight) => line_of_sight
+                .iter()
+                .map(|los| Rule {
+                    topology: base[0].topology,
+                    ..Rule::new(*los)
+                })
+                .collect(),
+            None => base.to_vec(),
+        };
+        if let Some(radii) = &self.radii {
+            rules = rules
+                .iter()
+                .flat_map(|rule| {
+                    radii.iter().map(move |radius| Rule {
+                        radius: Some(*radius),
+                        ..*rule
+                    })
+                })
+                .collect();
+        }
+        if let Some(thresholds) = &self.thresholds {
+            rules = rules
+                .iter()
+                .flat_map(|rule| {
+                    thresholds.iter().map(move |threshold| Rule {
+                        abandonment_threshold: *threshold,
+                        ..*rule
+                    })
+                })
+                .collect();
+        }
+        rules
+    }
+}
+
+// How one combination settled, and after how many generations: when it
+// stopped changing, or when the cycle it ended up in began
+pub struct Settled {
+    pub rule: Rule,
+    pub outcome: Outcome,
+    pub generations: usize,
+}
+
+#[must_use]
+pub fn settle(mut layout: Layout, rule: Rule) -> Settled {
+    let mut generations = 0;
+    let outcome = layout.run_with_observer(|generation, _| generations = generation);
+    if let Outcome::Cycle { start, .. } = outcome {
+        generations = start;
+    }
+    Settled {
+        rule,
+        outcome,
+        generations,
+    }
+}
+
+#[must_use]
+pub fn to_table(results: &[Settled]) -> MarkdownTable {
+    let mut table =
+        MarkdownTable::new(&["Radius", "Threshold", "Outcome", "Occupied", "Generations"])
+            .align(0, Align::Right)
+            .align(1, Align::Right)
+            .align(3, Align::Right)
+            .align(4, Align::Right);
+    for settled in results {
+        let (outcome, occupied) = match &settled.outcome {
+            Outcome::Stable(occupants) => ("stable", occupants.to_string()),
+            Outcome::Cycle {
+                length, occupancy, ..
+            } => (
+                "cycle",
+                format!(
+                    "{}..{} over {}",
+                    occupancy.iter().min().unwrap(),
+                    occupancy.iter().max().unwrap(),
+                    length
+                ),
+            ),
+            Outcome::Diverged { .. } => ("diverged", String::new()),
+            Outcome::OutOfTime { occupants, .. } => ("out of time", occupants.to_string()),
+        };
+        table
+            .row()
+            .cell(
+                settled
+                    .rule
+                    .radius
+                    .map_or(String::from("any"), |radius| radius.to_string()),
+            )
+            .cell(settled.rule.abandonment_threshold)
+            .cell(outcome)
+            .cell(occupied)
+            .cell(settled.generations)
+            .finish();
+    }
+    table
+}
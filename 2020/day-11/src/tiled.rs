@@ -0,0 +1,268 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    thread,
+    time::Duration,
+};
+
+use super::{check_row, read_rows, History, InputError, LayoutError, Outcome, Rule, Topology};
+
+const FLOOR: u8 = 0;
+const EMPTY: u8 = 1;
+const OCCUPIED: u8 = 2;
+
+pub const TILE_ROWS: usize = 256;
+
+// A horizontal band of rows, plus copies of the rows just outside it (the
+// halos) so that it can be evolved without looking at any other tile
+#[derive(Clone)]
+struct Tile {
+    cells: Vec<u8>,
+    next: Vec<u8>,
+    top_halo: Option<Vec<u8>>,
+    bottom_halo: Option<Vec<u8>>,
+}
+
+impl Tile {
+    fn new() -> Self {
+        Self {
+            cells: Vec::new(),
+            next: Vec::new(),
+            top_halo: None,
+            bottom_halo: None,
+        }
+    }
+
+    fn first_row(&self, column_count: usize) -> &[u8] {
+        &self.cells[..column_count]
+    }
+
+    fn last_row(&self, column_count: usize) -> &[u8] {
+        &self.cells[self.cells.len() - column_count..]
+    }
+
+    fn get_row(&self, row: Option<usize>, column_count: usize) -> Option<&[u8]> {
+        let row_count = self.cells.len() / column_count;
+        match row {
+            None => self.top_halo.as_deref(),
+            Some(row) if row >= row_count => self.bottom_halo.as_deref(),
+            Some(row) => Some(&self.cells[row * column_count..(row + 1) * column_count]),
+        }
+    }
+
+    // Returns the number of changed seats and the number of occupied seats
+    fn evolve(&mut self, column_count: usize, threshold: usize) -> (usize, usize) {
+        let mut next_cells = std::mem::take(&mut self.next);
+        next_cells.resize(self.cells.len(), FLOOR);
+
+        let mut changes = 0;
+        let mut occupants = 0;
+        for row in 0..self.cells.len() / column_count {
+            let rows = [
+                self.get_row(row.checked_sub(1), column_count),
+                self.get_row(Some(row), column_count),
+                self.get_row(Some(row + 1), column_count),
+            ];
+            let current = rows[1].expect("Tile row out of range");
+
+            for column in 0..column_count {
+                let cell = current[column];
+                let mut next = cell;
+                if cell != FLOOR {
+                    let first_column = column.saturating_sub(1);
+                    let last_column = (column + 1).min(column_count - 1);
+                    let adjacent_occupants = rows
+                        .iter()
+                        .flatten()
+                        .flat_map(|neighbors| &neighbors[first_column..=last_column])
+                        .filter(|neighbor| **neighbor == OCCUPIED)
+                        .count()
+                        - usize::from(cell == OCCUPIED);
+
+                    if cell == OCCUPIED && adjacent_occupants >= threshold {
+                        next = EMPTY;
+                    } else if cell == EMPTY && adjacent_occupants == 0 {
+                        next = OCCUPIED;
+                    }
+                }
+
+                if next != cell {
+                    changes += 1;
+                }
+                if next == OCCUPIED {
+                    occupants += 1;
+                }
+                next_cells[row * column_count + column] = next;
+            }
+        }
+
+        self.next = std::mem::replace(&mut self.cells, next_cells);
+        (changes, occupants)
+    }
+}
+
+#[derive(Clone)]
+pub struct TiledLayout {
+    tiles: Vec<Tile>,
+    column_count: usize,
+}
+
+impl TiledLayout {
+    fn new() -> Self {
+        Self {
+            tiles: vec![Tile::new()],
+            column_count: 0,
+        }
+    }
+
+    // Rows go into the last tile until it holds tile_rows of them
+    fn add_row(
+        &mut self,
+        line_number: usize,
+        line: &str,
+        tile_rows: usize,
+    ) -> Result<(), LayoutError> {
+        check_row(
+            line_number,
+            line,
+            (self.column_count > 0).then_some(self.column_count),
+        )?;
+        self.column_count = line.len();
+
+        if self.tiles.last().unwrap().cells.len() == tile_rows * self.column_count {
+            self.tiles.push(Tile::new());
+        }
+        let tile = self.tiles.last_mut().unwrap();
+        tile.cells.extend(line.bytes().map(|byte| match byte {
+            b'.' => FLOOR,
+            b'L' => EMPTY,
+            b'#' => OCCUPIED,
+            _ => unreachable!(),
+        }));
+        Ok(())
+    }
+
+    pub fn read(filename: &str, tile_rows: usize) -> Result<Self, InputError> {
+        let _scope = common::scope!("parse");
+        let mut layout = Self::new();
+        read_rows(filename, |line_number, line| {
+            layout.add_row(line_number, line, tile_rows)
+        })?;
+        Ok(layout)
+    }
+
+    // Like read, for rows that are already in memory
+    pub fn from_rows<'a, I>(rows: I, tile_rows: usize) -> Result<Self, LayoutError>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let mut layout = Self::new();
+        for (index, row) in rows.into_iter().enumerate() {
+            layout.add_row(index + 1, row, tile_rows)?;
+        }
+        if layout.column_count == 0 {
+            return Err(LayoutError::EmptyInput);
+        }
+        Ok(layout)
+    }
+
+    fn exchange_halos(&mut self) {
+        for index in 1..self.tiles.len() {
+            let (above, below) = self.tiles.split_at_mut(index);
+            let above = above.last_mut().unwrap();
+            let below = &mut below[0];
+            below.top_halo = Some(above.last_row(self.column_count).to_vec());
+            above.bottom_halo = Some(below.first_row(self.column_count).to_vec());
+        }
+    }
+
+    fn evolve(&mut self, threshold: usize, jobs: usize) -> (usize, usize) {
+        let _scope = common::scope!("evolve");
+        self.exchange_halos();
+
+        let column_count = self.column_count;
+        let chunk_size = self.tiles.len().div_ceil(jobs.max(1));
+        thread::scope(|scope| {
+            let workers: Vec<_> = self
+                .tiles
+                .chunks_mut(chunk_size)
+                .map(|tiles| {
+                    scope.spawn(move || {
+                        // Each worker's share shows up on its own thread
+                        let _scope = common::scope!("evolve tiles");
+                        tiles.iter_mut().fold((0, 0), |(changes, occupants), tile| {
+                            let (tile_changes, tile_occupants) =
+                                tile.evolve(column_count, threshold);
+                            (changes + tile_changes, occupants + tile_occupants)
+                        })
+                    })
+                })
+                .collect();
+
+            workers
+                .into_iter()
+                .map(|worker| worker.join().expect("Tile worker panicked"))
+                .fold(
+                    (0, 0),
+                    |(changes, occupants), (chunk_changes, chunk_occupants)| {
+                        (changes + chunk_changes, occupants + chunk_occupants)
+                    },
+                )
+        })
+    }
+
+    fn count_occupants(&self) -> usize {
+        let _scope = common::scope!("count");
+        self.tiles
+            .iter()
+            .flat_map(|tile| &tile.cells)
+            .map(|cell| usize::from(*cell == OCCUPIED))
+            .sum()
+    }
+
+    fn get_state_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        for tile in &self.tiles {
+            tile.cells.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+}
+
+// Tiles only exchange a single row with each other, so they can only follow
+// rules that look one seat away on a square grid
+#[must_use]
+pub fn supports(rule: &Rule) -> bool {
+    rule.radius == Some(1) && rule.topology == Topology::Square
+}
+
+// Only for rules the tiles support
+#[must_use]
+pub fn settle(
+    mut layout: TiledLayout,
+    rule: Rule,
+    jobs: usize,
+    max_generations: Option<usize>,
+    time_budget: Option<Duration>,
+) -> Outcome {
+    assert!(
+        supports(&rule),
+        "Tiled processing only supports a radius of 1 on a square grid"
+    );
+    let threshold = rule.abandonment_threshold;
+    let _scope = common::scope!("run");
+
+    let mut history = History::new(max_generations, time_budget);
+    let mut occupants = layout.count_occupants();
+    loop {
+        if let Some(outcome) = history.record(Some(layout.get_state_hash()), occupants) {
+            return outcome;
+        }
+
+        let (changes, next_occupants) = layout.evolve(threshold, jobs);
+        if changes == 0 {
+            return Outcome::Stable(occupants);
+        }
+        occupants = next_occupants;
+    }
+}
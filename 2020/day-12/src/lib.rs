@@ -0,0 +1,143 @@
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(clippy::missing_panics_doc)]
+
+use common::{coords, matrix::Mat2, LineReader, Solution};
+
+#[derive(Clone, Copy)]
+enum Direction {
+    North = 0,
+    East = 1,
+    South = 2,
+    West = 3,
+}
+
+impl Direction {
+    fn from_i64(value: i64) -> Self {
+        match value {
+            0 => Direction::North,
+            1 => Direction::East,
+            2 => Direction::South,
+            3 => Direction::West,
+            _ => panic!("Unexpected value {}", value),
+        }
+    }
+}
+
+enum Rotation {
+    Right,
+    Left,
+}
+
+// What the N, E, S and W instructions move
+pub enum Mode {
+    Ship,
+    Waypoint,
+}
+
+struct Navigator {
+    mode: Mode,
+    x: i64,
+    y: i64,
+    direction: Direction,
+    waypoint_x: i64,
+    waypoint_y: i64,
+}
+
+impl Navigator {
+    fn new(mode: Mode) -> Self {
+        Self {
+            mode,
+            x: 0,
+            y: 0,
+            direction: Direction::East,
+            waypoint_x: 10,
+            waypoint_y: 1,
+        }
+    }
+
+    fn translate(&mut self, direction: Direction, amount: i64) {
+        let (x, y) = match self.mode {
+            Mode::Ship => (&mut self.x, &mut self.y),
+            Mode::Waypoint => (&mut self.waypoint_x, &mut self.waypoint_y),
+        };
+
+        match direction {
+            Direction::North => {
+                *y += amount;
+            }
+            Direction::East => {
+                *x += amount;
+            }
+            Direction::South => {
+                *y -= amount;
+            }
+            Direction::West => {
+                *x -= amount;
+            }
+        }
+    }
+
+    fn turn(&mut self, rotation: &Rotation, amount: i64) {
+        let clockwise_amount = match rotation {
+            Rotation::Right => amount,
+            Rotation::Left => 360 - amount,
+        };
+        let quarter_turns = clockwise_amount / 90;
+        let [x, y] = Mat2::rotation(-quarter_turns).apply([self.waypoint_x, self.waypoint_y]);
+        self.waypoint_x = x;
+        self.waypoint_y = y;
+        self.direction = Direction::from_i64((self.direction as i64 + quarter_turns) % 4);
+    }
+
+    fn move_forward(&mut self, amount: i64) {
+        match self.mode {
+            Mode::Ship => self.translate(self.direction, amount),
+            Mode::Waypoint => {
+                self.x += self.waypoint_x * amount;
+                self.y += self.waypoint_y * amount;
+            }
+        }
+    }
+
+    fn parse_line(&mut self, line: &str) {
+        let amount = line[1..].parse().expect("Failed to parse amount as i64");
+        match line.as_bytes()[0] {
+            b'N' => self.translate(Direction::North, amount),
+            b'E' => self.translate(Direction::East, amount),
+            b'S' => self.translate(Direction::South, amount),
+            b'W' => self.translate(Direction::West, amount),
+            b'L' => self.turn(&Rotation::Left, amount),
+            b'R' => self.turn(&Rotation::Right, amount),
+            b'F' => self.move_forward(amount),
+            _ => panic!("Unexpected prefix {}", line.as_bytes()[0]),
+        }
+    }
+
+    fn get_distance(&self) -> i64 {
+        coords::manhattan((self.x, self.y), (0, 0))
+    }
+}
+
+// How far the ship ends up from where it started
+#[must_use]
+pub fn navigate(text: &str, mode: Mode) -> i64 {
+    let mut navigator = Navigator::new(mode);
+
+    for line in LineReader::from_text(text) {
+        navigator.parse_line(&line);
+    }
+
+    navigator.get_distance()
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    fn part1(&self, input: &str) -> String {
+        navigate(input, Mode::Ship).to_string()
+    }
+
+    fn part2(&self, input: &str) -> String {
+        navigate(input, Mode::Waypoint).to_string()
+    }
+}
@@ -0,0 +1,101 @@
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(clippy::missing_panics_doc)]
+
+use std::convert::TryInto;
+
+use common::{LineReader, Solution};
+
+pub struct Schedule {
+    earliest_timestamp: i32,
+    routes: String,
+}
+
+impl Schedule {
+    #[must_use]
+    pub fn from_text(text: &str) -> Self {
+        let mut lines = LineReader::from_text(text);
+
+        let earliest_timestamp: i32 = lines
+            .next()
+            .expect("Failed to read line")
+            .parse()
+            .expect("Failed to read earliest timestamp");
+
+        Self {
+            earliest_timestamp,
+            routes: lines.next().expect("Failed to read line"),
+        }
+    }
+
+    // The first route to leave after the earliest timestamp, and how many
+    // minutes there are to wait for it
+    #[must_use]
+    pub fn get_next_arrival(&self) -> (i32, i32) {
+        self.routes
+            .split(',')
+            .filter_map(|route| {
+                if route == "x" {
+                    return None;
+                }
+
+                let route = route.parse::<i32>().expect("Failed to parse route as i32");
+                let next_arrival =
+                    (self.earliest_timestamp / route + 1) * route - self.earliest_timestamp;
+
+                Some((route, next_arrival))
+            })
+            .min_by_key(|(_route, next_arrival)| *next_arrival)
+            .expect("Failed to find next arrival")
+    }
+
+    // The first timestamp where each route leaves as many minutes after it as
+    // its position in the list
+    #[must_use]
+    pub fn get_subsequent_timestamp(&self) -> i64 {
+        let mut timestamp = 0;
+        let mut skip = 1;
+        for (id, modulo) in self
+            .routes
+            .split(',')
+            .enumerate()
+            .filter_map(|(index, id)| {
+                if id == "x" {
+                    return None;
+                }
+
+                let id = id.parse::<i64>().expect("Failed to parse route as i64");
+                let index: i64 = index.try_into().expect("Failed to fit index into i64");
+
+                let mut modulo = -index;
+                while modulo < 0 {
+                    modulo += id;
+                }
+
+                Some((id, modulo))
+            })
+        {
+            while timestamp % id != modulo {
+                timestamp += skip;
+            }
+
+            skip *= id;
+        }
+
+        timestamp
+    }
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    fn part1(&self, input: &str) -> String {
+        let (route, next_arrival) = Schedule::from_text(input).get_next_arrival();
+        (route * next_arrival).to_string()
+    }
+
+    fn part2(&self, input: &str) -> String {
+        Schedule::from_text(input)
+            .get_subsequent_timestamp()
+            .to_string()
+    }
+}
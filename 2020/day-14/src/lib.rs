@@ -0,0 +1,115 @@
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(clippy::missing_panics_doc)]
+
+use common::{digits::iterate_subsets, LineReader, Solution};
+use std::collections::HashMap;
+
+// Whether the mask applies to the values written or the addresses they go to
+pub enum Mode {
+    Address,
+    Value,
+}
+
+struct ProgramLoader {
+    mode: Mode,
+    set_mask: u64,
+    clear_mask: u64,
+    floating_mask: u64,
+    memory: HashMap<u64, u64>,
+}
+
+impl ProgramLoader {
+    fn new(mode: Mode) -> Self {
+        let mut memory = HashMap::new();
+        memory.reserve(100_000);
+        Self {
+            mode,
+            set_mask: 0,
+            clear_mask: 0,
+            floating_mask: 0,
+            memory,
+        }
+    }
+
+    fn update_masks(&mut self, mask: &str) {
+        self.set_mask = 0;
+        self.clear_mask = 0;
+        self.floating_mask = 0;
+        for byte in mask.as_bytes() {
+            self.set_mask <<= 1;
+            self.clear_mask <<= 1;
+            self.floating_mask <<= 1;
+            match *byte {
+                b'X' => self.floating_mask |= 1,
+                b'0' => self.clear_mask |= 1,
+                b'1' => self.set_mask |= 1,
+                _ => panic!("Unexpected mask byte {}", *byte),
+            }
+        }
+    }
+
+    fn write_memory(&mut self, line: &str) {
+        let mut split = line.split('=');
+
+        let address = split.next().expect("Failed to get address split").trim();
+        let address: u64 = address[1..address.len() - 1]
+            .parse()
+            .expect("Failed to parse address as u64");
+
+        let value: u64 = split
+            .next()
+            .expect("Failed to get value split")
+            .trim()
+            .parse()
+            .expect("Failed to parse value as u64");
+
+        match self.mode {
+            Mode::Address => {
+                let base = (address | self.set_mask) & !self.floating_mask;
+                for floating in iterate_subsets(self.floating_mask) {
+                    self.memory.insert(base | floating, value);
+                }
+                None
+            }
+            Mode::Value => self
+                .memory
+                .insert(address, (value | self.set_mask) & !self.clear_mask),
+        };
+    }
+
+    fn parse_line(&mut self, line: &str) {
+        match &line[0..3] {
+            "mas" => self.update_masks(&line[7..]),
+            "mem" => self.write_memory(&line[3..]),
+            _ => panic!("Unexpected line [{}]", line),
+        }
+    }
+
+    fn get_memory_sum(&self) -> u64 {
+        self.memory.values().sum()
+    }
+}
+
+// The sum of everything left in memory once the program has run
+#[must_use]
+pub fn run_program(text: &str, mode: Mode) -> u64 {
+    let mut loader = ProgramLoader::new(mode);
+
+    for line in LineReader::from_text(text) {
+        loader.parse_line(&line);
+    }
+
+    loader.get_memory_sum()
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    fn part1(&self, input: &str) -> String {
+        run_program(input, Mode::Value).to_string()
+    }
+
+    fn part2(&self, input: &str) -> String {
+        run_program(input, Mode::Address).to_string()
+    }
+}
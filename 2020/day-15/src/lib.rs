@@ -0,0 +1,78 @@
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(clippy::missing_panics_doc)]
+
+use common::{LineReader, Solution};
+
+// The turns each part asks about
+pub const PART1_TURN: u32 = 2020;
+pub const PART2_TURN: u32 = 30_000_000;
+
+struct MemoryGame {
+    current_turn: u32,
+    previous_number: u32,
+    last_seen: Vec<u32>,
+}
+
+impl MemoryGame {
+    fn new(initial_numbers: &str, turns: u32) -> Self {
+        let mut current_turn = 1;
+        let mut previous_number = 0;
+        let mut last_seen = vec![0; turns as usize];
+
+        for number in initial_numbers.split(',').map(|number| {
+            number
+                .parse::<u32>()
+                .expect("Failed to parse number as i32")
+        }) {
+            last_seen[number as usize] = current_turn;
+            previous_number = number;
+            current_turn += 1;
+        }
+
+        Self {
+            current_turn,
+            previous_number,
+            last_seen,
+        }
+    }
+
+    fn nth(&mut self, n: u32) -> u32 {
+        while self.current_turn <= n {
+            let current_number = if self.last_seen[self.previous_number as usize] > 0 {
+                self.current_turn - 1 - self.last_seen[self.previous_number as usize]
+            } else {
+                0
+            };
+
+            self.last_seen[self.previous_number as usize] = self.current_turn - 1;
+
+            self.previous_number = current_number;
+            self.current_turn += 1;
+        }
+
+        self.previous_number
+    }
+}
+
+// The number spoken on turn n, given the starting numbers on the first line
+#[must_use]
+pub fn play(text: &str, n: u32) -> u32 {
+    let line = LineReader::from_text(text)
+        .next()
+        .expect("Failed to read line");
+
+    let mut game = MemoryGame::new(&line, n);
+    game.nth(n)
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    fn part1(&self, input: &str) -> String {
+        play(input, PART1_TURN).to_string()
+    }
+
+    fn part2(&self, input: &str) -> String {
+        play(input, PART2_TURN).to_string()
+    }
+}
@@ -0,0 +1,273 @@
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(clippy::missing_panics_doc)]
+
+use bit_set::BitSet;
+use common::{
+    search::{self, SearchProblem, Strategy},
+    LineReader, Solution,
+};
+
+struct Range {
+    begin: i32,
+    end: i32,
+}
+
+struct Field {
+    id: usize,
+    name: String,
+    ranges: Vec<Range>,
+}
+
+struct TicketValidator {
+    fields: Vec<Field>,
+}
+
+impl TicketValidator {
+    fn new() -> Self {
+        Self { fields: Vec::new() }
+    }
+
+    fn add_field(&mut self, line: &str) {
+        let mut split = line.split(':');
+
+        let name = split.next().expect("Failed to find field name");
+
+        let ranges: Vec<Range> = split
+            .next()
+            .expect("Failed to find ranges")
+            .trim()
+            .split(" or ")
+            .map(|range| {
+                let mut endpoints = range.split('-');
+                let begin: i32 = endpoints
+                    .next()
+                    .expect("Failed to find beginning of range")
+                    .parse()
+                    .expect("Failed to parse beginning of range as i32");
+                let end: i32 = endpoints
+                    .next()
+                    .expect("Failed to find end of range")
+                    .parse()
+                    .expect("Failed to parse end of range as i32");
+                Range { begin, end }
+            })
+            .collect();
+
+        self.fields.push(Field {
+            id: self.fields.len(),
+            name: String::from(name),
+            ranges,
+        });
+    }
+
+    fn get_invalid_sum(&self, ticket: &str) -> Option<i32> {
+        let mut sum = None;
+
+        for value in ticket.split(',').map(|value| {
+            value
+                .parse::<i32>()
+                .expect("Failed to parse field value as i32")
+        }) {
+            if !self
+                .fields
+                .iter()
+                .map(|field| &field.ranges)
+                .flat_map(|ranges| ranges.iter())
+                .any(|range| value >= range.begin && value <= range.end)
+            {
+                *sum.get_or_insert(0) += value;
+            }
+        }
+
+        sum
+    }
+
+    fn get_possible_field_ids(&self, ticket: &str) -> Vec<BitSet> {
+        let mut possibilities = Vec::new();
+
+        for value in ticket.split(',').map(|value| {
+            value
+                .parse::<i32>()
+                .expect("Failed to parse field value as i32")
+        }) {
+            let field_ids: BitSet = self
+                .fields
+                .iter()
+                .filter_map(|field| {
+                    for range in &field.ranges {
+                        if value >= range.begin && value <= range.end {
+                            return Some(field.id);
+                        }
+                    }
+                    None
+                })
+                .collect();
+            possibilities.push(field_ids);
+        }
+
+        possibilities
+    }
+
+    fn get_field_name(&self, id: usize) -> String {
+        for field in &self.fields {
+            if field.id == id {
+                return field.name.clone();
+            }
+        }
+
+        String::from("Unknown")
+    }
+}
+
+// Matches ticket positions to fields one at a time, most constrained position
+// first, so that any strategy only has to backtrack over genuine ambiguity
+struct FieldAssignment<'a> {
+    possibilities: &'a [BitSet],
+    order: Vec<usize>,
+}
+
+impl<'a> FieldAssignment<'a> {
+    fn new(possibilities: &'a [BitSet]) -> Self {
+        let mut order: Vec<usize> = (0..possibilities.len()).collect();
+        order.sort_by_key(|position| possibilities[*position].len());
+        Self {
+            possibilities,
+            order,
+        }
+    }
+
+    // Returns the field id for each ticket position
+    fn solve(&self, strategy: Strategy) -> Vec<usize> {
+        let solution = search::solve(self, strategy).expect("Failed to assign fields");
+        let assigned = solution.path.last().expect("Failed to find final state");
+
+        let mut field_ids = vec![0; self.possibilities.len()];
+        for (position, field_id) in self.order.iter().zip(assigned) {
+            field_ids[*position] = *field_id;
+        }
+        field_ids
+    }
+}
+
+impl SearchProblem for FieldAssignment<'_> {
+    // Field ids chosen so far, in the same order as self.order
+    type State = Vec<usize>;
+    type Cost = usize;
+
+    fn start(&self) -> Self::State {
+        Vec::new()
+    }
+
+    fn successors(&self, assigned: &Self::State) -> Vec<(Self::State, usize)> {
+        let position = self.order[assigned.len()];
+        self.possibilities[position]
+            .iter()
+            .filter(|field_id| !assigned.contains(field_id))
+            .map(|field_id| {
+                let mut next = assigned.clone();
+                next.push(field_id);
+                (next, 1)
+            })
+            .collect()
+    }
+
+    fn is_goal(&self, assigned: &Self::State) -> bool {
+        assigned.len() == self.possibilities.len()
+    }
+}
+
+pub struct Notes {
+    validator: TicketValidator,
+    your_ticket: String,
+    // The sum of values that fit no field at all
+    pub invalid_sum: i32,
+    // The fields each position could be, going by the valid nearby tickets
+    possibilities: Vec<BitSet>,
+}
+
+impl Notes {
+    #[must_use]
+    pub fn from_text(text: &str) -> Self {
+        let mut lines = LineReader::from_text(text);
+
+        let mut validator = TicketValidator::new();
+
+        // Parse fields
+        lines.read_with(|line| validator.add_field(line));
+
+        // Skip "your ticket" header
+        lines.next().expect("Failed to read 'your ticket' header");
+
+        let your_ticket = lines.next().expect("Failed to read your ticket");
+
+        // Skip blank line and "nearby tickets" header
+        lines.next().expect("Failed to read blank line");
+        lines
+            .next()
+            .expect("Failed to read 'nearby tickets' header");
+
+        let mut possibilities: Vec<BitSet> = Vec::new();
+
+        let mut invalid_sum = 0;
+        for line in lines {
+            if let Some(ticket_sum) = validator.get_invalid_sum(&line) {
+                invalid_sum += ticket_sum;
+            } else if possibilities.is_empty() {
+                possibilities = validator.get_possible_field_ids(&line);
+            } else {
+                let ticket_possibilities = validator.get_possible_field_ids(&line);
+                for (possible, ticket_possible) in
+                    possibilities.iter_mut().zip(&ticket_possibilities)
+                {
+                    possible.intersect_with(ticket_possible);
+                }
+            }
+        }
+
+        Self {
+            validator,
+            your_ticket,
+            invalid_sum,
+            possibilities,
+        }
+    }
+
+    // The product of your ticket's departure fields
+    #[must_use]
+    pub fn get_departure_product(&self, strategy: Strategy) -> i64 {
+        let field_ids = FieldAssignment::new(&self.possibilities).solve(strategy);
+
+        let mut your_values = self
+            .your_ticket
+            .split(',')
+            .map(|field| field.parse::<i64>().expect("Failed to parse field as i64"));
+
+        field_ids
+            .iter()
+            .filter_map(|field_id| {
+                let value = your_values.next().expect("Failed to find field value");
+                let field_name = self.validator.get_field_name(*field_id);
+
+                if field_name.len() >= 9 && &field_name[0..9] == "departure" {
+                    Some(value)
+                } else {
+                    None
+                }
+            })
+            .product()
+    }
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    fn part1(&self, input: &str) -> String {
+        Notes::from_text(input).invalid_sum.to_string()
+    }
+
+    fn part2(&self, input: &str) -> String {
+        Notes::from_text(input)
+            .get_departure_product(Strategy::Dfs)
+            .to_string()
+    }
+}
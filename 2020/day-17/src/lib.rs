@@ -0,0 +1,184 @@
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(clippy::missing_panics_doc)]
+
+use common::{grid::Grid, LineReader, Solution};
+
+// The boot process is always this many cycles
+pub const ITERATIONS: usize = 6;
+
+pub struct PocketDimension {
+    dimensions: u32,
+    side_length: usize,
+    margin: usize,
+    // Where the initial slice sits in z, and in w for four dimensions
+    center: usize,
+    cubes: Vec<bool>,
+}
+
+impl PocketDimension {
+    fn address_helper(side_length: usize, x: usize, y: usize, z: usize, w: usize) -> usize {
+        w * side_length * side_length * side_length
+            + z * side_length * side_length
+            + y * side_length
+            + x
+    }
+
+    fn get_address(&self, x: usize, y: usize, z: usize, w: usize) -> usize {
+        PocketDimension::address_helper(self.side_length, x, y, z, w)
+    }
+
+    #[must_use]
+    pub fn new(dimensions: u32, iterations: usize, initial_state: &[String]) -> Self {
+        let mut cubes = Vec::new();
+        let margin = iterations + 1;
+        let side_length = initial_state.len() + margin * 2;
+        cubes.resize(side_length * side_length * side_length * side_length, false);
+
+        for y in 0..initial_state.len() {
+            let line = &initial_state[y];
+            for x in 0..initial_state.len() {
+                let cube = match line.as_bytes()[x] {
+                    b'#' => true,
+                    b'.' => false,
+                    _ => panic!("Unexpected byte {}", line.as_bytes()[x]),
+                };
+                let w = match dimensions {
+                    3 => 0,
+                    4 => margin,
+                    _ => panic!("Unexpected dimensionality {}", dimensions),
+                };
+                cubes[PocketDimension::address_helper(
+                    side_length,
+                    x + margin,
+                    y + margin,
+                    margin,
+                    w,
+                )] = cube;
+            }
+        }
+
+        Self {
+            dimensions,
+            side_length,
+            margin,
+            center: margin,
+            cubes,
+        }
+    }
+
+    fn count_active_neighbors(
+        &self,
+        center_x: usize,
+        center_y: usize,
+        center_z: usize,
+        center_w: usize,
+    ) -> u32 {
+        let mut count = 0;
+
+        let w_range = match self.dimensions {
+            3 => center_w..=center_w,
+            4 => center_w - 1..=center_w + 1,
+            _ => panic!("Unexpected dimensionality {}", self.dimensions),
+        };
+
+        for w in w_range {
+            for z in center_z - 1..=center_z + 1 {
+                for y in center_y - 1..=center_y + 1 {
+                    for x in center_x - 1..=center_x + 1 {
+                        if x == center_x && y == center_y && z == center_z && w == center_w {
+                            continue;
+                        }
+
+                        if self.cubes[self.get_address(x, y, z, w)] {
+                            count += 1;
+                            if count >= 4 {
+                                return count;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        count
+    }
+
+    pub fn simulate(&mut self) {
+        let mut changes = Vec::new();
+
+        let range = self.margin - 1..self.side_length - self.margin;
+        self.margin -= 1;
+
+        let w_range = match self.dimensions {
+            3 => 0..1,
+            4 => range.clone(),
+            _ => panic!("Unexpected dimensionality {}", self.dimensions),
+        };
+
+        for w in w_range {
+            for z in range.clone() {
+                for y in range.clone() {
+                    for x in range.clone() {
+                        let address = self.get_address(x, y, z, w);
+                        if self.cubes[address] {
+                            let active_neighbors = self.count_active_neighbors(x, y, z, w);
+                            if !(2..=3).contains(&active_neighbors) {
+                                changes.push(address);
+                            }
+                        } else if self.count_active_neighbors(x, y, z, w) == 3 {
+                            changes.push(address);
+                        }
+                    }
+                }
+            }
+        }
+
+        for change in changes {
+            self.cubes[change] ^= true;
+        }
+    }
+
+    #[must_use]
+    pub fn get_active_count(&self) -> u32 {
+        self.cubes.iter().map(|active| u32::from(*active)).sum()
+    }
+
+    // The slice containing the initial state, which is the only one that can
+    // be compared with a 2D automaton
+    #[must_use]
+    pub fn get_center_slice(&self) -> Grid<bool> {
+        let w = if self.dimensions == 4 { self.center } else { 0 };
+        let cells = (0..self.side_length)
+            .flat_map(|y| (0..self.side_length).map(move |x| (x, y)))
+            .map(|(x, y)| self.cubes[self.get_address(x, y, self.center, w)])
+            .collect();
+        Grid::from_cells(self.side_length, cells)
+    }
+}
+
+// Runs the boot process from the initial state
+#[must_use]
+pub fn boot(initial_state: &[String], dimensions: u32) -> PocketDimension {
+    let mut pocket_dimension = PocketDimension::new(dimensions, ITERATIONS, initial_state);
+    for _ in 0..ITERATIONS {
+        pocket_dimension.simulate();
+    }
+    pocket_dimension
+}
+
+fn count_active(text: &str, dimensions: u32) -> u32 {
+    let initial_state: Vec<String> = LineReader::from_text(text).collect();
+    boot(&initial_state, dimensions).get_active_count()
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    fn part1(&self, input: &str) -> String {
+        count_active(input, 3).to_string()
+    }
+
+    fn part2(&self, input: &str) -> String {
+        count_active(input, 4).to_string()
+    }
+}
@@ -0,0 +1,107 @@
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(clippy::missing_panics_doc)]
+
+use common::{LineReader, Solution};
+
+#[derive(Clone, Copy, Debug)]
+enum Command {
+    Add,
+    Multiply,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Operation {
+    command: Command,
+    value: i64,
+}
+
+fn get_next_value(advanced: bool, expression: &str) -> (i64, usize) {
+    match &expression[0..1] {
+        "(" => evaluate_expression(advanced, &expression[1..]),
+        _ => (
+            expression[0..1]
+                .parse()
+                .expect("Failed to parse digit as i64"),
+            1,
+        ),
+    }
+}
+
+fn flatten_operations(advanced: bool, operations: &[Operation]) -> i64 {
+    if advanced {
+        let mut reduced = vec![operations[0]];
+        for operation in operations.iter().skip(1) {
+            match operation.command {
+                Command::Add => {
+                    reduced
+                        .last_mut()
+                        .expect("Failed to get last reduced element")
+                        .value += operation.value;
+                }
+                Command::Multiply => reduced.push(*operation),
+            }
+        }
+
+        return flatten_operations(false, &reduced);
+    }
+
+    operations
+        .iter()
+        .fold(0, |value, operation| match operation.command {
+            Command::Add => value + operation.value,
+            Command::Multiply => value * operation.value,
+        })
+}
+
+fn evaluate_expression(advanced: bool, expression: &str) -> (i64, usize) {
+    let mut cursor = 0;
+    let mut operations = Vec::new();
+
+    let (value, advance) = get_next_value(advanced, expression);
+    operations.push(Operation {
+        command: Command::Add,
+        value,
+    });
+    cursor += advance;
+
+    while cursor < expression.len() {
+        if &expression[cursor..=cursor] == ")" {
+            return (flatten_operations(advanced, &operations), cursor + 2);
+        }
+
+        let command = match &expression[cursor..cursor + 3] {
+            " + " => Command::Add,
+            " * " => Command::Multiply,
+            _ => panic!(
+                "Unexpected continuation [{}]",
+                &expression[cursor..cursor + 3]
+            ),
+        };
+
+        let (value, advance) = get_next_value(advanced, &expression[cursor + 3..]);
+        cursor += 3 + advance;
+        operations.push(Operation { command, value });
+    }
+
+    (flatten_operations(advanced, &operations), cursor)
+}
+
+// The sum of every line, where advanced math evaluates + before *
+#[must_use]
+pub fn sum_expressions(text: &str, advanced: bool) -> i64 {
+    LineReader::from_text(text)
+        .map(|line| evaluate_expression(advanced, &line).0)
+        .sum()
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    fn part1(&self, input: &str) -> String {
+        sum_expressions(input, false).to_string()
+    }
+
+    fn part2(&self, input: &str) -> String {
+        sum_expressions(input, true).to_string()
+    }
+}
@@ -0,0 +1,136 @@
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(clippy::missing_panics_doc)]
+
+use common::{LineReader, Solution};
+
+#[derive(Clone, Debug)]
+enum Rule {
+    Indirect(Vec<Vec<u8>>),
+    Direct(String),
+}
+
+struct MessageValidator {
+    rules: Vec<Rule>,
+    // Whether rules 8 and 11 are replaced with the looping ones from part 2
+    loops: bool,
+}
+
+impl MessageValidator {
+    fn new(loops: bool) -> Self {
+        Self {
+            rules: vec![Rule::Indirect(Vec::new()); 256],
+            loops,
+        }
+    }
+
+    fn parse_indirect(indirect: &str) -> Vec<Vec<u8>> {
+        let split = indirect.split(" | ");
+        let indirect: Vec<Vec<u8>> = split
+            .map(|alternative| {
+                let alternative = alternative.trim();
+                let split = alternative.split(' ');
+                let children: Vec<u8> = split
+                    .map(|child| child.parse::<u8>().expect("Failed to fit child in u8"))
+                    .collect();
+                children
+            })
+            .collect();
+        indirect
+    }
+
+    fn add_rule(&mut self, rule: &str) {
+        let mut split = rule.split(':');
+
+        let id: u8 = split
+            .next()
+            .expect("Failed to find ID in split")
+            .parse()
+            .expect("Failed to parse rule ID");
+
+        if self.loops && id == 8 {
+            self.rules[8] = Rule::Indirect(vec![vec![42], vec![42, 8]]);
+            return;
+        } else if self.loops && id == 11 {
+            self.rules[11] = Rule::Indirect(vec![vec![42, 31], vec![42, 11, 31]]);
+            return;
+        }
+
+        let contents = split.next().expect("Failed to find rule").trim();
+        self.rules[id as usize] = match &contents[0..=0] {
+            "\"" => Rule::Direct(String::from(&contents[1..=1])),
+            _ => Rule::Indirect(MessageValidator::parse_indirect(contents)),
+        };
+    }
+
+    fn message_matches_rule(&self, rule: &Rule, message: &str) -> Vec<usize> {
+        if message.is_empty() {
+            return Vec::new();
+        }
+
+        match rule {
+            Rule::Direct(string) => {
+                if &message[0..string.len()] == string {
+                    vec![1]
+                } else {
+                    Vec::new()
+                }
+            }
+            Rule::Indirect(alternatives) => {
+                let mut lengths = Vec::new();
+
+                for alternative in alternatives {
+                    let mut cursors = vec![0];
+                    for child_id in alternative {
+                        let mut new_cursors = Vec::new();
+                        for cursor in cursors {
+                            let lengths = self.message_matches_rule(
+                                &self.rules[(*child_id) as usize],
+                                &message[cursor..],
+                            );
+                            for length in lengths {
+                                new_cursors.push(cursor + length);
+                            }
+                        }
+                        cursors = new_cursors;
+                        if cursors.is_empty() {
+                            break;
+                        }
+                    }
+
+                    lengths.append(&mut cursors);
+                }
+
+                lengths
+            }
+        }
+    }
+
+    fn message_is_valid(&self, message: &str) -> bool {
+        let match_lengths = self.message_matches_rule(&self.rules[0], message);
+        match_lengths.contains(&message.len())
+    }
+}
+
+#[must_use]
+pub fn count_valid_messages(text: &str, loops: bool) -> usize {
+    let mut lines = LineReader::from_text(text);
+
+    let mut validator = MessageValidator::new(loops);
+    lines.read_with(|rule| validator.add_rule(rule));
+
+    lines
+        .filter(|message| validator.message_is_valid(message))
+        .count()
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    fn part1(&self, input: &str) -> String {
+        count_valid_messages(input, false).to_string()
+    }
+
+    fn part2(&self, input: &str) -> String {
+        count_valid_messages(input, true).to_string()
+    }
+}
@@ -0,0 +1,173 @@
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(clippy::missing_panics_doc)]
+
+use std::collections::{HashMap, HashSet};
+
+use common::{counter::Counter, LineReader, Solution};
+
+pub struct AllergenTracker {
+    candidate_ingredients: HashMap<String, HashSet<String>>,
+    ingredient_counts: Counter<String>,
+}
+
+impl AllergenTracker {
+    fn new() -> Self {
+        Self {
+            candidate_ingredients: HashMap::new(),
+            ingredient_counts: Counter::new(),
+        }
+    }
+
+    // Reads every food and narrows each allergen down to one ingredient
+    #[must_use]
+    pub fn from_text(text: &str) -> Self {
+        let mut tracker = Self::new();
+
+        let mut reader = LineReader::from_text(text);
+        reader.read_with(|line| tracker.add_food(line));
+
+        tracker.collapse_known_allergens();
+        tracker
+    }
+
+    fn add_food(&mut self, line: &str) {
+        let mut split = line.split('(');
+
+        let ingredients: HashSet<String> = split
+            .next()
+            .expect("Failed to find ingredients")
+            .trim()
+            .split(' ')
+            .map(String::from)
+            .collect();
+
+        self.ingredient_counts.add_all(ingredients.iter().cloned());
+
+        let allergens: Vec<String> = split
+            .next()
+            .expect("Failed to find allergens")
+            .trim_start_matches("contains ")
+            .trim_end_matches(')')
+            .split(", ")
+            .map(String::from)
+            .collect();
+
+        for allergen in allergens {
+            match self.candidate_ingredients.get_mut(&allergen) {
+                Some(candidate_ingredients) => {
+                    *candidate_ingredients = candidate_ingredients
+                        .intersection(&ingredients)
+                        .cloned()
+                        .collect();
+                }
+                None => {
+                    self.candidate_ingredients
+                        .insert(allergen.clone(), ingredients.clone());
+                }
+            }
+        }
+    }
+
+    fn collapse_known_allergens(&mut self) {
+        let mut changed = true;
+        while changed {
+            let known_allergens: HashSet<String> = self
+                .candidate_ingredients
+                .values()
+                .filter_map(|ingredients| {
+                    if ingredients.len() == 1 {
+                        Some(
+                            ingredients
+                                .iter()
+                                .next()
+                                .expect("Failed to get only element")
+                                .clone(),
+                        )
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+
+            changed = false;
+            for ingredients in self.candidate_ingredients.values_mut() {
+                if ingredients.len() > 1 {
+                    *ingredients = ingredients.difference(&known_allergens).cloned().collect();
+
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn get_safe_ingredient_count(&self) -> usize {
+        let allergens: HashSet<String> = self
+            .candidate_ingredients
+            .values()
+            .map(|ingredients| {
+                ingredients
+                    .iter()
+                    .next()
+                    .expect("Failed to find only ingredient")
+                    .clone()
+            })
+            .collect();
+
+        self.ingredient_counts
+            .iter()
+            .map(|(ingredient, count)| {
+                if allergens.contains(ingredient) {
+                    0
+                } else {
+                    count
+                }
+            })
+            .sum()
+    }
+
+    #[must_use]
+    pub fn get_canonical_list(&self) -> String {
+        let mut allergens: Vec<(String, String)> = self
+            .candidate_ingredients
+            .iter()
+            .map(|(allergen, ingredients)| {
+                (
+                    ingredients
+                        .iter()
+                        .next()
+                        .expect("Failed to find only ingredient")
+                        .clone(),
+                    allergen.clone(),
+                )
+            })
+            .collect();
+
+        allergens.sort_by_key(|(_ingredient, allergen)| allergen.clone());
+
+        let allergens: Vec<String> = allergens
+            .iter()
+            .map(|(ingredient, _allergen)| ingredient.clone())
+            .collect();
+        allergens.as_slice().join(",")
+    }
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    fn part1(&self, input: &str) -> String {
+        AllergenTracker::from_text(input)
+            .get_safe_ingredient_count()
+            .to_string()
+    }
+
+    fn part2(&self, input: &str) -> String {
+        AllergenTracker::from_text(input).get_canonical_list()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // use test::Bencher;
+}
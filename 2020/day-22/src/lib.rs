@@ -0,0 +1,177 @@
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+#![cfg_attr(feature = "nightly", feature(test))]
+
+#[cfg(feature = "nightly")]
+extern crate test;
+
+use std::{
+    collections::{hash_map::DefaultHasher, HashSet, VecDeque},
+    hash::{Hash, Hasher},
+};
+
+use common::{cache::Lru, normalize_text, Solution};
+
+fn compute_score(deck: &VecDeque<u8>) -> usize {
+    deck.iter()
+        .enumerate()
+        .map(|(index, card)| (deck.len() - index) * (*card as usize))
+        .sum()
+}
+
+#[must_use]
+pub fn play_basic_game(mut player1: VecDeque<u8>, mut player2: VecDeque<u8>) -> usize {
+    while !player1.is_empty() && !player2.is_empty() {
+        let card1 = player1.pop_front().unwrap();
+        let card2 = player2.pop_front().unwrap();
+
+        if card1 > card2 {
+            player1.push_back(card1);
+            player1.push_back(card2);
+        } else {
+            player2.push_back(card2);
+            player2.push_back(card1);
+        }
+    }
+
+    if player1.is_empty() {
+        compute_score(&player2)
+    } else {
+        compute_score(&player1)
+    }
+}
+
+// Winners of sub-games that have already been played, keyed by the starting
+// decks. Few sub-games repeat, so this is off unless asked for.
+pub type SubgameCache = Lru<(VecDeque<u8>, VecDeque<u8>), i8>;
+
+// The winner, and their score when needs_score is set
+pub fn play_recursive_game(
+    mut player1: VecDeque<u8>,
+    mut player2: VecDeque<u8>,
+    needs_score: bool,
+    cache: &mut Option<SubgameCache>,
+) -> (i8, usize) {
+    let mut previous_rounds = HashSet::new();
+
+    loop {
+        let hash = {
+            let mut hasher = DefaultHasher::new();
+            player1.hash(&mut hasher);
+            player2.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        if previous_rounds.contains(&hash) {
+            return (1, 0);
+        }
+
+        previous_rounds.insert(hash);
+
+        if !needs_score && player1.iter().max() > player2.iter().max() {
+            return (1, 0);
+        }
+
+        let card1 = player1.pop_front().unwrap();
+        let card2 = player2.pop_front().unwrap();
+
+        let winner = if player1.len() >= card1 as usize && player2.len() >= card2 as usize {
+            let decks: (VecDeque<u8>, VecDeque<u8>) = (
+                player1.iter().take(card1 as usize).copied().collect(),
+                player2.iter().take(card2 as usize).copied().collect(),
+            );
+            if let Some(winner) = cache.as_mut().and_then(|cache| cache.get(&decks)) {
+                *winner
+            } else {
+                let (winner, _) =
+                    play_recursive_game(decks.0.clone(), decks.1.clone(), false, cache);
+                if let Some(cache) = cache {
+                    cache.insert(decks, winner);
+                }
+                winner
+            }
+        } else if card1 > card2 {
+            1
+        } else {
+            2
+        };
+
+        match winner {
+            1 => {
+                player1.push_back(card1);
+                player1.push_back(card2);
+            }
+            2 => {
+                player2.push_back(card2);
+                player2.push_back(card1);
+            }
+            _ => panic!("Unexpected winner {}", winner),
+        }
+
+        if player1.is_empty() {
+            return (2, compute_score(&player2));
+        } else if player2.is_empty() {
+            return (1, compute_score(&player1));
+        }
+    }
+}
+
+// Decks are separated by a blank line, each under a "Player N:" header
+pub fn parse_decks(input: &str) -> Result<(VecDeque<u8>, VecDeque<u8>), String> {
+    let input = normalize_text(input);
+    let mut decks = input.trim().split("\n\n").map(|deck| {
+        deck.lines()
+            .skip(1)
+            .map(|line| {
+                line.parse::<u8>()
+                    .map_err(|_| format!("card [{line}] is not a number"))
+            })
+            .collect::<Result<VecDeque<_>, _>>()
+    });
+    let player1 = decks.next().ok_or("missing player 1's deck")??;
+    let player2 = decks.next().ok_or("missing player 2's deck")??;
+    Ok((player1, player2))
+}
+
+fn get_decks(input: &str) -> (VecDeque<u8>, VecDeque<u8>) {
+    parse_decks(input).unwrap_or_else(|error| panic!("Failed to parse decks: {}", error))
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    fn part1(&self, input: &str) -> String {
+        let (player1, player2) = get_decks(input);
+        play_basic_game(player1, player2).to_string()
+    }
+
+    fn part2(&self, input: &str) -> String {
+        let (player1, player2) = get_decks(input);
+        play_recursive_game(player1, player2, true, &mut None)
+            .1
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_basic_score(input: &str) -> usize {
+        let (player1, player2) = parse_decks(input).unwrap();
+        play_basic_game(player1, player2)
+    }
+
+    fn get_recursive_score(input: &str) -> usize {
+        let (player1, player2) = parse_decks(input).unwrap();
+        play_recursive_game(player1, player2, true, &mut None).1
+    }
+
+    common::assert_example! {
+        name: sample,
+        input: include_str!("../sample.txt"),
+        part1: get_basic_score => 306,
+        part2: get_recursive_score => 291,
+        bench,
+    }
+}
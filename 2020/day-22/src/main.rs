@@ -1,146 +1,71 @@
 #![deny(clippy::all, clippy::pedantic)]
-#![feature(test)]
 
-extern crate test;
-
-use std::{
-    collections::{hash_map::DefaultHasher, HashSet, VecDeque},
-    hash::{Hash, Hasher},
+use clap::{crate_name, value_t, App, Arg};
+use common::{
+    alloc,
+    cache::Lru,
+    cli::{self, Part},
+    input, timing,
 };
-
-use clap::{crate_name, App, Arg};
-use common::LineReader;
-
-fn compute_score(deck: &VecDeque<u8>) -> usize {
-    deck.iter()
-        .enumerate()
-        .map(|(index, card)| (deck.len() - index) * (*card as usize))
-        .sum()
-}
-
-fn play_basic_game(mut player1: VecDeque<u8>, mut player2: VecDeque<u8>) -> usize {
-    while !player1.is_empty() && !player2.is_empty() {
-        let card1 = player1.pop_front().unwrap();
-        let card2 = player2.pop_front().unwrap();
-
-        if card1 > card2 {
-            player1.push_back(card1);
-            player1.push_back(card2);
-        } else {
-            player2.push_back(card2);
-            player2.push_back(card1);
-        }
-    }
-
-    if player1.is_empty() {
-        compute_score(&player2)
-    } else {
-        compute_score(&player1)
-    }
-}
-
-fn play_recursive_game(
-    mut player1: VecDeque<u8>,
-    mut player2: VecDeque<u8>,
-    needs_score: bool,
-) -> (i8, usize) {
-    let mut previous_rounds = HashSet::new();
-
-    loop {
-        let hash = {
-            let mut hasher = DefaultHasher::new();
-            player1.hash(&mut hasher);
-            player2.hash(&mut hasher);
-            hasher.finish()
-        };
-
-        if previous_rounds.contains(&hash) {
-            return (1, 0);
-        }
-
-        previous_rounds.insert(hash);
-
-        if !needs_score && player1.iter().max() > player2.iter().max() {
-            return (1, 0);
-        }
-
-        let card1 = player1.pop_front().unwrap();
-        let card2 = player2.pop_front().unwrap();
-
-        let winner = if player1.len() >= card1 as usize && player2.len() >= card2 as usize {
-            let (winner, _) = play_recursive_game(
-                player1.iter().take(card1 as usize).copied().collect(),
-                player2.iter().take(card2 as usize).copied().collect(),
-                false,
-            );
-            winner
-        } else if card1 > card2 {
-            1
-        } else {
-            2
-        };
-
-        match winner {
-            1 => {
-                player1.push_back(card1);
-                player1.push_back(card2);
-            }
-            2 => {
-                player2.push_back(card2);
-                player2.push_back(card1);
-            }
-            _ => panic!("Unexpected winner {}", winner),
-        };
-
-        if player1.is_empty() {
-            return (2, compute_score(&player2));
-        } else if player2.is_empty() {
-            return (1, compute_score(&player1));
-        }
-    }
-}
+use day_22::{parse_decks, play_basic_game, play_recursive_game};
 
 fn main() {
     let args = App::new(crate_name!())
-        .arg(Arg::from_usage("<FILE>"))
-        .get_matches();
-
-    let mut reader = LineReader::new(args.value_of("FILE").unwrap());
-
-    let mut player1 = VecDeque::new();
-    reader.read_with(|line| {
-        if line.len() > 2 {
-            return;
-        }
-
-        player1.push_back(
-            line.parse::<u8>()
-                .unwrap_or_else(|_| panic!("Failed to parse {}", line)),
+        .arg(Arg::from_usage("[FILE]").required(!cfg!(feature = "embed-input")))
+        .arg(
+            Arg::from_usage("--part [PART] 'Which part to answer'")
+                .possible_values(&Part::NAMES)
+                .default_value("both"),
         )
-    });
-
-    let mut player2 = VecDeque::new();
-    reader.read_with(|line| {
-        if line.len() > 2 {
-            return;
-        }
-
-        player2.push_back(
-            line.parse::<u8>()
-                .unwrap_or_else(|_| panic!("Failed to parse {}", line)),
+        .arg(Arg::from_usage(
+            "--cache [SIZE] 'Remember the winners of up to SIZE sub-games'",
+        ))
+        .arg(Arg::from_usage("--stats 'Print sub-game cache statistics'").requires("cache"))
+        .arg(Arg::from_usage(
+            "--repeat [N] 'After one warmup, solve N more times and write min, mean and stddev to stderr'",
+        ))
+        .arg(Arg::from_usage(
+            "--timing 'Write a breakdown of where the time went to stderr'",
+        ))
+        .arg(Arg::from_usage(
+            "--trace-out [FILE] 'Write a Chrome trace of parsing and each part to FILE'",
+        ))
+        .get_matches();
+    let _trace = args.value_of("trace-out").map(timing::trace_to);
+
+    let filename = common::resolve_input!(args.value_of("FILE"));
+    let part = value_t!(args, "part", Part).unwrap_or_else(|error| error.exit());
+    let repeat = args
+        .value_of("repeat")
+        .map(|runs| runs.parse().expect("Failed to parse repeat count"));
+    let mut cache = args.value_of("cache").map(|capacity| {
+        Lru::new(
+            capacity
+                .parse()
+                .unwrap_or_else(|_| panic!("Failed to parse cache size {}", capacity)),
         )
     });
 
-    println!(
-        "Basic game score: {}",
-        play_basic_game(player1.clone(), player2.clone())
+    let text = input::read_to_string(filename)
+        .unwrap_or_else(|_| panic!("Failed to read file {}", filename));
+    cli::run_instances(
+        part,
+        repeat,
+        &text,
+        parse_decks,
+        |(player1, player2)| play_basic_game(player1.clone(), player2.clone()),
+        |(player1, player2)| {
+            play_recursive_game(player1.clone(), player2.clone(), true, &mut cache).1
+        },
     );
 
-    let (_winner, score) = play_recursive_game(player1, player2, true);
-    println!("Recursive game score: {}", score);
-}
+    if args.is_present("stats") {
+        let cache = cache.expect("Failed to find sub-game cache");
+        println!("Sub-game cache: {}", cache.stats());
+    }
 
-#[cfg(test)]
-mod tests {
-    // use test::Bencher;
+    if args.is_present("timing") {
+        eprintln!("Allocator: {}", alloc::NAME);
+        eprint!("{}", timing::report());
+    }
 }
@@ -0,0 +1,136 @@
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(clippy::missing_panics_doc)]
+
+use std::convert::TryFrom;
+
+use common::Solution;
+
+// The moves and cups part 2 is played with
+pub const PART2_MOVES: usize = 10_000_000;
+pub const PART2_CUPS: u32 = 1_000_000;
+
+// A circle of cups labeled 1 to the cup count, stored as the label of the cup
+// clockwise of each one
+pub struct Cups {
+    next_cup: Vec<u32>,
+    cup_count: u32,
+    current: u32,
+}
+
+impl Cups {
+    // The labels given come first, followed by the rest in increasing order
+    #[must_use]
+    pub fn new(labels: &str, cup_count: u32) -> Self {
+        let mut next_cup = vec![0; cup_count as usize + 1];
+
+        let mut max = 0;
+        let mut head = 0;
+        let mut tail = 0;
+        for value in labels.chars().map(|character| {
+            String::from(character)
+                .parse::<u32>()
+                .expect("Failed to parse cup as u8")
+        }) {
+            max = max.max(value);
+            if head == 0 {
+                head = value;
+            }
+            if tail != 0 {
+                next_cup[tail as usize] = value;
+            }
+            tail = value;
+        }
+
+        for value in max + 1..=cup_count {
+            next_cup[tail as usize] = value;
+            tail = value;
+        }
+
+        // Complete the circular list
+        next_cup[tail as usize] = head;
+
+        Self {
+            next_cup,
+            cup_count,
+            current: head,
+        }
+    }
+
+    pub fn play(&mut self, moves: usize) {
+        let cup_count = self.cup_count;
+        let next_cup = &mut self.next_cup;
+        let mut current = self.current;
+        for _ in 0..moves {
+            let mut pick_cursor = current;
+            let mut picked = [0; 3];
+            for pick in &mut picked {
+                pick_cursor = next_cup[pick_cursor as usize];
+                *pick = pick_cursor;
+            }
+            next_cup[current as usize] = next_cup[pick_cursor as usize];
+
+            let mut destination = (current + cup_count - 2) % cup_count + 1;
+            while picked.contains(&destination) {
+                destination = (destination + cup_count - 2) % cup_count + 1;
+            }
+
+            let destination_next = next_cup[destination as usize];
+            next_cup[destination as usize] = picked[0];
+            next_cup[picked[picked.len() - 1] as usize] = destination_next;
+
+            current = next_cup[current as usize];
+        }
+        self.current = current;
+    }
+
+    // Every cup after cup 1, clockwise
+    pub fn after_one(&self) -> impl Iterator<Item = u32> + '_ {
+        let mut cup = 1;
+        (1..self.cup_count).map(move |_| {
+            cup = self.next_cup[cup as usize];
+            cup
+        })
+    }
+}
+
+fn get_labels(input: &str) -> &str {
+    input.trim()
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    fn part1(&self, input: &str) -> String {
+        let labels = get_labels(input);
+        let mut cups = Cups::new(labels, u32::try_from(labels.len()).unwrap());
+        cups.play(100);
+        cups.after_one().map(|cup| cup.to_string()).collect()
+    }
+
+    fn part2(&self, input: &str) -> String {
+        let mut cups = Cups::new(get_labels(input), PART2_CUPS);
+        cups.play(PART2_MOVES);
+        cups.after_one()
+            .take(2)
+            .map(u64::from)
+            .product::<u64>()
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    // use test::Bencher;
+
+    #[test]
+    fn sample_after_ten_and_a_hundred_moves() {
+        let mut cups = Cups::new("389125467", 9);
+        cups.play(10);
+        assert_eq!(
+            cups.after_one().collect::<Vec<_>>(),
+            [9, 2, 6, 5, 8, 3, 7, 4]
+        );
+        assert_eq!(Solver.part1("389125467\n"), "67384529");
+    }
+}
@@ -0,0 +1,154 @@
+use bit_set::BitSet;
+
+use super::{
+    get_bounds, get_center, is_near_edge, translate, widen, AddressCodec, HexLife, Rule,
+    ADJACENT_OFFSETS,
+};
+
+// Copies of the floor stacked on top of each other, where each tile touches
+// the six around it in its own layer and the tiles directly above and below
+// it. The flipped floor is the bottom layer and the rest start out white.
+// Nothing lies past the top and bottom layers, so a single layer evolves just
+// like the floor on its own.
+pub struct Layers {
+    floors: Vec<BitSet>,
+    rule: Rule,
+    generation: usize,
+}
+
+// Every tile adjacent to the given one, as (layer, address) pairs
+fn get_adjacent_tiles(
+    layer: usize,
+    tile: usize,
+    layer_count: usize,
+) -> impl Iterator<Item = (usize, usize)> {
+    let below = layer.checked_sub(1);
+    let above = Some(layer + 1).filter(|above| *above < layer_count);
+    ADJACENT_OFFSETS
+        .iter()
+        .map(move |offset| (layer, tile.wrapping_add_signed(*offset)))
+        .chain(
+            below
+                .into_iter()
+                .chain(above)
+                .map(move |other| (other, tile)),
+        )
+}
+
+impl Layers {
+    // The rule should come from Rule::new_layered if it uses counts above six
+    #[must_use]
+    pub fn new(floor: &HexLife, layer_count: usize, rule: Rule) -> Self {
+        assert!(layer_count > 0, "There has to be at least one layer");
+        let mut floors = vec![BitSet::new(); layer_count];
+        floors[0].clone_from(
+            floor
+                .get_black_tiles()
+                .expect("Failed to stack layers on a floor in unbounded storage"),
+        );
+        Self {
+            floors,
+            rule,
+            generation: 0,
+        }
+    }
+
+    // All of the layers move together, so tiles stay lined up with the ones
+    // above and below them
+    fn recenter(&mut self) {
+        let all_tiles = self.floors.iter().fold(BitSet::new(), |mut all, layer| {
+            all.union_with(layer);
+            all
+        });
+        if !is_near_edge(&all_tiles) {
+            return;
+        }
+        let (min, max) = get_bounds(&all_tiles).unwrap();
+        let center = get_center(widen(min), widen(max));
+        let generation = self.generation;
+        for layer in &mut self.floors {
+            *layer = translate(layer, center).unwrap_or_else(|error| {
+                panic!(
+                    "Pattern has outgrown the coordinate space in generation {}: {}",
+                    generation, error
+                )
+            });
+        }
+    }
+
+    // Works like evolve_tiles, with a count array for each layer
+    pub fn step(&mut self) {
+        let _scope = common::scope!("generation");
+        self.generation += 1;
+        self.recenter();
+
+        let layer_count = self.floors.len();
+        let tiles: Vec<Vec<usize>> = self
+            .floors
+            .iter()
+            .map(|layer| layer.iter().collect())
+            .collect();
+        let Some(last_tile) = tiles.iter().filter_map(|tiles| tiles.last()).max() else {
+            return;
+        };
+
+        // The largest offset is two units east
+        let mut counts = vec![vec![0_u8; last_tile + (2 << AddressCodec::BITS) + 1]; layer_count];
+        for (layer, tiles) in tiles.iter().enumerate() {
+            for tile in tiles {
+                for (adjacent_layer, adjacent_tile) in get_adjacent_tiles(layer, *tile, layer_count)
+                {
+                    counts[adjacent_layer][adjacent_tile] += 1;
+                }
+            }
+        }
+
+        let mut tiles_to_flip: Vec<(usize, usize)> = tiles
+            .iter()
+            .enumerate()
+            .flat_map(|(layer, tiles)| tiles.iter().map(move |tile| (layer, *tile)))
+            .filter(|(layer, tile)| {
+                self.rule
+                    .should_flip(true, usize::from(counts[*layer][*tile]))
+            })
+            .collect();
+
+        for (layer, tiles) in tiles.iter().enumerate() {
+            for tile in tiles {
+                for (adjacent_layer, adjacent_tile) in get_adjacent_tiles(layer, *tile, layer_count)
+                {
+                    let count = std::mem::take(&mut counts[adjacent_layer][adjacent_tile]);
+                    if count > 0
+                        && !self.floors[adjacent_layer].contains(adjacent_tile)
+                        && self.rule.should_flip(false, usize::from(count))
+                    {
+                        tiles_to_flip.push((adjacent_layer, adjacent_tile));
+                    }
+                }
+            }
+        }
+
+        for (layer, tile) in tiles_to_flip {
+            if !self.floors[layer].remove(tile) {
+                self.floors[layer].insert(tile);
+            }
+        }
+    }
+
+    pub fn run(&mut self, generations: usize) {
+        for _ in 0..generations {
+            self.step();
+        }
+    }
+
+    #[must_use]
+    pub fn black_count(&self) -> usize {
+        self.floors.iter().map(BitSet::len).sum()
+    }
+
+    // From the bottom layer up
+    #[must_use]
+    pub fn get_layer_counts(&self) -> Vec<usize> {
+        self.floors.iter().map(BitSet::len).collect()
+    }
+}
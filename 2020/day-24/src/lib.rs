@@ -0,0 +1,2016 @@
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+#![cfg_attr(feature = "nightly", feature(test))]
+
+#[cfg(feature = "nightly")]
+extern crate test;
+
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    convert::{TryFrom, TryInto},
+    error::Error,
+    fmt::{Display, Formatter},
+    hash::{Hash, Hasher},
+    time::{Duration, Instant},
+};
+
+use bit_set::BitSet;
+use common::{gen::Rng, grid::Grid, hex::Cube, normalize_line, Solution};
+
+pub mod layers;
+
+// Evolving a generation looks up to two tiles beyond the current pattern, and
+// each tile is two units wide, so this keeps every lookup inside the packed range
+const RECENTER_MARGIN: i16 = 8;
+
+enum Direction {
+    East,
+    Southeast,
+    Southwest,
+    West,
+    Northwest,
+    Northeast,
+}
+
+impl Direction {
+    fn from_index(index: usize) -> Self {
+        match index {
+            0 => Direction::East,
+            1 => Direction::Southeast,
+            2 => Direction::Southwest,
+            3 => Direction::West,
+            4 => Direction::Northwest,
+            5 => Direction::Northeast,
+            _ => panic!("Unexpected direction index {}", index),
+        }
+    }
+
+    // How far a step moves along each axis, in coordinate units
+    fn get_offset(&self) -> (i16, i16) {
+        match self {
+            Direction::East => (2, 0),
+            Direction::Southeast => (1, -2),
+            Direction::Southwest => (-1, -2),
+            Direction::West => (-2, 0),
+            Direction::Northwest => (-1, 2),
+            Direction::Northeast => (1, 2),
+        }
+    }
+}
+
+// Unit steps and their names, in Direction order
+const CUBE_STEPS: [Cube; 6] = [
+    Cube { x: 1, y: -1, z: 0 },
+    Cube { x: 0, y: -1, z: 1 },
+    Cube { x: -1, y: 0, z: 1 },
+    Cube { x: -1, y: 1, z: 0 },
+    Cube { x: 0, y: 1, z: -1 },
+    Cube { x: 1, y: 0, z: -1 },
+];
+const DIRECTION_NAMES: [&str; 6] = ["e", "se", "sw", "w", "nw", "ne"];
+
+// What's wrong with a path. Columns count from 1.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PathError {
+    UnexpectedCharacter {
+        column: usize,
+        character: char,
+    },
+    // A north or south step has to say which way it leans, so 'n' and 's' can
+    // only be followed by 'e' or 'w'. found is None at the end of the line.
+    IncompleteDirection {
+        column: usize,
+        first: char,
+        found: Option<char>,
+    },
+}
+
+impl Display for PathError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathError::UnexpectedCharacter { column, character } => {
+                write!(f, "column {column}: unexpected character {character:?}")
+            }
+            PathError::IncompleteDirection {
+                column,
+                first,
+                found: Some(found),
+            } => write!(
+                f,
+                "column {column}: expected 'e' or 'w' after '{first}', found {found:?}"
+            ),
+            PathError::IncompleteDirection {
+                column,
+                first,
+                found: None,
+            } => write!(
+                f,
+                "column {column}: expected 'e' or 'w' after '{first}', found the end of the line"
+            ),
+        }
+    }
+}
+
+impl Error for PathError {}
+
+// A path error along with which of the instructions it was in, counting from 1
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InstructionError {
+    pub line: usize,
+    pub error: PathError,
+}
+
+impl Display for InstructionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+impl Error for InstructionError {}
+
+// Walks the bytes of a line, skipping whitespace between directions. Every
+// direction is ASCII, so the cursor is always on a character boundary and
+// doubles as the column. Nothing more is returned after an error.
+struct DirectionIterator<'a> {
+    line: &'a str,
+    cursor: usize,
+}
+
+impl<'a> DirectionIterator<'a> {
+    fn new(line: &'a str) -> Self {
+        Self { line, cursor: 0 }
+    }
+
+    fn get_character(&self, index: usize) -> Option<char> {
+        self.line.get(index..)?.chars().next()
+    }
+
+    fn fail(&mut self, error: PathError) -> Result<Direction, PathError> {
+        self.cursor = self.line.len();
+        Err(error)
+    }
+}
+
+impl Iterator for DirectionIterator<'_> {
+    type Item = Result<Direction, PathError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.line.as_bytes();
+        while bytes.get(self.cursor).is_some_and(u8::is_ascii_whitespace) {
+            self.cursor += 1;
+        }
+
+        let first = *bytes.get(self.cursor)?;
+        let direction = match (first, bytes.get(self.cursor + 1)) {
+            (b'e', _) => Direction::East,
+            (b'w', _) => Direction::West,
+            (b's', Some(b'e')) => Direction::Southeast,
+            (b's', Some(b'w')) => Direction::Southwest,
+            (b'n', Some(b'w')) => Direction::Northwest,
+            (b'n', Some(b'e')) => Direction::Northeast,
+            (b's' | b'n', _) => {
+                return Some(self.fail(PathError::IncompleteDirection {
+                    column: self.cursor + 2,
+                    first: char::from(first),
+                    found: self.get_character(self.cursor + 1),
+                }));
+            }
+            _ => {
+                return Some(self.fail(PathError::UnexpectedCharacter {
+                    column: self.cursor + 1,
+                    character: self.get_character(self.cursor).unwrap(),
+                }));
+            }
+        };
+
+        self.cursor += match direction {
+            Direction::East | Direction::West => 1,
+            _ => 2,
+        };
+        Some(Ok(direction))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Coordinate {
+    pub x: i16,
+    pub y: i16,
+}
+
+impl Coordinate {
+    fn new() -> Self {
+        Self { x: 0, y: 0 }
+    }
+
+    // Addresses in a set of black tiles always came from AddressCodec::encode,
+    // so failing to decode one is a bug rather than bad input
+    #[must_use]
+    pub fn from_address(address: usize) -> Self {
+        AddressCodec::decode(address).expect("Failed to decode tile address")
+    }
+
+    fn step(&mut self, direction: &Direction) {
+        let (x, y) = direction.get_offset();
+        self.x += x;
+        self.y += y;
+    }
+}
+
+// Why a coordinate has no address, or an address no coordinate. Coordinates
+// are widened so that positions on the unbounded floor can be reported too.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AddressError {
+    OutOfRange { x: i64, y: i64 },
+    PastEnd(usize),
+}
+
+impl Display for AddressError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AddressError::OutOfRange { x, y } => write!(
+                f,
+                "tile ({x}, {y}) is outside the address space, which spans {}..={} on each axis",
+                AddressCodec::MIN,
+                AddressCodec::MAX
+            ),
+            AddressError::PastEnd(address) => {
+                write!(f, "address {address} is past the end of the address space")
+            }
+        }
+    }
+}
+
+impl Error for AddressError {}
+
+// Packs a coordinate into a tile address, with x in the high bits and y in the
+// low ones. Each axis covers MIN..=MAX, which bounds how far the pattern can
+// spread before it has to be moved back to the origin, and every address below
+// END decodes to exactly one coordinate in that range.
+pub struct AddressCodec;
+
+impl AddressCodec {
+    pub const BITS: u32 = 10;
+    pub const MIN: i16 = -(1 << (Self::BITS - 1));
+    pub const MAX: i16 = (1 << (Self::BITS - 1)) - 1;
+    pub const END: usize = 1 << (2 * Self::BITS);
+    const MASK: usize = (1 << Self::BITS) - 1;
+
+    pub fn encode(coordinate: Coordinate) -> Result<usize, AddressError> {
+        Self::encode_position(i64::from(coordinate.x), i64::from(coordinate.y))
+    }
+
+    fn encode_position(x: i64, y: i64) -> Result<usize, AddressError> {
+        let pack = |value: i64| {
+            usize::try_from(value - i64::from(Self::MIN))
+                .ok()
+                .filter(|packed| *packed <= Self::MASK)
+        };
+        match (pack(x), pack(y)) {
+            (Some(packed_x), Some(packed_y)) => Ok(packed_x << Self::BITS | packed_y),
+            _ => Err(AddressError::OutOfRange { x, y }),
+        }
+    }
+
+    pub fn decode(address: usize) -> Result<Coordinate, AddressError> {
+        if address >= Self::END {
+            return Err(AddressError::PastEnd(address));
+        }
+        let unpack = |packed: usize| i16::try_from(packed).unwrap() + Self::MIN;
+        Ok(Coordinate {
+            x: unpack(address >> Self::BITS),
+            y: unpack(address & Self::MASK),
+        })
+    }
+}
+
+// Like Coordinate::step, but on the unbounded floor
+fn step_position(position: (i64, i64), direction: &Direction) -> (i64, i64) {
+    let (x, y) = direction.get_offset();
+    (position.0 + i64::from(x), position.1 + i64::from(y))
+}
+
+// Walks the line in i64 rather than as a Coordinate, since a long enough line
+// ends up further away than an i16 can reach
+fn get_coordinate(line: &str) -> Result<(i64, i64), PathError> {
+    DirectionIterator::new(line).try_fold((0, 0), |position, direction| {
+        Ok(step_position(position, &direction?))
+    })
+}
+
+// Instruction lines for random walks of the given length from the reference
+// tile, drawing directions in the same order as HexLife::seed_random_walks, so
+// flipping them gives the same floor for the same seed
+pub fn random_walks(walks: usize, length: usize, seed: u64) -> impl Iterator<Item = String> {
+    let mut rng = Rng::new(seed);
+    (0..walks).map(move |_| {
+        (0..length)
+            .map(|_| DIRECTION_NAMES[usize::try_from(rng.below(6)).unwrap()])
+            .collect()
+    })
+}
+
+// Returns the shortest sequence of directions that ends on the same tile as the
+// given line, along with its length
+pub fn reduce_path(line: &str) -> Result<(String, i64), PathError> {
+    let target = DirectionIterator::new(line).try_fold(Cube::ORIGIN, |cube, direction| {
+        Ok(cube + CUBE_STEPS[direction? as usize])
+    })?;
+    let path = target
+        .get_shortest_path(&CUBE_STEPS)
+        .iter()
+        .map(|index| DIRECTION_NAMES[*index])
+        .collect();
+    Ok((path, target.length()))
+}
+
+fn get_adjacent_tiles(coordinate: Coordinate) -> [Coordinate; 6] {
+    let mut adjacent_tiles = [coordinate; 6];
+    for (index, direction) in (0..6).map(Direction::from_index).enumerate() {
+        adjacent_tiles[index].step(&direction);
+    }
+    adjacent_tiles
+}
+
+// Address deltas of the six neighbors in Direction order. The re-centering
+// margin keeps every neighbor inside the packed range, so adding one of these
+// never carries between the x and y fields.
+const ADJACENT_OFFSETS: [isize; 6] = [
+    2 << AddressCodec::BITS,
+    (1 << AddressCodec::BITS) - 2,
+    -(1 << AddressCodec::BITS) - 2,
+    -(2 << AddressCodec::BITS),
+    -(1 << AddressCodec::BITS) + 2,
+    (1 << AddressCodec::BITS) + 2,
+];
+
+const HEX_NEIGHBORS: usize = 6;
+// Tiles in stacked layers also touch the tiles directly above and below them
+pub const PRISM_NEIGHBORS: usize = HEX_NEIGHBORS + 2;
+
+// Why a rule can't be built from the neighbor counts it was given
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RuleError {
+    SurvivalOutOfRange { count: usize, neighbors: usize },
+    BirthOutOfRange { count: usize, neighbors: usize },
+    // Only white tiles next to black ones are ever examined, so a birth count
+    // of 0 can't be followed across the whole floor
+    ZeroBirth,
+}
+
+impl Display for RuleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleError::SurvivalOutOfRange { count, neighbors } => write!(
+                f,
+                "survival count {count} is out of range, since a tile has {neighbors} neighbors"
+            ),
+            RuleError::BirthOutOfRange { count, neighbors } => write!(
+                f,
+                "birth count {count} is out of range, since a tile has {neighbors} neighbors"
+            ),
+            RuleError::ZeroBirth => {
+                write!(f, "a birth count of 0 would turn the whole floor black")
+            }
+        }
+    }
+}
+
+impl Error for RuleError {}
+
+// Indexed by the number of adjacent black tiles, which ranges from 0 to 6 on a
+// single floor, or to PRISM_NEIGHBORS once floors are stacked
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Rule {
+    survive: [bool; PRISM_NEIGHBORS + 1],
+    birth: [bool; PRISM_NEIGHBORS + 1],
+}
+
+impl Rule {
+    pub fn new(survive: &[usize], birth: &[usize]) -> Result<Self, RuleError> {
+        Self::with_neighbors(survive, birth, HEX_NEIGHBORS)
+    }
+
+    // For layers::Layers, where counts can reach PRISM_NEIGHBORS
+    pub fn new_layered(survive: &[usize], birth: &[usize]) -> Result<Self, RuleError> {
+        Self::with_neighbors(survive, birth, PRISM_NEIGHBORS)
+    }
+
+    fn with_neighbors(
+        survive: &[usize],
+        birth: &[usize],
+        neighbors: usize,
+    ) -> Result<Self, RuleError> {
+        let mut rule = Self {
+            survive: [false; PRISM_NEIGHBORS + 1],
+            birth: [false; PRISM_NEIGHBORS + 1],
+        };
+        for count in survive {
+            if *count > neighbors {
+                return Err(RuleError::SurvivalOutOfRange {
+                    count: *count,
+                    neighbors,
+                });
+            }
+            rule.survive[*count] = true;
+        }
+        for count in birth {
+            if *count == 0 {
+                return Err(RuleError::ZeroBirth);
+            }
+            if *count > neighbors {
+                return Err(RuleError::BirthOutOfRange {
+                    count: *count,
+                    neighbors,
+                });
+            }
+            rule.birth[*count] = true;
+        }
+        Ok(rule)
+    }
+
+    // The same rule in Golly's notation, with H for the hexagonal neighborhood
+    #[must_use]
+    pub fn get_golly_name(&self) -> String {
+        let counts = |flags: &[bool; PRISM_NEIGHBORS + 1]| -> String {
+            (0_u8..)
+                .zip(flags)
+                .filter(|(_, flag)| **flag)
+                .map(|(count, _)| char::from(b'0' + count))
+                .collect()
+        };
+        format!("B{}/S{}H", counts(&self.birth), counts(&self.survive))
+    }
+
+    fn should_flip(&self, is_black: bool, adjacent_black_tile_count: usize) -> bool {
+        if is_black {
+            !self.survive[adjacent_black_tile_count]
+        } else {
+            self.birth[adjacent_black_tile_count]
+        }
+    }
+}
+
+// Black tiles with one or two black neighbors stay black, and white tiles with
+// exactly two black neighbors turn black
+impl Default for Rule {
+    fn default() -> Self {
+        Self::new(&[1, 2], &[2]).expect("Failed to build the puzzle's rule")
+    }
+}
+
+// Rather than probing the set six times per candidate, every black tile adds
+// one to each of its neighbors in a flat count array, which leaves the black
+// neighbor count of every tile that could change sitting in that array
+//
+// The count array is kept between generations and is all zeroes between calls.
+// It only grows when the pattern reaches a higher address than before.
+//
+// Returns the number of white tiles that were candidates to turn black and the
+// number of tiles that flipped
+fn evolve_tiles(black_tiles: &mut BitSet, counts: &mut Vec<u8>, rule: &Rule) -> (usize, usize) {
+    let tiles: Vec<usize> = black_tiles.iter().collect();
+    let Some(last_tile) = tiles.last() else {
+        return (0, 0);
+    };
+
+    let count_scope = common::scope!("count neighbors");
+    // The largest offset is two units east
+    let length = last_tile + (2 << AddressCodec::BITS) + 1;
+    if counts.len() < length {
+        counts.resize(length, 0);
+    }
+    for tile in &tiles {
+        for offset in &ADJACENT_OFFSETS {
+            counts[tile.wrapping_add_signed(*offset)] += 1;
+        }
+    }
+
+    let mut tiles_to_flip: Vec<usize> = tiles
+        .iter()
+        .copied()
+        .filter(|tile| rule.should_flip(true, usize::from(counts[*tile])))
+        .collect();
+
+    // Counts are cleared as they're read so that white tiles next to several
+    // black tiles are only considered once. Every count that was added to sits
+    // next to a black tile, so this also leaves the array zeroed for next time.
+    let mut candidates = 0;
+    for tile in &tiles {
+        for offset in &ADJACENT_OFFSETS {
+            let adjacent_tile = tile.wrapping_add_signed(*offset);
+            let count = std::mem::take(&mut counts[adjacent_tile]);
+            if count > 0 && !black_tiles.contains(adjacent_tile) {
+                candidates += 1;
+                if rule.should_flip(false, usize::from(count)) {
+                    tiles_to_flip.push(adjacent_tile);
+                }
+            }
+        }
+    }
+
+    drop(count_scope);
+
+    let _flip_scope = common::scope!("apply flips");
+    let flips = tiles_to_flip.len();
+    for tile_to_flip in tiles_to_flip {
+        if !black_tiles.remove(tile_to_flip) {
+            black_tiles.insert(tile_to_flip);
+        }
+    }
+    (candidates, flips)
+}
+
+// Used once the pattern has outgrown the address space, counting neighbors in
+// a map keyed by position instead of a flat array. Returns the same counts as
+// evolve_tiles.
+fn evolve_unbounded(black_tiles: &mut HashSet<(i64, i64)>, rule: &Rule) -> (usize, usize) {
+    let offsets = get_adjacent_tiles(Coordinate::new()).map(widen);
+    let mut counts: HashMap<(i64, i64), usize> = HashMap::new();
+    for tile in black_tiles.iter() {
+        for offset in &offsets {
+            *counts
+                .entry((tile.0 + offset.0, tile.1 + offset.1))
+                .or_default() += 1;
+        }
+    }
+
+    let mut tiles_to_flip: Vec<(i64, i64)> = black_tiles
+        .iter()
+        .copied()
+        .filter(|tile| rule.should_flip(true, counts.get(tile).copied().unwrap_or(0)))
+        .collect();
+    let mut candidates = 0;
+    for (tile, count) in &counts {
+        if !black_tiles.contains(tile) {
+            candidates += 1;
+            if rule.should_flip(false, *count) {
+                tiles_to_flip.push(*tile);
+            }
+        }
+    }
+
+    let flips = tiles_to_flip.len();
+    for tile_to_flip in tiles_to_flip {
+        toggle_position(black_tiles, tile_to_flip);
+    }
+    (candidates, flips)
+}
+
+fn toggle_position(black_tiles: &mut HashSet<(i64, i64)>, position: (i64, i64)) {
+    if !black_tiles.remove(&position) {
+        black_tiles.insert(position);
+    }
+}
+
+fn get_bounds(black_tiles: &BitSet) -> Option<(Coordinate, Coordinate)> {
+    let mut coordinates = black_tiles.iter().map(Coordinate::from_address);
+    let first = coordinates.next()?;
+    Some(coordinates.fold((first, first), |(min, max), coordinate| {
+        (
+            Coordinate {
+                x: min.x.min(coordinate.x),
+                y: min.y.min(coordinate.y),
+            },
+            Coordinate {
+                x: max.x.max(coordinate.x),
+                y: max.y.max(coordinate.y),
+            },
+        )
+    }))
+}
+
+fn is_near_edge(black_tiles: &BitSet) -> bool {
+    get_bounds(black_tiles).is_some_and(|(min, max)| {
+        min.x < AddressCodec::MIN + RECENTER_MARGIN
+            || min.y < AddressCodec::MIN + RECENTER_MARGIN
+            || max.x > AddressCodec::MAX - RECENTER_MARGIN
+            || max.y > AddressCodec::MAX - RECENTER_MARGIN
+    })
+}
+
+// Rasterizes the bounding box (plus room for new tiles around it) and scans it
+// a row at a time, which beats hashing addresses once the pattern fills in.
+// Returns the same counts as evolve_tiles, where every white tile in the box is
+// a candidate.
+fn evolve_tiles_dense(black_tiles: &mut BitSet, rule: &Rule) -> (usize, usize) {
+    let Some((min, max)) = get_bounds(black_tiles) else {
+        return (0, 0);
+    };
+    let min = Coordinate {
+        x: min.x - 2,
+        y: min.y - 2,
+    };
+    let max = Coordinate {
+        x: max.x + 2,
+        y: max.y + 2,
+    };
+
+    // Rows are two units apart, so each grid row holds one value of y
+    let width: usize = (max.x - min.x + 1).try_into().unwrap();
+    let height: usize = ((max.y - min.y) / 2 + 1).try_into().unwrap();
+    let get_index = |coordinate: Coordinate| -> Option<usize> {
+        let column: usize = (coordinate.x - min.x).try_into().ok()?;
+        let row: usize = ((coordinate.y - min.y) / 2).try_into().ok()?;
+        (column < width && row < height).then_some(row * width + column)
+    };
+
+    let rasterize_scope = common::scope!("rasterize");
+    let mut grid = vec![false; width * height];
+    for address in black_tiles.iter() {
+        grid[get_index(Coordinate::from_address(address)).unwrap()] = true;
+    }
+    drop(rasterize_scope);
+
+    let _scan_scope = common::scope!("scan");
+    let mut next_black_tiles = BitSet::new();
+    let mut candidates = 0;
+    let mut flips = 0;
+    for (y, row) in (min.y..=max.y).step_by(2).zip(grid.chunks(width)) {
+        for (x, is_black) in (min.x..=max.x).zip(row) {
+            // Only every other column holds a tile on any given row
+            if (x - y / 2).rem_euclid(2) != 0 {
+                continue;
+            }
+
+            let coordinate = Coordinate { x, y };
+            let adjacent_black_tile_count = get_adjacent_tiles(coordinate)
+                .iter()
+                .filter(|adjacent_tile| get_index(**adjacent_tile).is_some_and(|index| grid[index]))
+                .count();
+            let should_flip = rule.should_flip(*is_black, adjacent_black_tile_count);
+            if *is_black != should_flip {
+                // The re-centering margin covers the two tiles around the box
+                next_black_tiles.insert(AddressCodec::encode(coordinate).unwrap());
+            }
+            candidates += usize::from(!*is_black);
+            flips += usize::from(should_flip);
+        }
+    }
+
+    *black_tiles = next_black_tiles;
+    (candidates, flips)
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Engine {
+    // Visits each black tile and its neighbors through the address set
+    Sparse,
+    // Scans every tile in the bounding box
+    Dense,
+}
+
+// Finds the tile nearest the middle of a bounding box. Rows are two units apart
+// and odd rows are offset by one unit, so the middle has to keep y even and
+// match the x parity of the row it lands on.
+fn get_center(min: (i64, i64), max: (i64, i64)) -> (i64, i64) {
+    let center_y = (min.1 + max.1).div_euclid(4) * 2;
+    let mut center_x = (min.0 + max.0).div_euclid(2);
+    if (center_x - center_y / 2).rem_euclid(2) != 0 {
+        center_x -= 1;
+    }
+    (center_x, center_y)
+}
+
+// Moves every tile back by the given offset, failing on the first one that
+// would land outside the address space
+fn translate(black_tiles: &BitSet, by: (i64, i64)) -> Result<BitSet, AddressError> {
+    black_tiles
+        .iter()
+        .map(|address| {
+            let coordinate = Coordinate::from_address(address);
+            AddressCodec::encode_position(
+                i64::from(coordinate.x) - by.0,
+                i64::from(coordinate.y) - by.1,
+            )
+        })
+        .collect()
+}
+
+fn widen(coordinate: Coordinate) -> (i64, i64) {
+    (i64::from(coordinate.x), i64::from(coordinate.y))
+}
+
+fn get_position_bounds(positions: &[(i64, i64)]) -> Option<((i64, i64), (i64, i64))> {
+    let (first, rest) = positions.split_first()?;
+    Some(rest.iter().fold((*first, *first), |(min, max), (x, y)| {
+        (
+            (min.0.min(*x), min.1.min(*y)),
+            (max.0.max(*x), max.1.max(*y)),
+        )
+    }))
+}
+
+// Moves the middle of the bounding box back to the origin, returning the moved
+// tiles and where the old middle was
+fn recenter(black_tiles: &BitSet) -> Result<(BitSet, (i64, i64)), AddressError> {
+    let Some((min, max)) = get_bounds(black_tiles) else {
+        return Ok((BitSet::new(), (0, 0)));
+    };
+    let center = get_center(widen(min), widen(max));
+    Ok((translate(black_tiles, center)?, center))
+}
+
+// Describes a repeating stretch of generations that was skipped over. A
+// non-zero displacement means the pattern moves across the floor as it repeats.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Shortcut {
+    pub start: usize,
+    pub period: usize,
+    pub displacement: (i64, i64),
+    pub skipped: usize,
+}
+
+impl Display for Shortcut {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Pattern repeats every {} generations from generation {}",
+            self.period, self.start
+        )?;
+        if self.displacement != (0, 0) {
+            write!(
+                f,
+                ", moving by ({}, {}) each time",
+                self.displacement.0, self.displacement.1
+            )?;
+        }
+        write!(f, "; skipped {} generations", self.skipped)
+    }
+}
+
+// Why run_with_shortcut gave up before the requested day. Generations count
+// the days it simulated, not where the floor started.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Unfinished {
+    // The pattern didn't repeat within the generations HexLife was allowed to
+    // step through, so reaching the requested day would mean simulating every one
+    Diverged {
+        generations: usize,
+    },
+    // The time budget ran out, with this many tiles black at the time
+    OutOfTime {
+        generations: usize,
+        black_tiles: usize,
+    },
+}
+
+impl Display for Unfinished {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Unfinished::Diverged { generations } => {
+                write!(f, "exceeded {generations} generations without converging")
+            }
+            Unfinished::OutOfTime {
+                generations,
+                black_tiles,
+            } => write!(
+                f,
+                "ran out of time after {generations} generations with {black_tiles} tiles black"
+            ),
+        }
+    }
+}
+
+impl Error for Unfinished {}
+
+// How the tiles flipped by separate instruction lists are merged
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Combine {
+    // Same as following all of the instructions on one floor
+    Xor,
+    Union,
+    Intersect,
+}
+
+// What happened during one generation
+#[derive(Clone, Debug)]
+pub struct Profile {
+    pub generation: usize,
+    pub black_tiles: usize,
+    pub candidates: usize,
+    pub flips: usize,
+    pub elapsed: Duration,
+}
+
+type Hook<'a> = Box<dyn FnMut(usize, &HexLife) + 'a>;
+type ProfileHook<'a> = Box<dyn FnMut(&Profile) + 'a>;
+
+// Black tiles are kept as a set of packed addresses, which
+// Coordinate::from_address turns back into coordinates
+pub struct HexLife<'a> {
+    black_tiles: BitSet,
+    // Once the pattern is too spread out for the address space, its tiles move
+    // here for good as positions relative to the origin, and black_tiles stays
+    // empty. Neither engine is used from then on.
+    unbounded: Option<HashSet<(i64, i64)>>,
+    generation: usize,
+    // Where the origin of the address space sits on the unbounded floor
+    origin: (i64, i64),
+    rule: Rule,
+    engine: Engine,
+    recenter_interval: Option<usize>,
+    max_generations: Option<usize>,
+    time_budget: Option<Duration>,
+    hooks: Vec<Hook<'a>>,
+    profile_hooks: Vec<ProfileHook<'a>>,
+    // Kept between generations so the sparse engine doesn't allocate
+    neighbor_counts: Vec<u8>,
+}
+
+impl<'a> HexLife<'a> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            black_tiles: BitSet::new(),
+            unbounded: None,
+            generation: 0,
+            origin: (0, 0),
+            rule: Rule::default(),
+            engine: Engine::Sparse,
+            recenter_interval: None,
+            max_generations: None,
+            time_budget: None,
+            hooks: Vec::new(),
+            profile_hooks: Vec::new(),
+            neighbor_counts: Vec::new(),
+        }
+    }
+
+    // Each line is a path from the reference tile to a tile that gets flipped.
+    // Lines before a bad one have already been flipped when it's reported.
+    pub fn flip_from_instructions<'b, I>(&mut self, lines: I) -> Result<(), InstructionError>
+    where
+        I: IntoIterator<Item = &'b str>,
+    {
+        for (index, line) in lines.into_iter().enumerate() {
+            let position = get_coordinate(line).map_err(|error| InstructionError {
+                line: index + 1,
+                error,
+            })?;
+            self.toggle(position);
+        }
+        Ok(())
+    }
+
+    // Turns over the tile at the given coordinate, before any evolution
+    pub fn flip_tile(&mut self, coordinate: Coordinate) {
+        assert_eq!(self.generation, 0, "Tiles can only be flipped on day 0");
+        self.toggle(widen(coordinate));
+    }
+
+    // Flips the tile at the end of each of a number of random walks from the
+    // reference tile, like a generated instruction list, so benchmarks can
+    // build floors of any size
+    pub fn seed_random_walks(&mut self, walks: usize, length: usize, seed: u64) {
+        assert_eq!(self.generation, 0, "Tiles can only be flipped on day 0");
+        let mut rng = Rng::new(seed);
+        for _ in 0..walks {
+            let mut position = (0, 0);
+            for _ in 0..length {
+                let direction = Direction::from_index(usize::try_from(rng.below(6)).unwrap());
+                position = step_position(position, &direction);
+            }
+            self.toggle(position);
+        }
+    }
+
+    // Positions here are relative to the reference tile rather than the
+    // origin of the address space. When a tile lands outside the address space
+    // the floor falls back to re-centering on the pattern and the new tile
+    // together, and if they're too far apart to share it, to unbounded storage.
+    fn toggle(&mut self, position: (i64, i64)) {
+        let x = position.0 - self.origin.0;
+        let y = position.1 - self.origin.1;
+        if let Some(unbounded) = &mut self.unbounded {
+            toggle_position(unbounded, (x, y));
+            return;
+        }
+
+        let address = if let Ok(address) = AddressCodec::encode_position(x, y) {
+            address
+        } else {
+            let (min, max) =
+                get_bounds(&self.black_tiles).map_or(((x, y), (x, y)), |(min, max)| {
+                    (
+                        (i64::from(min.x).min(x), i64::from(min.y).min(y)),
+                        (i64::from(max.x).max(x), i64::from(max.y).max(y)),
+                    )
+                });
+            let center = get_center(min, max);
+            let (Ok(recentered), Ok(address)) = (
+                translate(&self.black_tiles, center),
+                AddressCodec::encode_position(x - center.0, y - center.1),
+            ) else {
+                let reason = format!(
+                    "Tile ({}, {}) is too far from the others to share the address space",
+                    position.0, position.1
+                );
+                toggle_position(self.spill(&reason), (x, y));
+                return;
+            };
+            self.black_tiles = recentered;
+            self.origin.0 += center.0;
+            self.origin.1 += center.1;
+            address
+        };
+        if !self.black_tiles.remove(address) {
+            self.black_tiles.insert(address);
+        }
+    }
+
+    // Moves the tiles into unbounded storage for good, saying why on stderr
+    // the first time
+    fn spill(&mut self, reason: &str) -> &mut HashSet<(i64, i64)> {
+        if self.unbounded.is_none() {
+            eprintln!("{reason}, switching to unbounded storage");
+        }
+        let black_tiles = std::mem::take(&mut self.black_tiles);
+        self.unbounded.get_or_insert_with(|| {
+            black_tiles
+                .iter()
+                .map(|address| widen(Coordinate::from_address(address)))
+                .collect()
+        })
+    }
+
+    // Neither floor can have evolved yet. The other floor's tiles are moved
+    // into this one's address space if either had to re-center while flipping,
+    // and both go to unbounded storage if they don't fit in one.
+    pub fn combine_with(&mut self, other: &HexLife, combine: Combine) {
+        assert!(
+            self.generation == 0 && other.generation == 0,
+            "Only floors that haven't evolved can be combined"
+        );
+        let by = (
+            self.origin.0 - other.origin.0,
+            self.origin.1 - other.origin.1,
+        );
+        let translated = if self.unbounded.is_none() && other.unbounded.is_none() {
+            translate(&other.black_tiles, by).ok()
+        } else {
+            None
+        };
+        if let Some(other_tiles) = translated {
+            match combine {
+                Combine::Xor => self.black_tiles.symmetric_difference_with(&other_tiles),
+                Combine::Union => self.black_tiles.union_with(&other_tiles),
+                Combine::Intersect => self.black_tiles.intersect_with(&other_tiles),
+            }
+            return;
+        }
+
+        let origin = self.origin;
+        let other_tiles: HashSet<(i64, i64)> = other
+            .get_positions()
+            .into_iter()
+            .map(|(x, y)| (x - origin.0, y - origin.1))
+            .collect();
+        let tiles = self.spill("The floors are too far apart to share the address space");
+        *tiles = match combine {
+            Combine::Xor => tiles.symmetric_difference(&other_tiles).copied().collect(),
+            Combine::Union => tiles.union(&other_tiles).copied().collect(),
+            Combine::Intersect => tiles.intersection(&other_tiles).copied().collect(),
+        };
+    }
+
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+    }
+
+    pub fn set_engine(&mut self, engine: Engine) {
+        self.engine = engine;
+    }
+
+    // The pattern is always re-centered when it gets close to the edge of the
+    // address space, and also every interval generations if one is set
+    pub fn set_recenter_interval(&mut self, interval: Option<usize>) {
+        self.recenter_interval = interval.map(|interval| interval.max(1));
+    }
+
+    // Limits how many generations run_with_shortcut will step through while
+    // looking for the pattern to repeat
+    pub fn set_max_generations(&mut self, max_generations: Option<usize>) {
+        self.max_generations = max_generations;
+    }
+
+    // Limits how long run_with_shortcut will spend stepping through generations
+    pub fn set_time_budget(&mut self, time_budget: Option<Duration>) {
+        self.time_budget = time_budget;
+    }
+
+    // Copies the tiles and every setting, but none of the hooks, so the copy
+    // can be run again and again from the same starting point
+    #[must_use]
+    pub fn detach<'b>(&self) -> HexLife<'b> {
+        HexLife {
+            black_tiles: self.black_tiles.clone(),
+            unbounded: self.unbounded.clone(),
+            generation: self.generation,
+            origin: self.origin,
+            rule: self.rule,
+            engine: self.engine,
+            recenter_interval: self.recenter_interval,
+            max_generations: self.max_generations,
+            time_budget: self.time_budget,
+            hooks: Vec::new(),
+            profile_hooks: Vec::new(),
+            neighbor_counts: Vec::new(),
+        }
+    }
+
+    // Hooks are called with the generation number and the floor after every
+    // step, so they can look at the tiles as well as count them
+    pub fn on_generation<F>(&mut self, hook: F)
+    where
+        F: FnMut(usize, &HexLife) + 'a,
+    {
+        self.hooks.push(Box::new(hook));
+    }
+
+    pub fn on_profile<F>(&mut self, hook: F)
+    where
+        F: FnMut(&Profile) + 'a,
+    {
+        self.profile_hooks.push(Box::new(hook));
+    }
+
+    pub fn step(&mut self) {
+        let _scope = common::scope!("generation");
+        let start = Instant::now();
+        self.generation += 1;
+
+        let is_scheduled = self
+            .recenter_interval
+            .is_some_and(|interval| self.generation.is_multiple_of(interval));
+        if self.unbounded.is_none() && (is_scheduled || is_near_edge(&self.black_tiles)) {
+            let _scope = common::scope!("recenter");
+            let recentered = recenter(&self.black_tiles)
+                .ok()
+                .filter(|(recentered, _)| !is_near_edge(recentered));
+            if let Some((recentered, center)) = recentered {
+                self.black_tiles = recentered;
+                self.origin.0 += center.0;
+                self.origin.1 += center.1;
+            } else {
+                let reason = format!(
+                    "Pattern has outgrown the address space in generation {}",
+                    self.generation
+                );
+                self.spill(&reason);
+            }
+        }
+        let (candidates, flips) = match (&mut self.unbounded, self.engine) {
+            (Some(unbounded), _) => evolve_unbounded(unbounded, &self.rule),
+            (None, Engine::Sparse) => {
+                evolve_tiles(&mut self.black_tiles, &mut self.neighbor_counts, &self.rule)
+            }
+            (None, Engine::Dense) => evolve_tiles_dense(&mut self.black_tiles, &self.rule),
+        };
+
+        if !self.profile_hooks.is_empty() {
+            let profile = Profile {
+                generation: self.generation,
+                black_tiles: self.black_count(),
+                candidates,
+                flips,
+                elapsed: start.elapsed(),
+            };
+            for hook in &mut self.profile_hooks {
+                hook(&profile);
+            }
+        }
+        // Hooks are set aside while they run so that each one can borrow the floor
+        let mut hooks = std::mem::take(&mut self.hooks);
+        for hook in &mut hooks {
+            hook(self.generation, self);
+        }
+        self.hooks = hooks;
+    }
+
+    pub fn run(&mut self, generations: usize) {
+        for _ in 0..generations {
+            self.step();
+        }
+    }
+
+    // Hashes the black tiles relative to their bounding box, so translated
+    // copies of a pattern share a hash, and returns it along with where the
+    // bounding box sits on the floor
+    fn get_fingerprint(&self) -> (u64, (i64, i64)) {
+        if self.unbounded.is_some() {
+            // Set order isn't stable, so sort before hashing
+            let mut positions = self.get_positions();
+            positions.sort_unstable();
+            let min = get_position_bounds(&positions).map_or((0, 0), |(min, _)| min);
+            let mut hasher = DefaultHasher::new();
+            for (x, y) in positions {
+                (x - min.0, y - min.1).hash(&mut hasher);
+            }
+            return (hasher.finish(), min);
+        }
+
+        let min = get_bounds(&self.black_tiles).map_or_else(Coordinate::new, |(min, _)| min);
+
+        let mut hasher = DefaultHasher::new();
+        for address in &self.black_tiles {
+            let coordinate = Coordinate::from_address(address);
+            (coordinate.x - min.x, coordinate.y - min.y).hash(&mut hasher);
+        }
+
+        let position = (
+            self.origin.0 + i64::from(min.x),
+            self.origin.1 + i64::from(min.y),
+        );
+        (hasher.finish(), position)
+    }
+
+    // Like run, but once the pattern repeats (possibly shifted) it jumps over
+    // as many whole repetitions as fit. Hooks aren't called for the generations
+    // that get skipped.
+    pub fn run_with_shortcut(
+        &mut self,
+        generations: usize,
+    ) -> Result<Option<Shortcut>, Unfinished> {
+        let start_generation = self.generation;
+        let deadline = self
+            .time_budget
+            .map(|time_budget| Instant::now() + time_budget);
+        let target = self.generation + generations;
+        let mut seen = HashMap::new();
+
+        while self.generation < target {
+            let (hash, position) = {
+                let _scope = common::scope!("fingerprint");
+                self.get_fingerprint()
+            };
+            if let Some((start, start_position)) = seen.insert(hash, (self.generation, position)) {
+                let period = self.generation - start;
+                let repetitions = (target - self.generation) / period;
+                let displacement = (position.0 - start_position.0, position.1 - start_position.1);
+
+                let skipped = repetitions * period;
+                let repetitions: i64 = repetitions
+                    .try_into()
+                    .expect("Couldn't store repetition count in i64");
+                self.generation += skipped;
+                self.origin.0 += displacement.0 * repetitions;
+                self.origin.1 += displacement.1 * repetitions;
+                self.run(target - self.generation);
+
+                return Ok(Some(Shortcut {
+                    start,
+                    period,
+                    displacement,
+                    skipped,
+                }));
+            }
+            if let Some(max_generations) = self.max_generations {
+                if self.generation - start_generation >= max_generations {
+                    return Err(Unfinished::Diverged {
+                        generations: max_generations,
+                    });
+                }
+            }
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return Err(Unfinished::OutOfTime {
+                    generations: self.generation - start_generation,
+                    black_tiles: self.black_count(),
+                });
+            }
+            self.step();
+        }
+
+        Ok(None)
+    }
+
+    #[must_use]
+    pub fn black_count(&self) -> usize {
+        self.unbounded
+            .as_ref()
+            .map_or_else(|| self.black_tiles.len(), HashSet::len)
+    }
+
+    #[must_use]
+    pub fn get_generation(&self) -> usize {
+        self.generation
+    }
+
+    // Lays the black tiles out for printing, or None if there aren't any.
+    // Rows of the map are two apart in y with north at the top, and alternate
+    // tiles are offset by one in x, so each tile gets one cell with gaps
+    // (None) between neighbors in a row. Tiles are Some(true) when black.
+    #[must_use]
+    pub fn get_map(&self) -> Option<Grid<Option<bool>>> {
+        let positions = self.get_positions();
+        let (min, max) = get_position_bounds(&positions)?;
+
+        let width = usize::try_from(max.0 - min.0 + 1).unwrap();
+        let height = usize::try_from((max.1 - min.1) / 2 + 1).unwrap();
+        let mut grid = Grid::new(width, height, None);
+        for row in 0..height {
+            let y = max.1 - 2 * i64::try_from(row).unwrap();
+            for column in 0..width {
+                let x = min.0 + i64::try_from(column).unwrap();
+                // Tiles sit where x and y/2 have the same parity as the origin
+                if (x - y / 2).rem_euclid(2) == 0 {
+                    grid[(column, row)] = Some(false);
+                }
+            }
+        }
+        for (x, y) in positions {
+            let column = usize::try_from(x - min.0).unwrap();
+            let row = usize::try_from((max.1 - y) / 2).unwrap();
+            grid[(column, row)] = Some(true);
+        }
+        Some(grid)
+    }
+
+    // Where each black tile sits relative to the reference tile, which works
+    // whether or not the pattern still fits in the address space
+    #[must_use]
+    pub fn get_positions(&self) -> Vec<(i64, i64)> {
+        let relative: Vec<(i64, i64)> = match &self.unbounded {
+            Some(unbounded) => unbounded.iter().copied().collect(),
+            None => self
+                .black_tiles
+                .iter()
+                .map(|address| widen(Coordinate::from_address(address)))
+                .collect(),
+        };
+        relative
+            .into_iter()
+            .map(|(x, y)| (x + self.origin.0, y + self.origin.1))
+            .collect()
+    }
+
+    // Addresses iterate in ascending order, and x sits above y in an address,
+    // so decoded tiles come out ordered by x and then by y. There are no
+    // addresses once the pattern has moved to unbounded storage.
+    #[must_use]
+    pub fn get_black_tiles(&self) -> Option<&BitSet> {
+        self.unbounded.is_none().then_some(&self.black_tiles)
+    }
+}
+
+impl Default for HexLife<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// The days part 2 asks about
+pub const DAYS: usize = 100;
+
+fn flip_from_text(text: &str) -> HexLife<'static> {
+    let mut life = HexLife::new();
+    life.flip_from_instructions(
+        text.lines()
+            .map(normalize_line)
+            .filter(|line| !line.is_empty()),
+    )
+    .unwrap_or_else(|error| panic!("Failed to flip tiles: {}", error));
+    life
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    fn part1(&self, input: &str) -> String {
+        flip_from_text(input).black_count().to_string()
+    }
+
+    fn part2(&self, input: &str) -> String {
+        let mut life = flip_from_text(input);
+        life.run(DAYS);
+        life.black_count().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{fixtures, input, LineReader};
+    #[cfg(feature = "nightly")]
+    use test::Bencher;
+
+    // Files come from this crate's directory, wherever the tests run from
+    fn read_lines(filename: &str) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut reader = LineReader::new(&fixtures::path(&format!("2020/day-24/{filename}")));
+        reader.read_with(|line| lines.push(String::from(line)));
+        lines
+    }
+
+    fn get_sample() -> HexLife<'static> {
+        let mut life = HexLife::new();
+        life.flip_from_instructions(read_lines("sample.txt").iter().map(String::as_str))
+            .unwrap();
+        life
+    }
+
+    #[test]
+    fn sample_flips() {
+        assert_eq!(get_sample().black_count(), 10);
+    }
+
+    #[test]
+    fn solver_answers_the_sample() {
+        let text = input::load(&fixtures::path("2020/day-24/sample.txt"));
+        assert_eq!(Solver.part1(&text), "10");
+        assert_eq!(Solver.part2(&text), "2208");
+    }
+
+    #[test]
+    fn sample_days() {
+        let expected = [
+            (1, 15),
+            (2, 12),
+            (3, 25),
+            (4, 14),
+            (5, 23),
+            (6, 28),
+            (7, 41),
+            (8, 37),
+            (9, 49),
+            (10, 37),
+            (20, 132),
+            (30, 259),
+            (40, 406),
+            (50, 566),
+            (60, 788),
+            (70, 1106),
+            (80, 1373),
+            (90, 1844),
+            (100, 2208),
+        ];
+
+        let mut counts = Vec::new();
+        {
+            let mut life = get_sample();
+            life.on_generation(|_, life| counts.push(life.black_count()));
+            life.run(100);
+        }
+        for (day, count) in &expected {
+            assert_eq!(counts[day - 1], *count, "Wrong count on day {day}");
+        }
+    }
+
+    #[cfg(feature = "nightly")]
+    #[bench]
+    fn bench_sample(bencher: &mut Bencher) {
+        let life = get_sample();
+        bencher.iter(|| {
+            let mut life = HexLife {
+                black_tiles: life.black_tiles.clone(),
+                ..HexLife::new()
+            };
+            life.run(100);
+            assert_eq!(life.black_count(), 2208);
+        });
+    }
+
+    #[cfg(feature = "nightly")]
+    #[bench]
+    fn bench_random_walks(bencher: &mut Bencher) {
+        let mut seeded = HexLife::new();
+        seeded.seed_random_walks(2000, 40, 24);
+        bencher.iter(|| {
+            let mut life = HexLife {
+                black_tiles: seeded.black_tiles.clone(),
+                ..HexLife::new()
+            };
+            life.run(20);
+            life.black_count()
+        });
+    }
+
+    #[test]
+    fn random_walks_follow_seed() {
+        let mut first = HexLife::new();
+        first.seed_random_walks(500, 30, 1);
+        let mut second = HexLife::new();
+        second.seed_random_walks(500, 30, 1);
+        assert_eq!(first.get_black_tiles(), second.get_black_tiles());
+        assert!(first.get_black_tiles().is_some());
+        assert!(first.black_count() > 0 && first.black_count() <= 500);
+
+        // Walks of no steps all flip the reference tile
+        let mut life = HexLife::new();
+        life.seed_random_walks(3, 0, 1);
+        assert_eq!(life.black_count(), 1);
+
+        // The same walks written out as instructions
+        let lines: Vec<String> = random_walks(500, 30, 1).collect();
+        assert!(lines
+            .iter()
+            .all(|line| DirectionIterator::new(line).count() == 30));
+        let mut written = HexLife::new();
+        written
+            .flip_from_instructions(lines.iter().map(String::as_str))
+            .unwrap();
+        assert_eq!(written.get_black_tiles(), first.get_black_tiles());
+    }
+
+    #[test]
+    fn golly_name() {
+        assert_eq!(Rule::default().get_golly_name(), "B2/S12H");
+        assert_eq!(
+            Rule::new(&[0, 6], &[1, 3]).unwrap().get_golly_name(),
+            "B13/S06H"
+        );
+        assert_eq!(
+            Rule::new(&[7], &[2]),
+            Err(RuleError::SurvivalOutOfRange {
+                count: 7,
+                neighbors: 6
+            })
+        );
+        assert_eq!(Rule::new(&[1], &[0]), Err(RuleError::ZeroBirth));
+        assert_eq!(
+            Rule::new(&[1], &[7]).unwrap_err().to_string(),
+            "birth count 7 is out of range, since a tile has 6 neighbors"
+        );
+    }
+
+    #[test]
+    fn profiles_account_for_flips() {
+        let mut profiles = Vec::new();
+        {
+            let mut life = get_sample();
+            life.on_profile(|profile| profiles.push(profile.clone()));
+            life.run(10);
+        }
+
+        let mut previous = 10;
+        for profile in &profiles {
+            let change = profile.black_tiles.abs_diff(previous);
+            assert!(profile.flips >= change && (profile.flips - change) % 2 == 0);
+            assert!(profile.candidates > 0);
+            previous = profile.black_tiles;
+        }
+        assert_eq!(profiles[9].black_tiles, 37);
+    }
+
+    #[test]
+    fn combine_sample_halves() {
+        let lines = read_lines("sample.txt");
+        let (first_half, second_half) = lines.split_at(lines.len() / 2);
+        let get_half = |half: &[String]| {
+            let mut life = HexLife::new();
+            life.flip_from_instructions(half.iter().map(String::as_str))
+                .unwrap();
+            life
+        };
+
+        let mut xor = get_half(first_half);
+        xor.combine_with(&get_half(second_half), Combine::Xor);
+        assert_eq!(xor.get_black_tiles(), get_sample().get_black_tiles());
+
+        let mut union = get_half(first_half);
+        union.combine_with(&get_half(second_half), Combine::Union);
+        let mut intersection = get_half(first_half);
+        intersection.combine_with(&get_half(second_half), Combine::Intersect);
+        assert_eq!(
+            union.black_count() - intersection.black_count(),
+            xor.black_count()
+        );
+    }
+
+    #[test]
+    fn reduce_sample_paths() {
+        assert_eq!(reduce_path("esew").unwrap(), (String::from("se"), 1));
+        assert_eq!(reduce_path("nwwswee").unwrap(), (String::new(), 0));
+        assert_eq!(reduce_path("eeenenw").unwrap(), (String::from("eenene"), 4));
+
+        for line in read_lines("sample.txt") {
+            let (path, distance) = reduce_path(&line).unwrap();
+            assert_eq!(get_coordinate(&path), get_coordinate(&line));
+            let length: i64 = DirectionIterator::new(&path).count().try_into().unwrap();
+            assert_eq!(length, distance);
+        }
+    }
+
+    #[test]
+    fn paths_past_the_coordinate_range_parse() {
+        let line = "e".repeat(20_000) + "ne";
+        assert_eq!(get_coordinate(&line), Ok((40_001, 2)));
+        assert_eq!(get_coordinate(&"sw".repeat(40_000)), Ok((-40_000, -80_000)));
+    }
+
+    #[test]
+    fn bad_paths_are_errors() {
+        assert_eq!(get_coordinate("ne e\tsw "), get_coordinate("neesw"));
+        assert_eq!(
+            get_coordinate("ees"),
+            Err(PathError::IncompleteDirection {
+                column: 4,
+                first: 's',
+                found: None
+            })
+        );
+        assert_eq!(
+            reduce_path("wn e").unwrap_err().to_string(),
+            "column 3: expected 'e' or 'w' after 'n', found ' '"
+        );
+        assert_eq!(
+            reduce_path("eé").unwrap_err().to_string(),
+            "column 2: unexpected character 'é'"
+        );
+
+        let mut iterator = DirectionIterator::new("xe");
+        assert!(iterator.next().unwrap().is_err());
+        assert!(iterator.next().is_none());
+
+        let mut life = HexLife::new();
+        assert_eq!(
+            life.flip_from_instructions(["esew", "nwx"])
+                .unwrap_err()
+                .to_string(),
+            "line 2: column 3: unexpected character 'x'"
+        );
+    }
+
+    #[test]
+    fn recentering_preserves_answer() {
+        let lines = read_lines("input.txt");
+        for interval in [None, Some(1), Some(7)] {
+            let mut life = HexLife::new();
+            life.flip_from_instructions(lines.iter().map(String::as_str))
+                .unwrap();
+            life.set_recenter_interval(interval);
+            life.run(100);
+            assert_eq!(life.black_count(), 3519);
+        }
+    }
+
+    #[test]
+    fn evolve_reuses_neighbor_counts() {
+        let mut life = get_sample();
+        life.run(10);
+        assert!(life.neighbor_counts.iter().all(|count| *count == 0));
+
+        // Room for any address, so later generations never need more
+        life.neighbor_counts
+            .resize(AddressCodec::END + (2 << AddressCodec::BITS), 0);
+        let buffer = life.neighbor_counts.as_ptr();
+        life.run(20);
+        assert_eq!(life.neighbor_counts.as_ptr(), buffer);
+        assert!(life.neighbor_counts.iter().all(|count| *count == 0));
+        assert_eq!(life.black_count(), 259);
+    }
+
+    #[test]
+    fn hooks_see_every_generation() {
+        let mut counts = Vec::new();
+        let mut last_positions = Vec::new();
+        let final_positions = {
+            let mut life = HexLife::new();
+            life.flip_from_instructions(read_lines("input.txt").iter().map(String::as_str))
+                .unwrap();
+            life.on_generation(|generation, life| {
+                counts.push((generation, life.black_count()));
+                last_positions = life.get_positions();
+            });
+            life.run(100);
+            life.get_positions()
+        };
+        assert_eq!(counts.len(), 100);
+        assert_eq!(counts[0].0, 1);
+        assert_eq!(counts[99], (100, 3519));
+        assert_eq!(last_positions, final_positions);
+    }
+
+    #[test]
+    fn recenter_moves_pattern_to_origin() {
+        let mut black_tiles = BitSet::new();
+        for (x, y) in [(400, 400), (399, 398), (403, 402)] {
+            black_tiles.insert(AddressCodec::encode(Coordinate { x, y }).unwrap());
+        }
+
+        let (recentered, _) = recenter(&black_tiles).unwrap();
+        assert_eq!(recentered.len(), 3);
+        let (min, max) = get_bounds(&recentered).unwrap();
+        assert!(min.x < 0 && max.x > 0 && min.y < 0 && max.y > 0);
+        for address in &recentered {
+            let coordinate = Coordinate::from_address(address);
+            assert_eq!(coordinate.y.rem_euclid(2), 0);
+            assert_eq!((coordinate.x - coordinate.y / 2).rem_euclid(2), 0);
+        }
+    }
+
+    #[test]
+    fn shortcut_skips_dead_pattern() {
+        let mut life = HexLife::new();
+        life.flip_from_instructions(["esew"]).unwrap();
+        let shortcut = life.run_with_shortcut(1_000_000_000).unwrap();
+        assert_eq!(life.black_count(), 0);
+        assert_eq!(life.get_generation(), 1_000_000_000);
+        assert_eq!(
+            shortcut,
+            Some(Shortcut {
+                start: 1,
+                period: 1,
+                displacement: (0, 0),
+                skipped: 999_999_998,
+            })
+        );
+    }
+
+    #[test]
+    fn max_generations_stops_growing_pattern() {
+        let mut life = get_sample();
+        life.set_max_generations(Some(10));
+        assert_eq!(
+            life.run_with_shortcut(100),
+            Err(Unfinished::Diverged { generations: 10 })
+        );
+        assert_eq!(life.get_generation(), 10);
+
+        // Reaching the limit is fine as long as no more days are needed
+        let mut life = get_sample();
+        life.set_max_generations(Some(10));
+        assert_eq!(life.run_with_shortcut(10), Ok(None));
+        assert_eq!(life.black_count(), 37);
+
+        // A repeating pattern can still skip far past the limit
+        let mut life = HexLife::new();
+        life.flip_from_instructions(["esew"]).unwrap();
+        life.set_max_generations(Some(5));
+        assert!(life.run_with_shortcut(1_000_000_000).unwrap().is_some());
+    }
+
+    #[test]
+    fn time_budget_reports_progress() {
+        let mut life = get_sample();
+        life.set_time_budget(Some(Duration::ZERO));
+        let error = life.run_with_shortcut(100).unwrap_err();
+        assert_eq!(
+            error,
+            Unfinished::OutOfTime {
+                generations: 0,
+                black_tiles: 10
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "ran out of time after 0 generations with 10 tiles black"
+        );
+
+        let mut life = get_sample();
+        life.set_time_budget(Some(Duration::from_secs(30)));
+        assert_eq!(life.run_with_shortcut(100), Ok(None));
+        assert_eq!(life.black_count(), 2208);
+    }
+
+    #[test]
+    fn detached_copies_start_over_without_hooks() {
+        let days = std::cell::Cell::new(0);
+        let mut life = get_sample();
+        life.on_generation(|_, _| days.set(days.get() + 1));
+
+        for _ in 0..2 {
+            let mut copy = life.detach();
+            copy.run_with_shortcut(100).unwrap();
+            assert_eq!(copy.black_count(), 2208);
+        }
+        assert_eq!(life.black_count(), 10);
+        assert_eq!(days.get(), 0);
+    }
+
+    #[test]
+    fn sample_map_snapshot() {
+        let mut life = get_sample();
+        let mut maps = Vec::new();
+        for _ in 0..2 {
+            let map = life.get_map().unwrap();
+            maps.push(common::vis::term::text(&map, |tile| match tile {
+                Some(true) => '#',
+                Some(false) => '.',
+                None => ' ',
+            }));
+            life.step();
+        }
+        insta::assert_snapshot!(maps.join("\n"));
+    }
+
+    #[test]
+    fn single_layer_evolves_like_the_floor() {
+        let mut layers = layers::Layers::new(&get_sample(), 1, Rule::default());
+        layers.run(100);
+        assert_eq!(layers.black_count(), 2208);
+    }
+
+    #[test]
+    fn lone_tile_reaches_the_layer_above() {
+        // Every neighbor of a lone black tile turns black, and it turns white
+        let rule = Rule::new_layered(&[], &[1]).unwrap();
+        let mut life = HexLife::new();
+        life.flip_tile(Coordinate::new());
+
+        let mut layers = layers::Layers::new(&life, 2, rule);
+        layers.step();
+        assert_eq!(layers.get_layer_counts(), [6, 1]);
+        assert_eq!(
+            Rule::new_layered(&[7, 8], &[8]).unwrap().get_golly_name(),
+            "B8/S78H"
+        );
+        assert!(Rule::new_layered(&[9], &[2]).is_err());
+    }
+
+    #[test]
+    fn default_rule_matches_puzzle() {
+        let rule = Rule::default();
+        assert!(rule.should_flip(true, 0));
+        assert!(!rule.should_flip(true, 1));
+        assert!(!rule.should_flip(true, 2));
+        assert!(rule.should_flip(true, 3));
+        assert!(!rule.should_flip(false, 1));
+        assert!(rule.should_flip(false, 2));
+        assert!(!rule.should_flip(false, 3));
+    }
+
+    #[test]
+    fn engines_agree() {
+        let lines = read_lines("input.txt");
+        let mut counts = [Vec::new(), Vec::new()];
+        for (engine, counts) in [Engine::Sparse, Engine::Dense].iter().zip(&mut counts) {
+            let mut life = HexLife::new();
+            life.set_engine(*engine);
+            life.flip_from_instructions(lines.iter().map(String::as_str))
+                .unwrap();
+            life.on_generation(|_, life| counts.push(life.black_count()));
+            life.run(100);
+        }
+        assert_eq!(counts[0], counts[1]);
+        assert_eq!(counts[1].last(), Some(&3519));
+    }
+
+    #[test]
+    fn codec_round_trips_every_coordinate() {
+        // Addresses run in order with x major, so every one below END is used
+        let mut expected = 0;
+        for x in AddressCodec::MIN..=AddressCodec::MAX {
+            for y in AddressCodec::MIN..=AddressCodec::MAX {
+                let coordinate = Coordinate { x, y };
+                assert_eq!(AddressCodec::encode(coordinate), Ok(expected));
+                assert_eq!(AddressCodec::decode(expected), Ok(coordinate));
+                expected += 1;
+            }
+        }
+        assert_eq!(expected, AddressCodec::END);
+    }
+
+    #[test]
+    fn codec_rejects_out_of_range() {
+        for (x, y) in [
+            (AddressCodec::MIN - 1, 0),
+            (AddressCodec::MAX + 1, 0),
+            (0, AddressCodec::MIN - 1),
+            (0, AddressCodec::MAX + 1),
+            (i16::MIN, i16::MAX),
+        ] {
+            assert_eq!(
+                AddressCodec::encode(Coordinate { x, y }),
+                Err(AddressError::OutOfRange {
+                    x: i64::from(x),
+                    y: i64::from(y)
+                })
+            );
+        }
+        assert_eq!(
+            AddressCodec::decode(AddressCodec::END),
+            Err(AddressError::PastEnd(AddressCodec::END))
+        );
+        assert_eq!(
+            AddressCodec::encode(Coordinate { x: 512, y: 0 })
+                .unwrap_err()
+                .to_string(),
+            "tile (512, 0) is outside the address space, which spans -512..=511 on each axis"
+        );
+    }
+
+    #[test]
+    fn far_tiles_fall_back_to_recentering() {
+        let mut life = HexLife::new();
+        life.flip_from_instructions(["e", &"e".repeat(300)])
+            .unwrap();
+        assert_eq!(life.black_count(), 2);
+        assert_ne!(life.origin, (0, 0));
+
+        // Flipping the far tile again has to find it in the moved address space
+        life.flip_tile(Coordinate { x: 600, y: 0 });
+        assert_eq!(life.black_count(), 1);
+
+        // Floors with different origins still line up tile for tile
+        let mut other = HexLife::new();
+        other.flip_tile(Coordinate { x: 2, y: 0 });
+        life.combine_with(&other, Combine::Xor);
+        assert_eq!(life.black_count(), 0);
+        assert!(life.unbounded.is_none());
+    }
+
+    #[test]
+    fn instructions_beyond_the_packed_range_fall_back_to_unbounded() {
+        let lines = ["w".repeat(300), "e".repeat(300), "e".repeat(300) + "ne"];
+        let mut life = HexLife::new();
+        life.flip_from_instructions(lines.iter().map(String::as_str))
+            .unwrap();
+        assert!(life.unbounded.is_some());
+        assert!(life.get_black_tiles().is_none());
+        let mut positions = life.get_positions();
+        positions.sort_unstable();
+        assert_eq!(positions, [(-600, 0), (600, 0), (601, 2)]);
+
+        // Flipping again still finds the tiles, and the floor stays unbounded
+        life.flip_tile(Coordinate { x: 601, y: 2 });
+        assert_eq!(life.get_positions().len(), 2);
+        assert!(life.unbounded.is_some());
+
+        // Floors too far apart to share the address space combine unbounded
+        let mut east = HexLife::new();
+        east.flip_from_instructions(["e".repeat(300).as_str()])
+            .unwrap();
+        let mut west = HexLife::new();
+        west.flip_from_instructions(["w".repeat(300).as_str()])
+            .unwrap();
+        east.combine_with(&west, Combine::Union);
+        assert!(east.unbounded.is_some());
+        assert_eq!(east.black_count(), 2);
+    }
+
+    #[test]
+    fn instructions_past_the_coordinate_range_fall_back_to_unbounded() {
+        let lines = ["e".repeat(20_000), "w".repeat(20_000), "ne".repeat(20_000)];
+        let mut life = HexLife::new();
+        life.flip_from_instructions(lines.iter().map(String::as_str))
+            .unwrap();
+        assert!(life.unbounded.is_some());
+        let mut positions = life.get_positions();
+        positions.sort_unstable();
+        assert_eq!(positions, [(-40_000, 0), (20_000, 40_000), (40_000, 0)]);
+
+        // Lone tiles all turn white
+        life.step();
+        assert_eq!(life.black_count(), 0);
+    }
+
+    #[test]
+    fn far_apart_patterns_evolve_like_separate_ones() {
+        let pattern = ["e", "ne", "nw", "w", "sesw"];
+        let mut centered = HexLife::new();
+        centered.flip_from_instructions(pattern).unwrap();
+
+        let lines: Vec<String> = ["e".repeat(400), "w".repeat(400)]
+            .iter()
+            .flat_map(|walk| pattern.iter().map(move |line| walk.clone() + line))
+            .collect();
+        let mut far_apart = HexLife::new();
+        far_apart
+            .flip_from_instructions(lines.iter().map(String::as_str))
+            .unwrap();
+        assert!(far_apart.unbounded.is_some());
+
+        for _ in 0..20 {
+            centered.step();
+            far_apart.step();
+            assert_eq!(far_apart.black_count(), 2 * centered.black_count());
+        }
+        assert!(far_apart.get_map().is_some());
+    }
+
+    #[test]
+    fn outgrowing_the_address_space_switches_storage() {
+        // Both tiles fit, but they sit too close to opposite edges for
+        // re-centering to help, which used to panic
+        let mut life = HexLife::new();
+        life.set_rule(Rule::new(&[0], &[1]).unwrap());
+        life.flip_from_instructions(["e".repeat(253).as_str(), "w".repeat(253).as_str()])
+            .unwrap();
+        assert!(life.unbounded.is_none());
+
+        let mut reference = HexLife::new();
+        reference.set_rule(Rule::new(&[0], &[1]).unwrap());
+        reference.flip_from_instructions(["e"]).unwrap();
+
+        life.step();
+        reference.step();
+        assert!(life.unbounded.is_some());
+        assert_eq!(life.black_count(), 2 * reference.black_count());
+        assert_eq!(life.get_generation(), 1);
+
+        // A repeating pattern can still be skipped over once it's unbounded
+        let mut life = HexLife::new();
+        life.flip_from_instructions(["e".repeat(300).as_str(), "w".repeat(300).as_str()])
+            .unwrap();
+        assert_eq!(
+            life.run_with_shortcut(1_000_000),
+            Ok(Some(Shortcut {
+                start: 1,
+                period: 1,
+                displacement: (0, 0),
+                skipped: 999_998,
+            }))
+        );
+        assert_eq!(life.black_count(), 0);
+    }
+
+    #[test]
+    fn unbounded_storage_evolves_like_packed() {
+        // Tiles either side of 127 and 128 steps out, in each direction
+        let mut lines = Vec::new();
+        for steps in [127, 128] {
+            for direction in ["e", "w", "ne", "sw", "nw", "se"] {
+                lines.push(direction.repeat(steps));
+                lines.push(direction.repeat(steps) + "e");
+            }
+        }
+        let mut packed = HexLife::new();
+        packed
+            .flip_from_instructions(lines.iter().map(String::as_str))
+            .unwrap();
+        let mut unbounded = packed.detach();
+        unbounded.spill("Testing");
+
+        for _ in 0..30 {
+            let mut expected = packed.get_positions();
+            expected.sort_unstable();
+            let mut actual = unbounded.get_positions();
+            actual.sort_unstable();
+            assert_eq!(actual, expected);
+            packed.step();
+            unbounded.step();
+        }
+        assert!(packed.unbounded.is_none());
+    }
+
+    #[test]
+    fn instructions_at_the_packed_boundary() {
+        // 255 steps east ends on 510 and 256 steps west on -512, both inside
+        let mut life = HexLife::new();
+        life.flip_from_instructions(["e".repeat(255).as_str(), "w".repeat(256).as_str()])
+            .unwrap();
+        assert_eq!(life.origin, (0, 0));
+
+        // One more step east walks past 511 and moves the address space
+        let mut life = HexLife::new();
+        life.flip_from_instructions(["e".repeat(256).as_str()])
+            .unwrap();
+        assert_ne!(life.origin, (0, 0));
+        let mut life = HexLife::new();
+        life.flip_from_instructions(["ne".repeat(256).as_str()])
+            .unwrap();
+        assert_ne!(life.origin, (0, 0));
+    }
+
+    #[test]
+    fn patterns_at_the_boundary_evolve_like_centered_ones() {
+        let pattern = ["e", "ne", "nw", "w"];
+        let mut centered = HexLife::new();
+        centered.flip_from_instructions(pattern).unwrap();
+
+        let walk = "e".repeat(254);
+        let lines: Vec<String> = pattern.iter().map(|line| walk.clone() + line).collect();
+        let mut shifted = HexLife::new();
+        shifted
+            .flip_from_instructions(lines.iter().map(String::as_str))
+            .unwrap();
+
+        for _ in 0..20 {
+            centered.step();
+            shifted.step();
+            assert_eq!(shifted.black_count(), centered.black_count());
+        }
+    }
+
+    #[test]
+    fn black_tiles_come_out_sorted() {
+        let mut life = get_sample();
+        life.run(10);
+        let coordinates: Vec<(i16, i16)> = life
+            .get_black_tiles()
+            .unwrap()
+            .iter()
+            .map(|address| {
+                let coordinate = Coordinate::from_address(address);
+                (coordinate.x, coordinate.y)
+            })
+            .collect();
+        let mut sorted = coordinates.clone();
+        sorted.sort_unstable();
+        assert_eq!(coordinates, sorted);
+        assert_eq!(coordinates.len(), 37);
+    }
+
+    #[test]
+    fn offsets_match_steps() {
+        let origin = Coordinate::new();
+        for (adjacent_tile, offset) in get_adjacent_tiles(origin).iter().zip(&ADJACENT_OFFSETS) {
+            assert_eq!(
+                AddressCodec::encode(*adjacent_tile).unwrap(),
+                AddressCodec::encode(origin)
+                    .unwrap()
+                    .wrapping_add_signed(*offset)
+            );
+        }
+    }
+}
@@ -1,228 +1,502 @@
 #![deny(clippy::all, clippy::pedantic)]
-#![feature(test)]
-
-extern crate test;
-
-use std::convert::TryInto;
-
-use bit_set::BitSet;
-use clap::{crate_name, App, Arg};
-use common::LineReader;
-
-enum Direction {
-    East,
-    Southeast,
-    Southwest,
-    West,
-    Northwest,
-    Northeast,
-}
-
-impl Direction {
-    fn from_index(index: usize) -> Self {
-        match index {
-            0 => Direction::East,
-            1 => Direction::Southeast,
-            2 => Direction::Southwest,
-            3 => Direction::West,
-            4 => Direction::Northwest,
-            5 => Direction::Northeast,
-            _ => panic!("Unexpected direction index {}", index),
-        }
-    }
+
+use std::{cell::RefCell, convert::TryFrom, fmt::Display, path::Path, process};
+
+use clap::{crate_name, value_t, App, Arg, ArgMatches};
+use common::{
+    alloc,
+    cli::{self, Part},
+    golly,
+    grid::Grid,
+    input, timing,
+    vis::{
+        ansi::{self, Role, Theme},
+        sparkline, svg, term, Rgb,
+    },
+    LineReader,
+};
+use day_24::{layers::Layers, reduce_path, Combine, Coordinate, Engine, HexLife, Rule};
+
+fn parse_counts(counts: &str) -> Result<Vec<usize>, String> {
+    counts
+        .split(',')
+        .filter(|count| !count.is_empty())
+        .map(|count| {
+            count
+                .trim()
+                .parse()
+                .map_err(|_| format!("{count:?} is not a neighbor count"))
+        })
+        .collect()
+}
+
+fn read_lines(filename: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut reader = LineReader::new(filename);
+    reader.read_with(|line| lines.push(String::from(line)));
+    lines
 }
 
-struct DirectionIterator<'a> {
-    line: &'a str,
-    cursor: usize,
+// Coordinates step x by 2 along a row and y by 2 between rows, with north
+// being positive, so convert them to cube coordinates with z growing south
+fn write_svg(filename: &str, life: &HexLife) {
+    let points: Vec<_> = life
+        .get_positions()
+        .into_iter()
+        .map(|(x, y)| {
+            let z = -y / 2;
+            let x = (x - z) / 2;
+            let (x, y) = svg::hex_center(x, z);
+            (x, y, Rgb(0x20, 0x20, 0x20))
+        })
+        .collect();
+    let svg = svg::render_points(&points, svg::Marker::Hex, 1.0, 800);
+    std::fs::write(filename, svg).unwrap_or_else(|_| panic!("Failed to write file {}", filename));
 }
 
-impl<'a> DirectionIterator<'a> {
-    fn new(line: &'a str) -> Self {
-        Self { line, cursor: 0 }
+fn print_map(life: &HexLife, theme: Option<Theme>) {
+    let Some(grid) = life.get_map() else {
+        return;
+    };
+
+    let style = |tile: &Option<bool>| match tile {
+        Some(true) => ('#', Role::On),
+        Some(false) => ('.', Role::Off),
+        None => (' ', Role::Fixed),
+    };
+    match theme {
+        Some(theme) => print!("{}", ansi::render(&grid, &theme, style)),
+        None => print!("{}", term::text(&grid, |tile| style(tile).0)),
     }
 }
 
-impl<'a> Iterator for DirectionIterator<'a> {
-    type Item = Direction;
+// Golly lays out hexagonal rules on a square grid where the diagonal from the
+// top left to the bottom right counts as adjacent, which axial coordinates
+// map onto with each row shifted by half its index
+fn write_rle(filename: &str, life: &HexLife, rule: &Rule) {
+    let cells: Vec<(i64, i64)> = life
+        .get_positions()
+        .into_iter()
+        .map(|(x, y)| {
+            let row = -y / 2;
+            (i64::midpoint(x, row), row)
+        })
+        .collect();
+    let min_column = cells.iter().map(|cell| cell.0).min().unwrap_or(0);
+    let max_column = cells.iter().map(|cell| cell.0).max().unwrap_or(0);
+    let min_row = cells.iter().map(|cell| cell.1).min().unwrap_or(0);
+    let max_row = cells.iter().map(|cell| cell.1).max().unwrap_or(0);
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.cursor == self.line.len() {
-            return None;
-        }
+    let mut grid = Grid::new(
+        usize::try_from(max_column - min_column + 1).unwrap(),
+        usize::try_from(max_row - min_row + 1).unwrap(),
+        false,
+    );
+    for (column, row) in cells {
+        grid[(
+            usize::try_from(column - min_column).unwrap(),
+            usize::try_from(row - min_row).unwrap(),
+        )] = true;
+    }
+    let pattern = golly::encode(&grid, Some(&rule.get_golly_name()), |black| {
+        u8::from(*black)
+    });
+    std::fs::write(filename, pattern)
+        .unwrap_or_else(|_| panic!("Failed to write file {}", filename));
+}
 
-        let first = &self.line[self.cursor..=self.cursor];
-        match first {
-            "e" => {
-                self.cursor += 1;
-                Some(Direction::East)
-            }
-            "s" => {
-                let next = &self.line[self.cursor + 1..=self.cursor + 1];
-                self.cursor += 2;
-                match next {
-                    "e" => Some(Direction::Southeast),
-                    "w" => Some(Direction::Southwest),
-                    _ => panic!("Unexpected character after 's': {}", next),
-                }
-            }
-            "w" => {
-                self.cursor += 1;
-                Some(Direction::West)
-            }
-            "n" => {
-                let next = &self.line[self.cursor + 1..=self.cursor + 1];
-                self.cursor += 2;
-                match next {
-                    "w" => Some(Direction::Northwest),
-                    "e" => Some(Direction::Northeast),
-                    _ => panic!("Unexpected character after 'n': {}", next),
-                }
-            }
-            _ => panic!("Unexpected first character: {}", first),
+// The inverse of write_rle, with the pattern centered on the reference tile
+fn flip_from_pattern(life: &mut HexLife, filename: &str) -> Result<(), String> {
+    let text = input::load(filename);
+    let pattern = golly::decode(&text).map_err(|error| format!("{filename}: {error}"))?;
+    // Grid indices count cells that were allocated, so they always fit
+    let offset = |value: usize, length: usize| {
+        i64::try_from(value).unwrap() - i64::try_from(length / 2).unwrap()
+    };
+    for ((column, row), state) in pattern.grid.iter() {
+        if *state != 0 {
+            let column = offset(column, pattern.grid.width());
+            let row = offset(row, pattern.grid.height());
+            let coordinate = i16::try_from(2 * column - row)
+                .and_then(|x| Ok(Coordinate {
+                    x,
+                    y: i16::try_from(-2 * row)?,
+                }))
+                .map_err(|_| {
+                    format!(
+                        "{filename}: cell ({column}, {row}) is too far from the middle of the pattern"
+                    )
+                })?;
+            life.flip_tile(coordinate);
         }
     }
+    Ok(())
 }
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-struct Coordinate {
-    x: i8,
-    y: i8,
+// Reports a bad input file and exits, rather than panicking with a backtrace
+fn exit_with(filename: &str, error: &dyn Display) -> ! {
+    eprintln!("{filename}: {error}");
+    process::exit(1);
 }
 
-impl Coordinate {
-    fn new() -> Self {
-        Self { x: 0, y: 0 }
+// Instruction files list a path to each tile to flip, while .rle files are
+// Golly patterns with live cells black
+fn flip_from_file(life: &mut HexLife, filename: &str) -> Result<(), String> {
+    if Path::new(filename)
+        .extension()
+        .is_some_and(|extension| extension == "rle")
+    {
+        flip_from_pattern(life, filename)
+    } else {
+        let lines = read_lines(filename);
+        life.flip_from_instructions(lines.iter().map(String::as_str))
+            .map_err(|error| format!("{filename}: {error}"))
     }
+}
 
-    fn from_address(address: usize) -> Self {
-        let x: i16 = ((address >> 8) & 0xFF).try_into().unwrap();
-        let y: i16 = (address & 0xFF).try_into().unwrap();
-
-        Self {
-            x: (x - 128).try_into().unwrap(),
-            y: (y - 128).try_into().unwrap(),
+// Flips the tiles from every file, merging them with --combine, onto a floor
+// set up with the rule and engine to evolve
+fn load_floor<'a>(
+    filenames: &[String],
+    args: &ArgMatches,
+    rule: Rule,
+) -> Result<HexLife<'a>, String> {
+    let mut life = HexLife::new();
+    life.set_rule(rule);
+    life.set_engine(match args.value_of("engine").unwrap() {
+        "dense" => Engine::Dense,
+        _ => Engine::Sparse,
+    });
+    let combine = match args.value_of("combine").unwrap() {
+        "union" => Combine::Union,
+        "intersect" => Combine::Intersect,
+        _ => Combine::Xor,
+    };
+    for (index, filename) in filenames.iter().enumerate() {
+        if index == 0 {
+            flip_from_file(&mut life, filename)?;
+        } else {
+            let mut other = HexLife::new();
+            flip_from_file(&mut other, filename)?;
+            life.combine_with(&other, combine);
         }
     }
+    Ok(life)
+}
 
-    fn step(&mut self, direction: &Direction) {
-        match direction {
-            Direction::East => self.x += 2,
-            Direction::Southeast => {
-                self.y -= 2;
-                self.x += 1;
-            }
-            Direction::Southwest => {
-                self.y -= 2;
-                self.x -= 1;
+// Answers the puzzle's two parts like any other day, where part 2 counts the
+// black tiles after --days days
+fn answer_parts(filenames: &[String], args: &ArgMatches, rule: Rule, days: usize) {
+    let part = value_t!(args, "part", Part).unwrap_or_else(|error| error.exit());
+    cli::run_parts(
+        part,
+        || load_floor(filenames, args, rule),
+        HexLife::black_count,
+        |life| {
+            let mut life = life.detach();
+            set_limits(&mut life, args);
+            if let Err(error) = life.run_with_shortcut(days) {
+                eprintln!("After {} days: {}", life.get_generation(), error);
+                process::exit(1);
             }
-            Direction::West => self.x -= 2,
-            Direction::Northwest => {
-                self.y += 2;
-                self.x -= 1;
-            }
-            Direction::Northeast => {
-                self.y += 2;
-                self.x += 1;
-            }
-        }
+            life.black_count()
+        },
+    );
+}
+
+// Prints or writes out the floor in whichever forms were asked for
+fn write_final_state(args: &ArgMatches, life: &HexLife, rule: &Rule) {
+    if args.is_present("map") {
+        print_map(
+            life,
+            args.value_of("theme")
+                .map(|name| Theme::from_name(name).unwrap()),
+        );
     }
 
-    fn get_address(self) -> u16 {
-        let high_byte: u16 = (i16::from(self.x) + 128).try_into().unwrap();
-        let low_byte: u16 = (i16::from(self.y) + 128).try_into().unwrap();
-        high_byte << 8 | low_byte
+    if let Some(filename) = args.value_of("emit-rle") {
+        write_rle(filename, life, rule);
     }
-}
 
-fn get_coordinate(line: &str) -> Coordinate {
-    let mut coordinate = Coordinate::new();
-    for direction in DirectionIterator::new(line) {
-        coordinate.step(&direction);
+    if let Some(filename) = args.value_of("svg") {
+        write_svg(filename, life);
     }
-    coordinate
 }
 
-fn get_adjacent_tiles(coordinate: Coordinate) -> [Coordinate; 6] {
-    let mut adjacent_tiles = [coordinate; 6];
-    for (index, direction) in (0..6).map(Direction::from_index).enumerate() {
-        adjacent_tiles[index].step(&direction);
-    }
-    adjacent_tiles
+fn get_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(crate_name!())
+        .arg(
+            Arg::from_usage("[FILE]... 'Instruction files or Golly patterns ending in .rle, globs of either, or - for stdin'")
+                .required(!cfg!(feature = "embed-input")),
+        )
+        .arg(Arg::from_usage("-d, --days [DAYS] 'Number of days to evolve'").default_value("100"))
+        .arg(
+            Arg::from_usage("--part [PART] 'Answer the puzzle instead, with part 2 after DAYS days'")
+                .possible_values(&Part::NAMES)
+                .conflicts_with_all(&[
+                    "reduce", "layers", "explain", "profile", "map", "emit-rle", "svg", "repeat",
+                ]),
+        )
+        .arg(Arg::from_usage(
+            "--recenter-every [DAYS] 'Re-center the pattern on this schedule as well as near the edge'",
+        ))
+        .arg(
+            Arg::from_usage(
+                "--max-generations [N] 'Give up if the pattern hasn't repeated after simulating N days'",
+            )
+            .default_value("100000"),
+        )
+        .arg(Arg::from_usage(
+            "--time-budget [DURATION] 'Give up after simulating for this long, such as 500ms or 2s, reporting how far it got'",
+        ))
+        .arg(
+            Arg::from_usage(
+                "--survive [COUNTS] 'Black neighbor counts that keep a black tile black'",
+            )
+            .default_value("1,2"),
+        )
+        .arg(
+            Arg::from_usage("--birth [COUNTS] 'Black neighbor counts that turn a white tile black'")
+                .default_value("2"),
+        )
+        .arg(
+            Arg::from_usage("-e, --engine [ENGINE] 'Evolution algorithm'")
+                .possible_values(&["sparse", "dense"])
+                .default_value("sparse"),
+        )
+        .arg(Arg::from_usage(
+            "--reduce 'Print the shortest equivalent path and distance for each line instead'",
+        ))
+        .arg(
+            Arg::from_usage(
+                "-c, --combine [MODE] 'How the tiles flipped by each file are merged'",
+            )
+            .possible_values(&["xor", "union", "intersect"])
+            .default_value("xor"),
+        )
+        .arg(Arg::from_usage(
+            "--profile 'Write per-day tile counts and timings to stderr as CSV'",
+        ))
+        .arg(Arg::from_usage(
+            "--explain 'Describe the rule and count black tiles on the days the puzzle lists'",
+        ))
+        .arg(Arg::from_usage(
+            "--map 'Print the final floor, with # for black tiles'",
+        ))
+        .arg(
+            Arg::from_usage("--theme [THEME] 'Color the printed map'")
+                .possible_values(&Theme::NAMES)
+                .requires("map"),
+        )
+        .arg(Arg::from_usage(
+            "--emit-rle [RLE] 'Write the final black tiles as a Golly pattern'",
+        ))
+        .arg(Arg::from_usage(
+            "--svg [SVG] 'Draw the final black tiles as an SVG image'",
+        ))
+        .arg(
+            Arg::from_usage(
+                "--layers [N] 'Experimental: stack N floors with the flipped one at the bottom, so each tile also has black neighbors above and below, up to 8 in all'",
+            )
+            .conflicts_with_all(&["explain", "profile", "map", "emit-rle", "svg", "repeat"]),
+        )
+        .arg(Arg::from_usage(
+            "--repeat [N] 'After one warmup, run the days N more times and write min, mean and stddev to stderr'",
+        ))
+        .arg(Arg::from_usage(
+            "--timing 'Write a breakdown of where the time went to stderr'",
+        ))
+        .arg(Arg::from_usage(
+            "--trace-out [FILE] 'Write a Chrome trace of every timed phase to FILE'",
+        ))
 }
 
-fn count_adjacent_black_tiles(coordinate: Coordinate, black_tiles: &BitSet) -> usize {
-    let adjacent_tiles = get_adjacent_tiles(coordinate);
-    let mut count = 0;
-    for adjacent_tile in &adjacent_tiles {
-        if black_tiles.contains(adjacent_tile.get_address() as usize) {
-            count += 1;
-            if count > 2 {
-                return count;
-            }
+// Describes the rule, then counts black tiles on the same days as the puzzle's
+// example: each of the first ten, then every tenth. Days skipped over by a
+// shortcut aren't counted.
+fn explain(life: &mut HexLife, args: &ArgMatches) {
+    println!(
+        "Each day, a black tile with {} black neighbors stays black, and a white tile with {} black neighbors turns black.",
+        args.value_of("survive").unwrap().replace(',', " or "),
+        args.value_of("birth").unwrap().replace(',', " or ")
+    );
+    life.on_generation(|day, life| {
+        if day <= 10 || day.is_multiple_of(10) {
+            println!("Day {day}: {}", life.black_count());
         }
-    }
-    count
+    });
 }
 
-fn evolve_tiles(black_tiles: &mut BitSet) {
-    let mut tiles_to_flip = Vec::new();
-    let mut white_tiles = BitSet::new();
+// Applies the options that bound how far and how long the simulation runs
+fn set_limits(life: &mut HexLife, args: &ArgMatches) {
+    life.set_recenter_interval(args.value_of("recenter-every").map(|interval| {
+        interval
+            .parse()
+            .expect("Failed to parse re-center interval")
+    }));
+    life.set_max_generations(Some(
+        args.value_of("max-generations")
+            .unwrap()
+            .parse()
+            .expect("Failed to parse maximum generations"),
+    ));
+    life.set_time_budget(
+        args.value_of("time-budget")
+            .map(|budget| timing::parse_duration(budget).expect("Failed to parse time budget")),
+    );
+}
 
-    for black_tile in black_tiles.iter() {
-        let coordinate = Coordinate::from_address(black_tile);
-        let adjacent_black_tile_count = count_adjacent_black_tiles(coordinate, black_tiles);
-        if adjacent_black_tile_count == 0 || adjacent_black_tile_count > 2 {
-            tiles_to_flip.push(black_tile);
-        }
+// Times the days alone, each run starting over from the flipped tiles. The
+// hooks aren't copied, so --explain and --profile only see the real run.
+fn time_runs(life: &HexLife, days: usize, runs: &str) {
+    let runs = runs.parse().expect("Failed to parse repeat count");
+    let summary = timing::repeat(runs, || life.detach().run_with_shortcut(days));
+    eprintln!("{days} days took {summary}");
+}
 
-        for adjacent_tile in &get_adjacent_tiles(coordinate) {
-            white_tiles.insert(adjacent_tile.get_address() as usize);
-        }
-    }
+// Stacked floors allow counts that reach the tiles above and below
+fn get_rule(args: &ArgMatches) -> Rule {
+    let new_rule = if args.is_present("layers") {
+        Rule::new_layered
+    } else {
+        Rule::new
+    };
+    let counts = |name: &str| {
+        parse_counts(args.value_of(name).unwrap())
+            .unwrap_or_else(|error| exit_with(&format!("--{name}"), &error))
+    };
+    new_rule(&counts("survive"), &counts("birth"))
+        .unwrap_or_else(|error| exit_with("--survive and --birth", &error))
+}
 
-    white_tiles.difference_with(black_tiles);
-    for white_tile in &white_tiles {
-        let coordinate = Coordinate::from_address(white_tile);
-        let adjacent_black_tile_count = count_adjacent_black_tiles(coordinate, black_tiles);
-        if adjacent_black_tile_count == 2 {
-            tiles_to_flip.push(white_tile);
-        }
+// Runs the stacked floors in place of the single one
+fn run_layers(life: &HexLife, rule: Rule, layer_count: &str, days: usize) {
+    let layer_count = layer_count.parse().expect("Failed to parse layer count");
+    let mut layers = Layers::new(life, layer_count, rule);
+    let run_scope = common::scope!("run");
+    layers.run(days);
+    drop(run_scope);
+    println!(
+        "After {} days, {} tiles are black across {} layers",
+        days,
+        layers.black_count(),
+        layer_count
+    );
+    for (layer, count) in layers.get_layer_counts().iter().enumerate() {
+        println!("Layer {}: {}", layer + 1, count);
     }
+}
 
-    for tile_to_flip in tiles_to_flip {
-        if !black_tiles.remove(tile_to_flip) {
-            black_tiles.insert(tile_to_flip);
-        }
+// Globs expand to the files they match, in sorted order
+fn get_filenames(args: &ArgMatches) -> Vec<String> {
+    match args.values_of("FILE") {
+        Some(paths) => input::expand_globs(paths).unwrap_or_else(|error| {
+            eprintln!("{error}");
+            process::exit(1);
+        }),
+        None => vec![String::from(common::resolve_input!(None::<&str>))],
     }
 }
 
 fn main() {
-    let args = App::new(crate_name!())
-        .arg(Arg::from_usage("<FILE>"))
-        .get_matches();
+    let args = get_app().get_matches();
+    let _trace = args.value_of("trace-out").map(timing::trace_to);
 
-    let mut black_tiles = BitSet::new();
+    let filenames = get_filenames(&args);
 
-    let mut reader = LineReader::new(args.value_of("FILE").unwrap());
-    reader.read_with(|line| {
-        let coordinate = get_coordinate(line);
-        if !black_tiles.remove(coordinate.get_address() as usize) {
-            black_tiles.insert(coordinate.get_address() as usize);
+    if args.is_present("reduce") {
+        for filename in &filenames {
+            for (index, line) in read_lines(filename).iter().enumerate() {
+                let (path, distance) = reduce_path(line).unwrap_or_else(|error| {
+                    exit_with(filename, &format!("line {}: {}", index + 1, error))
+                });
+                println!("{line} -> {path} ({distance})");
+            }
         }
+        return;
+    }
+
+    let rule = get_rule(&args);
+    let days: usize = args
+        .value_of("days")
+        .unwrap()
+        .parse()
+        .expect("Failed to parse days");
+    if args.is_present("part") {
+        answer_parts(&filenames, &args, rule, days);
+        print_timing(&args);
+        return;
+    }
+
+    // Filled in by the profile hook, so it has to outlive life
+    let black_counts = RefCell::new(Vec::new());
+    let parse_scope = common::scope!("parse");
+    let mut life = load_floor(&filenames, &args, rule).unwrap_or_else(|error| {
+        eprintln!("{error}");
+        process::exit(1);
     });
+    drop(parse_scope);
+    println!("{} tiles remain flipped", life.black_count());
 
-    println!("{} tiles remain flipped", black_tiles.len());
+    if let Some(layer_count) = args.value_of("layers") {
+        run_layers(&life, rule, layer_count, days);
+        return;
+    }
+    set_limits(&mut life, &args);
+    if args.is_present("explain") {
+        explain(&mut life, &args);
+    }
+    if args.is_present("profile") {
+        eprintln!("day,black_tiles,candidates,flips,elapsed_us");
+        life.on_profile(|profile| {
+            eprintln!(
+                "{},{},{},{},{}",
+                profile.generation,
+                profile.black_tiles,
+                profile.candidates,
+                profile.flips,
+                profile.elapsed.as_micros()
+            );
+            black_counts.borrow_mut().push(
+                u32::try_from(profile.black_tiles).expect("Failed to store tile count in u32"),
+            );
+        });
+    }
+    if let Some(runs) = args.value_of("repeat") {
+        time_runs(&life, days, runs);
+    }
+    let run_scope = common::scope!("run");
+    let shortcut = life.run_with_shortcut(days).unwrap_or_else(|error| {
+        eprintln!("After {} days: {}", life.get_generation(), error);
+        process::exit(1);
+    });
+    drop(run_scope);
+    if let Some(shortcut) = shortcut {
+        println!("{shortcut}");
+    }
+
+    println!(
+        "After {} days, {} tiles are black",
+        days,
+        life.black_count()
+    );
 
-    let days = 100;
-    for _day in 1..=days {
-        evolve_tiles(&mut black_tiles);
+    if args.is_present("profile") {
+        let black_counts = black_counts.borrow();
+        eprintln!(
+            "Black tiles over {} days: {}",
+            black_counts.len(),
+            sparkline(&black_counts)
+        );
     }
 
-    println!("After {} days, {} tiles are black", days, black_tiles.len());
+    write_final_state(&args, &life, &rule);
+    print_timing(&args);
 }
 
-#[cfg(test)]
-mod tests {
-    // use test::Bencher;
+fn print_timing(args: &ArgMatches) {
+    if args.is_present("timing") {
+        eprintln!("Allocator: {}", alloc::NAME);
+        eprint!("{}", timing::report());
+    }
 }
@@ -0,0 +1,87 @@
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(clippy::missing_panics_doc)]
+
+use common::Solution;
+
+struct Transformer {
+    subject: u64,
+    value: u64,
+    loop_count: u32,
+}
+
+impl Transformer {
+    fn new(subject: u64) -> Self {
+        Self {
+            subject,
+            value: 1,
+            loop_count: 0,
+        }
+    }
+
+    fn run_loop(&mut self) {
+        self.value *= self.subject;
+        self.value %= 20_201_227;
+        self.loop_count += 1;
+    }
+
+    fn get_value(&self) -> u64 {
+        self.value
+    }
+
+    fn get_loop_count(&self) -> u32 {
+        self.loop_count
+    }
+}
+
+#[must_use]
+pub fn find_encryption_key(card_public_key: u64, room_public_key: u64) -> u64 {
+    let mut card_transformer = Transformer::new(7);
+    let mut room_transformer = Transformer::new(7);
+
+    loop {
+        card_transformer.run_loop();
+        room_transformer.run_loop();
+
+        if card_transformer.get_value() == card_public_key {
+            let mut key_transformer = Transformer::new(room_public_key);
+            for _ in 0..card_transformer.get_loop_count() {
+                key_transformer.run_loop();
+            }
+            return key_transformer.get_value();
+        }
+        if room_transformer.get_value() == room_public_key {
+            let mut key_transformer = Transformer::new(card_public_key);
+            for _ in 0..room_transformer.get_loop_count() {
+                key_transformer.run_loop();
+            }
+            return key_transformer.get_value();
+        }
+    }
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    // The card's and the room's public keys, one per line
+    fn part1(&self, input: &str) -> String {
+        let keys: Vec<u64> = input
+            .split_whitespace()
+            .map(|key| {
+                key.parse()
+                    .unwrap_or_else(|_| panic!("Failed to parse public key {}", key))
+            })
+            .collect();
+        assert_eq!(keys.len(), 2, "Expected two public keys");
+        find_encryption_key(keys[0], keys[1]).to_string()
+    }
+
+    // The last day has no second puzzle
+    fn part2(&self, _input: &str) -> String {
+        String::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // use test::Bencher;
+}